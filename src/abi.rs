@@ -0,0 +1,245 @@
+// abi.rs
+// Versioned, C-compatible views of the list types for prebuilt plugins.
+//
+// The native types are free to grow fields between releases. The `V1` structs below are
+// frozen: their fields, order and meaning never change, and a later incompatible layout
+// gets a new `V2` struct alongside them. The const assertions at the bottom tie the native
+// node's layout to `RustyListNodeV1`, so a field reorder fails the build instead of
+// silently breaking plugins.
+use core::mem::{align_of, offset_of, size_of};
+use core::ptr::{self, NonNull};
+use crate::{RustyList, RustyListNode};
+
+/// The version of the structs in this module, stored in `RustyListV1::abi_version`.
+pub const RUSTY_LIST_ABI_VERSION: u32 = 1;
+
+/// The C view of an embedded `RustyListNode`, version 1.
+///
+/// A native node can be read in place through this struct, see `RustyListNode::as_abi_v1`.
+/// Its leading fields are exactly these; native nodes may carry more after them, so
+/// plugins must never copy nodes by value or assume `sizeof` of the native node.
+///
+/// ```c
+/// struct rusty_list_node_v1 {
+///     uint8_t dynamic;
+///     uint8_t linked;
+///     struct rusty_list_node_v1 *prev;
+///     struct rusty_list_node_v1 *next;
+/// };
+/// ```
+#[repr(C)]
+#[derive(Debug)]
+pub struct RustyListNodeV1 {
+    /// 1 if the element was created as dynamic, otherwise 0.
+    pub dynamic: u8,
+    /// 1 while the node is in a list, otherwise 0. Only 0 and 1 may be written.
+    pub linked: u8,
+    /// The previous node, or null at the head.
+    pub prev: *mut RustyListNodeV1,
+    /// The next node, or null at the tail.
+    pub next: *mut RustyListNodeV1,
+}
+
+/// The C view of a `RustyList` header, version 1.
+///
+/// Unlike a node, the native list header is not shared in place: `RustyList::to_abi_v1`
+/// copies it into this struct, and `RustyList::apply_abi_v1` copies the links back after a
+/// plugin has changed them. The element holding a node is `node - node_offset`.
+///
+/// ```c
+/// struct rusty_list_v1 {
+///     uint32_t abi_version;
+///     uint8_t dynamic;
+///     size_t len;
+///     size_t node_offset;
+///     struct rusty_list_node_v1 *head;
+///     struct rusty_list_node_v1 *tail;
+/// };
+/// ```
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct RustyListV1 {
+    /// Always `RUSTY_LIST_ABI_VERSION` (1) for this struct; checked by `apply_abi_v1`.
+    pub abi_version: u32,
+    /// 1 if the list is dynamic, otherwise 0.
+    pub dynamic: u8,
+    /// Number of elements.
+    pub len: usize,
+    /// Offset in bytes of the node inside each element.
+    pub node_offset: usize,
+    /// The first node, or null if the list is empty.
+    pub head: *mut RustyListNodeV1,
+    /// The last node, or null if the list is empty.
+    pub tail: *mut RustyListNodeV1,
+}
+
+/// Returned by `RustyList::apply_abi_v1` when the header can't describe this list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AbiMismatch {
+    /// `abi_version` isn't 1.
+    Version(u32),
+    /// `node_offset` differs from the list's node offset.
+    NodeOffset,
+    /// Exactly one of `head` and `tail` is null.
+    Header,
+}
+
+impl core::fmt::Display for AbiMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AbiMismatch::Version(version) => write!(f, "unsupported list ABI version {version}"),
+            AbiMismatch::NodeOffset => f.write_str("list ABI header has a different node offset"),
+            AbiMismatch::Header => f.write_str("list ABI header has only one of head and tail"),
+        }
+    }
+}
+
+impl<T> RustyListNode<T> {
+    /// Views the node as its version 1 C struct, to hand to a plugin.
+    pub fn as_abi_v1(&mut self) -> *mut RustyListNodeV1 {
+        (self as *mut Self).cast()
+    }
+
+    /// Converts a node pointer received from a plugin back to the native node.
+    ///
+    /// # Safety
+    /// `node` must have come from `as_abi_v1` or a `RustyListV1` of a list of `T`.
+    pub unsafe fn from_abi_v1(node: *mut RustyListNodeV1) -> *mut Self {
+        node.cast()
+    }
+}
+
+impl<T> RustyList<T> {
+    /// Copies the list header into its version 1 C struct.
+    pub fn to_abi_v1(&self) -> RustyListV1 {
+        let node = |link: Option<NonNull<RustyListNode<T>>>| link.map_or(ptr::null_mut(), |n| n.as_ptr().cast());
+        RustyListV1 {
+            abi_version: RUSTY_LIST_ABI_VERSION,
+            dynamic: self.dynamic as u8,
+            len: self.len,
+            node_offset: self.offset,
+            head: node(self.head),
+            tail: node(self.tail),
+        }
+    }
+
+    /// Takes over `head`, `tail` and `len` from a header a plugin has changed the list
+    /// through.
+    ///
+    /// The header is checked first, and the list is left alone if it doesn't match.
+    ///
+    /// # Safety
+    /// The header must describe a well-formed list of `T` elements, with `len` nodes
+    /// linked in both directions between `head` and `tail`. Attached cursors, watermarks
+    /// and the reposition hook are not told about what the plugin did, so the plugin must
+    /// not have unlinked an element a cursor was on.
+    pub unsafe fn apply_abi_v1(&mut self, header: &RustyListV1) -> Result<(), AbiMismatch> {
+        self.assert_not_traversing("apply_abi_v1");
+        if header.abi_version != RUSTY_LIST_ABI_VERSION {
+            return Err(AbiMismatch::Version(header.abi_version));
+        }
+        if header.node_offset != self.offset {
+            return Err(AbiMismatch::NodeOffset);
+        }
+        if header.head.is_null() != header.tail.is_null() {
+            return Err(AbiMismatch::Header);
+        }
+
+        let before = self.len;
+        self.head = NonNull::new(header.head.cast());
+        self.tail = NonNull::new(header.tail.cast());
+        self.len = header.len;
+        #[cfg(feature = "replay")]
+        self.record_rebuild();
+        self.notify_watermarks(before);
+        Ok(())
+    }
+}
+
+// The native node must start with the `RustyListNodeV1` fields, in the same places.
+const _: () = {
+    type Native = RustyListNode<u8>;
+    assert!(offset_of!(Native, dynamic) == offset_of!(RustyListNodeV1, dynamic));
+    assert!(offset_of!(Native, linked) == offset_of!(RustyListNodeV1, linked));
+    assert!(offset_of!(Native, prev) == offset_of!(RustyListNodeV1, prev));
+    assert!(offset_of!(Native, next) == offset_of!(RustyListNodeV1, next));
+    assert!(size_of::<bool>() == size_of::<u8>());
+    assert!(size_of::<Option<NonNull<Native>>>() == size_of::<*mut RustyListNodeV1>());
+    assert!(size_of::<Native>() >= size_of::<RustyListNodeV1>());
+    assert!(align_of::<Native>() >= align_of::<RustyListNodeV1>());
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HasRustyNode, rusty_offset, rusty_container_of};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn plugin_walks_and_relinks_through_the_v1_view() {
+        let mut items = [make_item(1), make_item(2), make_item(3)];
+        let mut list = RustyList::<TestItem>::new();
+        items.iter_mut().for_each(|item| list.push(item));
+
+        let mut header = list.to_abi_v1();
+        assert_eq!((header.abi_version, header.len, header.node_offset), (1, 3, list.offset));
+
+        // what a C plugin would do: walk, then unlink the head by hand
+        let mut values = [0; 3];
+        let mut node = header.head;
+        for value in values.iter_mut() {
+            let item = unsafe { rusty_container_of(RustyListNode::<TestItem>::from_abi_v1(node), header.node_offset) };
+            *value = unsafe { (*item).value };
+            node = unsafe { (*node).next };
+        }
+        assert_eq!(values, [1, 2, 3]);
+        assert!(node.is_null());
+
+        unsafe {
+            let old = header.head;
+            header.head = (*old).next;
+            (*header.head).prev = core::ptr::null_mut();
+            (*old).next = core::ptr::null_mut();
+            (*old).linked = 0;
+        }
+        header.len -= 1;
+        unsafe { list.apply_abi_v1(&header) }.unwrap();
+
+        assert_eq!(list.iter().map(|item| item.value).sum::<i32>(), 5);
+        assert_eq!(list.len, 2);
+        assert!(!items[0].node.is_linked());
+    }
+
+    #[test]
+    fn mismatched_headers_are_rejected() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut header = list.to_abi_v1();
+        header.abi_version = 2;
+        assert_eq!(unsafe { list.apply_abi_v1(&header) }, Err(AbiMismatch::Version(2)));
+
+        let mut item = make_item(1);
+        header = list.to_abi_v1();
+        header.head = item.node.as_abi_v1();
+        assert_eq!(unsafe { list.apply_abi_v1(&header) }, Err(AbiMismatch::Header));
+        assert!(list.head.is_none());
+    }
+}
@@ -0,0 +1,126 @@
+// compat.rs
+// Interop with `intrusive_collections::LinkedList` (feature `intrusive-collections`).
+//
+// An element can embed both a `RustyListNode` and a `LinkedListLink` and be moved between
+// the two containers while a codebase migrates. The `intrusive_collections` side uses
+// `UnsafeRef` pointers, which like `RustyList` neither own nor free the elements.
+use crate::RustyList;
+use intrusive_collections::linked_list::LinkedListOps;
+use intrusive_collections::{Adapter, DefaultPointerOps};
+
+pub use intrusive_collections::{intrusive_adapter, LinkedList, LinkedListLink, UnsafeRef};
+
+/// Declares an `intrusive_collections` adapter over `UnsafeRef<$ty>` for the
+/// `LinkedListLink` field `$field`, ready for the `drain_into_*` helpers, e.g.
+/// `rusty_compat_adapter!(pub ItemAdapter = Item { link });`.
+#[macro_export]
+macro_rules! rusty_compat_adapter {
+    ($vis:vis $name:ident = $ty:path { $field:ident }) => {
+        $crate::compat::intrusive_adapter!(
+            $vis $name = $crate::compat::UnsafeRef<$ty>: $ty { $field: $crate::compat::LinkedListLink }
+        );
+    };
+}
+
+/// Moves every element of `from` to the tail of `to`, front to back.
+///
+/// Elements are appended with `push`, keeping their order; call `sort` afterwards if `to`
+/// should be ordered by its `order_function`.
+///
+/// # Returns
+/// The number of elements moved.
+///
+/// # Safety
+/// Every element of `from` must be valid for as long as it stays in `to`, and nothing
+/// else may access an element through another `UnsafeRef` while it is in `to`.
+pub unsafe fn drain_into_rusty<T, A>(from: &mut LinkedList<A>, to: &mut RustyList<T>) -> usize
+where
+    A: Adapter<PointerOps = DefaultPointerOps<UnsafeRef<T>>>,
+    A::LinkOps: LinkedListOps,
+{
+    let mut moved = 0;
+    while let Some(ptr) = from.pop_front() {
+        to.push(unsafe { &mut *UnsafeRef::into_raw(ptr) });
+        moved += 1;
+    }
+    moved
+}
+
+/// Moves every element of `from` to the back of `to`, head to tail.
+///
+/// # Returns
+/// The number of elements moved.
+///
+/// # Safety
+/// Every element of `from` must be valid for as long as it stays in `to`. Panics (from
+/// `intrusive_collections`) if an element's `LinkedListLink` is already linked.
+pub unsafe fn drain_into_intrusive<T, A>(from: &mut RustyList<T>, to: &mut LinkedList<A>) -> usize
+where
+    A: Adapter<PointerOps = DefaultPointerOps<UnsafeRef<T>>>,
+    A::LinkOps: LinkedListOps,
+{
+    let mut moved = 0;
+    while let Some(item) = from.pop() {
+        to.push_back(unsafe { UnsafeRef::from_raw(item) });
+        moved += 1;
+    }
+    moved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+    use crate::{RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+        pub link: LinkedListLink,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    rusty_compat_adapter!(TestAdapter = TestItem { link });
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+            link: LinkedListLink::new(),
+        }
+    }
+
+    #[test]
+    fn drain_round_trip_keeps_order() {
+        let mut items = [make_item(1), make_item(2), make_item(3)];
+        let mut rusty = RustyList::<TestItem>::new();
+        for item in items.iter_mut() {
+            rusty.push(item);
+        }
+
+        let mut theirs = LinkedList::new(TestAdapter::new());
+        assert_eq!(unsafe { drain_into_intrusive(&mut rusty, &mut theirs) }, 3);
+        assert_eq!(rusty.len, 0);
+        assert!(!items[0].node.is_linked());
+
+        let values: Vec<i32> = theirs.iter().map(|item| item.value).collect();
+        assert_eq!(values, [1, 2, 3]);
+
+        assert_eq!(unsafe { drain_into_rusty(&mut theirs, &mut rusty) }, 3);
+        assert!(theirs.is_empty());
+        assert!(!items[2].link.is_linked());
+
+        let mut values = Vec::new();
+        while let Some(item) = rusty.pop() {
+            values.push(unsafe { (*item).value });
+        }
+        assert_eq!(values, [1, 2, 3]);
+    }
+}
@@ -1,7 +1,7 @@
 // core_types.rs
 // This file contains the core types and traits used in the RustyList library.
 use core::marker::PhantomData;
-use core::mem::MaybeUninit;
+use core::mem::{MaybeUninit, size_of};
 use core::ptr::NonNull;
 
 
@@ -9,13 +9,23 @@ use core::ptr::NonNull;
 /// A node that gets embedded inside a struct to make it linkable in a RustyList.
 ///
 /// This is like `struct list_head` in Linux — it doesn’t own data, it just connects items.
+///
+/// The fields are private so the links can only be changed by the list operations.
+/// Use `new()`/`new_const()` to create a node and the accessors to inspect it.
 #[repr(C)]
 #[derive(Debug, PartialEq)]
 pub struct RustyListNode<T> {
-    pub dynamic: bool,
-    pub _marker: PhantomData<T>,
-    pub prev: Option<NonNull<RustyListNode<T>>>,
-    pub next: Option<NonNull<RustyListNode<T>>>,
+    pub(crate) dynamic: bool,
+    pub(crate) linked: bool,
+    pub(crate) _marker: PhantomData<T>,
+    pub(crate) prev: Option<NonNull<RustyListNode<T>>>,
+    pub(crate) next: Option<NonNull<RustyListNode<T>>>,
+    /// Sequence stamp recorded when the node was last pushed or inserted.
+    #[cfg(feature = "age-stamps")]
+    pub(crate) stamp: u64,
+    /// Bumped each time the node is newly linked, to catch stale `NodeRef`s.
+    #[cfg(debug_assertions)]
+    pub(crate) generation: u32,
 }
 
 /// A doubly linked intrusive list.
@@ -34,6 +44,34 @@ pub struct RustyList<T> {
     /// Optional sort/comparison function.
     /// Like in C: returns `< 0`, `0`, or `> 0` for ordering two items.
     pub order_function: Option<fn(*const T, *const T) -> i32>,
+
+    /// Attached `PersistentCursor`s, linked through the cursors themselves.
+    #[cfg(feature = "persistent-cursors")]
+    pub(crate) cursors: Option<NonNull<crate::list_ops::persistent_cursor::PersistentCursor<T>>>,
+
+    /// Length thresholds and callback set by `set_watermarks`.
+    #[cfg(feature = "watermarks")]
+    pub(crate) watermarks: Option<crate::list_ops::watermarks::Watermarks>,
+
+    /// Callback set by `set_reposition_hook`, run when an element changes place.
+    #[cfg(feature = "reposition-hook")]
+    pub(crate) reposition_hook: Option<crate::list_ops::reposition_hook::RepositionHook<T>>,
+
+    /// Stamp given to the next pushed or inserted node.
+    #[cfg(feature = "age-stamps")]
+    pub(crate) next_stamp: u64,
+
+    /// Scan-length histograms, updated from `&self` lookups too.
+    #[cfg(feature = "metrics")]
+    pub(crate) stats: core::cell::Cell<crate::list_ops::metrics::ScanStats>,
+
+    /// Log set by `record_into`, receiving every structural change.
+    #[cfg(feature = "replay")]
+    pub(crate) replay: Option<crate::list_ops::replay::Recorder<T>>,
+
+    /// Number of active traversals; structural operations panic while it is non-zero.
+    #[cfg(feature = "borrow-check")]
+    pub(crate) borrow_flag: core::cell::Cell<usize>,
 }
 
 /// Trait that must be implemented by any struct that embeds a `RustyListNode<T>`.
@@ -47,6 +85,12 @@ pub trait HasRustyNode {
 /// Generic offsetof-style function.
 /// Gets the offset of a field inside a struct at compile time.
 /// Similar to `offsetof(T, field)` in C.
+///
+/// Works for any position of the field, including first and last, and for over-aligned
+/// (`#[repr(align(N))]`) containers and zero-sized payloads alongside the field.
+///
+/// # Panics
+/// If `field` returns a reference to something outside the struct.
 #[inline(always)]
 pub fn rusty_offset<T, F>(field: fn(&T) -> &F) -> usize {
     let uninit = MaybeUninit::<T>::uninit();
@@ -55,17 +99,101 @@ pub fn rusty_offset<T, F>(field: fn(&T) -> &F) -> usize {
     // SAFETY: We are not reading the memory, just getting the address of a field.
     let field_ptr = field(unsafe { &*uninit.as_ptr() }) as *const F as *const u8;
 
-    field_ptr as usize - base as usize
+    let offset = field_ptr.addr().wrapping_sub(base.addr());
+    assert!(
+        field_ptr.addr() >= base.addr() && offset + size_of::<F>() <= size_of::<T>(),
+        "rusty_offset: the field is not inside the struct",
+    );
+    offset
+}
+
+/// Checks at compile time that an element type's layout suits the list's offset math.
+///
+/// `assert_rusty_layout!(Item, link)` names the node field; `assert_rusty_layout!(Item)`
+/// uses a field called `node`, as in the examples. Compilation fails unless:
+/// - the field is exactly a `RustyListNode<Item>`, so the offset can only resolve to that
+///   one node and not to a node of another element type or one nested in a wrapper;
+/// - the node lies wholly inside `Item` at an offset aligned for `RustyListNode`;
+/// - `Item` is aligned at least as strictly as its node, so every element address yields
+///   an aligned node.
+///
+/// Whether the type is `#[repr(C)]` can't be observed from a `const`, so keep the
+/// attribute; these are the properties it is there to guarantee.
+#[macro_export]
+macro_rules! assert_rusty_layout {
+    ($ty:ty) => {
+        $crate::assert_rusty_layout!($ty, node);
+    };
+    ($ty:ty, $field:ident) => {
+        const _: () = {
+            let _: fn(&$ty) -> &$crate::RustyListNode<$ty> = |item| &item.$field;
+
+            let offset = ::core::mem::offset_of!($ty, $field);
+            let node_size = ::core::mem::size_of::<$crate::RustyListNode<$ty>>();
+            let node_align = ::core::mem::align_of::<$crate::RustyListNode<$ty>>();
+            assert!(
+                offset % node_align == 0,
+                concat!("assert_rusty_layout: ", stringify!($ty), ".", stringify!($field), " is misaligned"),
+            );
+            assert!(
+                offset + node_size <= ::core::mem::size_of::<$ty>(),
+                concat!("assert_rusty_layout: ", stringify!($ty), ".", stringify!($field), " extends past the element"),
+            );
+            assert!(
+                ::core::mem::align_of::<$ty>() >= node_align,
+                concat!("assert_rusty_layout: ", stringify!($ty), " is less aligned than its node"),
+            );
+        };
+    };
+}
+
+/// Walks a `RustyList` head to tail, reading each element's successor before running the
+/// body, so the body may unlink (and then free or move) the current element.
+///
+/// The Rust counterpart of Linux's `list_for_each_entry_safe`. `item` is bound to a
+/// `*mut T` for each element in turn, and `list` stays usable inside the body:
+///
+/// ```ignore
+/// rusty_for_each_safe!(task in run_queue => {
+///     if unsafe { (*task).finished } {
+///         run_queue.remove(unsafe { &mut *task });
+///         free_task(task);
+///     }
+/// });
+/// ```
+///
+/// Only the current element may be unlinked. Removing any other element, in particular
+/// the next one, leaves the walk holding a stale successor, exactly as in C. Elements
+/// linked in during the walk may or may not be visited.
+#[macro_export]
+macro_rules! rusty_for_each_safe {
+    ($item:ident in $list:expr => $body:block) => {{
+        let offset = $list.offset;
+        let mut next = $list.head;
+        while let ::core::option::Option::Some(node) = next {
+            // SAFETY: `node` is linked into the list; the body hasn't run for it yet
+            next = unsafe { (*node.as_ptr()).next_node() };
+            let $item = unsafe { $crate::rusty_container_of_mut(node.as_ptr(), offset) };
+            $body
+        }
+    }};
 }
 
 /// SAFELY go from a pointer to the embedded node to a pointer to the container `T`.
 /// This is like `container_of()` in C.
+///
+/// # Safety
+/// - `node` must point to a `RustyListNode<T>` embedded in a `T`.
+/// - `offset` must be the offset of that node inside `T` (see `rusty_offset`).
 #[inline(always)]
 pub unsafe fn rusty_container_of<T>(node: *const RustyListNode<T>, offset: usize) -> *const T {
     unsafe { (node as *const u8).sub(offset) as *const T }
 }
 
 /// Mutable version of `rusty_container_of`.
+///
+/// # Safety
+/// Same requirements as `rusty_container_of`.
 #[inline(always)]
 pub unsafe fn rusty_container_of_mut<T>(node: *mut RustyListNode<T>, offset: usize) -> *mut T {
     unsafe { (node as *mut u8).sub(offset) as *mut T }
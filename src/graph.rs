@@ -0,0 +1,171 @@
+// graph.rs
+// Intrusive adjacency lists for directed graphs.
+//
+// Each edge embeds two `RustyListNode`s: one links it into the outgoing list of its
+// source vertex, the other into the incoming list of its target. The two lists of a
+// vertex use different node offsets, which is what lets one edge sit on two lists at once.
+use crate::{RustyList, RustyListNode, rusty_offset};
+
+/// Implemented by edge types: names the two nodes an edge embeds.
+///
+/// Like the closure passed to `rusty_offset`, each method must do nothing but return a
+/// reference to the field: it is also called on uninitialized memory to find the offset.
+///
+/// ```ignore
+/// #[repr(C)]
+/// struct Wire {
+///     net: u32,
+///     out_node: RustyListNode<Wire>,
+///     in_node: RustyListNode<Wire>,
+/// }
+///
+/// impl GraphEdge for Wire {
+///     fn out_node(&self) -> &RustyListNode<Self> { &self.out_node }
+///     fn in_node(&self) -> &RustyListNode<Self> { &self.in_node }
+/// }
+/// ```
+pub trait GraphEdge: Sized {
+    /// The node linking the edge into its source's outgoing list.
+    fn out_node(&self) -> &RustyListNode<Self>;
+    /// The node linking the edge into its target's incoming list.
+    fn in_node(&self) -> &RustyListNode<Self>;
+}
+
+/// The edge lists a vertex embeds: edges leaving it and edges arriving at it.
+pub struct EdgeLists<E> {
+    /// Edges whose source is this vertex, in link order.
+    pub outgoing: RustyList<E>,
+    /// Edges whose target is this vertex, in link order.
+    pub incoming: RustyList<E>,
+}
+
+impl<E: GraphEdge> EdgeLists<E> {
+    /// Creates the empty edge lists of a vertex.
+    pub fn new() -> Self {
+        Self {
+            outgoing: RustyList::with_offset(rusty_offset(E::out_node)),
+            incoming: RustyList::with_offset(rusty_offset(E::in_node)),
+        }
+    }
+}
+
+impl<E: GraphEdge> Default for EdgeLists<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E> EdgeLists<E> {
+    /// Number of edges touching the vertex; a self-loop counts twice.
+    pub fn degree(&self) -> usize {
+        self.outgoing.len + self.incoming.len
+    }
+}
+
+/// Links `edge` from the vertex owning `outgoing` to the vertex owning `incoming`,
+/// appending it to both lists.
+///
+/// Taking the two lists rather than the two vertices allows self-loops:
+/// `link_edge(&mut v.outgoing, &mut v.incoming, edge)`. An edge that is already linked is
+/// left alone, with a warning.
+pub fn link_edge<E: GraphEdge>(outgoing: &mut RustyList<E>, incoming: &mut RustyList<E>, edge: &mut E) {
+    debug_assert_eq!(outgoing.offset, rusty_offset(E::out_node), "link_edge: not an outgoing list");
+    debug_assert_eq!(incoming.offset, rusty_offset(E::in_node), "link_edge: not an incoming list");
+    if edge.out_node().linked || edge.in_node().linked {
+        rusty_warn!("graph::link_edge: edge is already linked, ignoring");
+        return;
+    }
+
+    outgoing.push(edge);
+    incoming.push(edge);
+}
+
+/// Unlinks `edge` from both of its vertices' lists, which must be the ones it was linked
+/// into. Does nothing if the edge isn't linked.
+pub fn unlink_edge<E: GraphEdge>(outgoing: &mut RustyList<E>, incoming: &mut RustyList<E>, edge: &mut E) {
+    debug_assert_eq!(
+        edge.out_node().linked,
+        edge.in_node().linked,
+        "unlink_edge: edge is linked on one side only"
+    );
+    if !edge.out_node().linked {
+        return;
+    }
+    debug_assert!(
+        outgoing.find_item(edge).is_some() && incoming.find_item(edge).is_some(),
+        "unlink_edge: edge is not on these lists"
+    );
+
+    outgoing.remove(edge);
+    incoming.remove(edge);
+}
+
+/// Iterates over every edge touching the vertex: outgoing edges first, then incoming
+/// ones. A self-loop is visited twice.
+pub fn edges_of<E>(vertex: &EdgeLists<E>) -> impl Iterator<Item = &E> {
+    vertex.outgoing.iter().chain(vertex.incoming.iter())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+    use super::*;
+
+    #[repr(C)]
+    struct Wire {
+        pub net: u32,
+        pub out_node: RustyListNode<Wire>,
+        pub in_node: RustyListNode<Wire>,
+    }
+
+    impl GraphEdge for Wire {
+        fn out_node(&self) -> &RustyListNode<Self> {
+            &self.out_node
+        }
+        fn in_node(&self) -> &RustyListNode<Self> {
+            &self.in_node
+        }
+    }
+
+    fn wire(net: u32) -> Wire {
+        Wire {
+            net,
+            out_node: RustyListNode::new(),
+            in_node: RustyListNode::new(),
+        }
+    }
+
+    fn nets<'a>(edges: impl Iterator<Item = &'a Wire>) -> Vec<u32> {
+        edges.map(|w| w.net).collect()
+    }
+
+    #[test]
+    fn edges_link_and_unlink_on_both_endpoints() {
+        let mut a = EdgeLists::<Wire>::new();
+        let mut b = EdgeLists::<Wire>::new();
+        let mut c = EdgeLists::<Wire>::new();
+        let mut wires: Vec<Wire> = (1..=4).map(wire).collect();
+        let [ab, ac, cb, aa] = wires.as_mut_slice() else { unreachable!() };
+
+        link_edge(&mut a.outgoing, &mut b.incoming, ab);
+        link_edge(&mut a.outgoing, &mut c.incoming, ac);
+        link_edge(&mut c.outgoing, &mut b.incoming, cb);
+        link_edge(&mut a.outgoing, &mut a.incoming, aa);
+        // already linked: ignored
+        link_edge(&mut c.outgoing, &mut a.incoming, ab);
+
+        assert_eq!(nets(edges_of(&a)), [1, 2, 4, 4]);
+        assert_eq!(nets(edges_of(&b)), [1, 3]);
+        assert_eq!((a.degree(), b.degree(), c.degree()), (4, 2, 2));
+
+        unlink_edge(&mut a.outgoing, &mut b.incoming, ab);
+        unlink_edge(&mut a.outgoing, &mut a.incoming, aa);
+        assert_eq!(nets(edges_of(&a)), [2]);
+        assert_eq!(nets(b.incoming.iter()), [3]);
+        assert!(!ab.out_node.is_linked() && !ab.in_node.is_linked());
+
+        // unlinked edges are ignored
+        unlink_edge(&mut a.outgoing, &mut b.incoming, ab);
+        assert_eq!(b.degree(), 1);
+    }
+}
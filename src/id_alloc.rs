@@ -0,0 +1,261 @@
+// id_alloc.rs
+// A lowest-first ID allocator whose free IDs are kept as ranges on a sorted list.
+//
+// Each free range is an `IdRange` element on a list sorted by start. Freeing an ID looks
+// at its two neighbours on the list and grows or joins them, so the list stays as short
+// as the number of gaps. Range elements are caller-provided storage: the allocator takes
+// one from its spare list when a freed ID starts a new range, and puts one back when a
+// range is used up or two ranges merge.
+use core::fmt;
+use core::ops::Range;
+use crate::{HasRustyNode, RustyList, RustyListNode, rusty_container_of_mut, rusty_offset};
+
+/// A range of free IDs, `start..end`; also the storage the allocator keeps ranges in.
+#[repr(C)]
+#[derive(Debug)]
+pub struct IdRange {
+    start: usize,
+    end: usize,
+    node: RustyListNode<IdRange>,
+}
+
+impl IdRange {
+    /// Creates an empty range element, to hand to `IdAllocator::add_storage`.
+    pub const fn new() -> Self {
+        Self { start: 0, end: 0, node: RustyListNode::new_const() }
+    }
+
+    /// The free IDs this element currently describes.
+    pub fn ids(&self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+impl Default for IdRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HasRustyNode for IdRange {
+    fn rusty_offset() -> usize {
+        rusty_offset(|x: &Self| &x.node)
+    }
+}
+
+fn by_start(a: *const IdRange, b: *const IdRange) -> i32 {
+    unsafe { (*a).start.cmp(&(*b).start) as i32 }
+}
+
+/// Returned by `IdAllocator::free_id`/`free_range`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdError {
+    /// Some of the IDs are already free.
+    AlreadyFree,
+    /// The IDs start a new range and there is no spare `IdRange` to hold it.
+    NoStorage,
+    /// The range is empty.
+    Empty,
+}
+
+impl fmt::Display for IdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdError::AlreadyFree => f.write_str("ID is already free"),
+            IdError::NoStorage => f.write_str("no spare IdRange to record the freed IDs"),
+            IdError::Empty => f.write_str("empty ID range"),
+        }
+    }
+}
+
+/// Hands out the lowest free ID and takes IDs back, coalescing adjacent free ranges.
+///
+/// Starts with no free IDs and no storage: give it `IdRange` elements with `add_storage`
+/// and free the initial pool with `free_range`. A pool that is handed out and returned in
+/// any order needs at most one range element per gap between allocated IDs, plus one.
+///
+/// ```ignore
+/// static mut RANGES: [IdRange; 8] = [const { IdRange::new() }; 8];
+///
+/// let mut pids = IdAllocator::new();
+/// for range in unsafe { &mut *addr_of_mut!(RANGES) } {
+///     pids.add_storage(range);
+/// }
+/// pids.free_range(1..32768)?;
+/// let pid = pids.alloc_id().unwrap();
+/// pids.free_id(pid)?;
+/// ```
+pub struct IdAllocator {
+    free: RustyList<IdRange>,
+    spare: RustyList<IdRange>,
+}
+
+impl IdAllocator {
+    /// Creates an allocator with no free IDs and no storage.
+    pub fn new() -> Self {
+        Self {
+            free: RustyList::new_with_order(by_start),
+            spare: RustyList::new(),
+        }
+    }
+
+    /// Gives the allocator an `IdRange` element to record free ranges in.
+    ///
+    /// The element must stay valid for as long as the allocator is used.
+    pub fn add_storage(&mut self, range: &mut IdRange) {
+        self.spare.push(range);
+    }
+
+    /// Number of spare `IdRange` elements.
+    pub fn spare_storage(&self) -> usize {
+        self.spare.len
+    }
+
+    /// Number of free ranges, i.e. `IdRange` elements in use.
+    pub fn free_ranges(&self) -> usize {
+        self.free.len
+    }
+
+    /// Allocates the lowest free ID, or returns `None` if there is none.
+    pub fn alloc_id(&mut self) -> Option<usize> {
+        let lowest = unsafe { &mut *self.free.peek()? };
+        let id = lowest.start;
+        lowest.start += 1;
+
+        if lowest.start == lowest.end {
+            self.free.pop();
+            self.spare.push(lowest);
+        }
+        Some(id)
+    }
+
+    /// Returns `true` if `id` is free.
+    pub fn is_free(&self, id: usize) -> bool {
+        self.free.any(|range| range.ids().contains(&id))
+    }
+
+    /// Frees `id`. Same as `free_range(id..id + 1)`.
+    pub fn free_id(&mut self, id: usize) -> Result<(), IdError> {
+        self.free_range(id..id.saturating_add(1))
+    }
+
+    /// Frees every ID in `ids`, merging them with the free ranges right before and after.
+    ///
+    /// Fails, changing nothing, if any of the IDs is already free, or if they touch no
+    /// free range and there is no spare `IdRange` to record them in.
+    pub fn free_range(&mut self, ids: Range<usize>) -> Result<(), IdError> {
+        if ids.is_empty() {
+            return Err(IdError::Empty);
+        }
+
+        // the neighbours: the last range starting at or before `ids`, and the one after it
+        let offset = self.free.offset;
+        let range_of = |node: core::ptr::NonNull<RustyListNode<IdRange>>| unsafe {
+            rusty_container_of_mut(node.as_ptr(), offset)
+        };
+        let mut prev: Option<*mut IdRange> = None;
+        let mut next = self.free.head;
+        while let Some(node) = next {
+            if unsafe { (*range_of(node)).start } > ids.start {
+                break;
+            }
+            prev = Some(range_of(node));
+            next = unsafe { (*node.as_ptr()).next_node() };
+        }
+        let next = next.map(range_of);
+
+        let prev = prev.map(|range| unsafe { &mut *range });
+        let next = next.map(|range| unsafe { &mut *range });
+        if prev.as_ref().is_some_and(|p| p.end > ids.start) || next.as_ref().is_some_and(|n| n.start < ids.end) {
+            return Err(IdError::AlreadyFree);
+        }
+
+        match (prev.filter(|p| p.end == ids.start), next.filter(|n| n.start == ids.end)) {
+            (Some(prev), Some(next)) => {
+                prev.end = next.end;
+                self.free.remove(next);
+                self.spare.push(next);
+            }
+            (Some(prev), None) => prev.end = ids.end,
+            (None, Some(next)) => next.start = ids.start,
+            (None, None) => {
+                let range = unsafe { &mut *self.spare.pop().ok_or(IdError::NoStorage)? };
+                range.start = ids.start;
+                range.end = ids.end;
+                self.free.insert(range);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for IdAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+    use super::*;
+
+    fn ranges(ids: &IdAllocator) -> Vec<(usize, usize)> {
+        ids.free.iter().map(|range| (range.start, range.end)).collect()
+    }
+
+    #[test]
+    fn allocates_lowest_first_and_coalesces_on_free() {
+        let mut storage = [IdRange::new(), IdRange::new()];
+        let mut ids = IdAllocator::new();
+        storage.iter_mut().for_each(|range| ids.add_storage(range));
+        ids.free_range(0..8).unwrap();
+
+        assert_eq!([ids.alloc_id(), ids.alloc_id(), ids.alloc_id()], [Some(0), Some(1), Some(2)]);
+        assert_eq!(ranges(&ids), [(3, 8)]);
+
+        // 1 touches no free range, so it takes the spare element
+        ids.free_id(1).unwrap();
+        assert_eq!(ranges(&ids), [(1, 2), (3, 8)]);
+        assert_eq!(ids.spare_storage(), 0);
+
+        // 2 joins both neighbours and gives an element back
+        ids.free_id(2).unwrap();
+        assert_eq!(ranges(&ids), [(1, 8)]);
+        assert_eq!(ids.spare_storage(), 1);
+
+        ids.free_id(0).unwrap();
+        assert_eq!(ranges(&ids), [(0, 8)]);
+        assert!(ids.is_free(7) && !ids.is_free(8));
+    }
+
+    #[test]
+    fn exhausted_ranges_return_their_storage() {
+        let mut storage = [IdRange::new(), IdRange::new()];
+        let mut ids = IdAllocator::new();
+        storage.iter_mut().for_each(|range| ids.add_storage(range));
+        ids.free_range(10..12).unwrap();
+        ids.free_id(20).unwrap();
+
+        let all: Vec<usize> = core::iter::from_fn(|| ids.alloc_id()).collect();
+        assert_eq!(all, [10, 11, 20]);
+        assert_eq!((ids.free_ranges(), ids.spare_storage()), (0, 2));
+    }
+
+    #[test]
+    fn bad_frees_change_nothing() {
+        let mut storage = IdRange::new();
+        let mut ids = IdAllocator::new();
+        ids.add_storage(&mut storage);
+        ids.free_range(4..8).unwrap();
+
+        assert_eq!(ids.free_id(5), Err(IdError::AlreadyFree));
+        assert_eq!(ids.free_range(2..5), Err(IdError::AlreadyFree));
+        assert_eq!(ids.free_id(0), Err(IdError::NoStorage));
+        assert_eq!(ids.free_range(3..3), Err(IdError::Empty));
+        assert_eq!(ranges(&ids), [(4, 8)]);
+
+        ids.free_id(3).unwrap();
+        assert_eq!(ranges(&ids), [(3, 8)]);
+    }
+}
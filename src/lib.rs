@@ -1,10 +1,28 @@
 #![no_std]
 
-#[cfg(test)]
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(any(test, feature = "std"))]
 extern crate std;
 
+/// Reports recoverable misuse through `log::warn!` when the `log` feature is enabled.
+macro_rules! rusty_warn {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "log")]
+        log::warn!($($arg)*);
+    };
+}
+
 mod core_types;      // RustyListNode, RustyList, traits, offset helpers
 mod list_ops;        // insert, remove, pop, push, etc.
+mod list_types;      // wrappers and sibling containers built on RustyList
+pub mod graph;       // intrusive adjacency lists for directed graphs
+pub mod abi;         // frozen, versioned C layouts for plugins
+pub mod id_alloc;    // lowest-first ID allocator over a sorted list of free ranges
+#[cfg(feature = "intrusive-collections")]
+pub mod compat;      // interop with intrusive_collections::LinkedList
+#[cfg(all(kani, feature = "verify"))]
+mod verification;    // bounded Kani proof harnesses
 
 #[allow(unused_imports)]
 pub use core_types::*;
@@ -12,10 +30,70 @@ pub use core_types::*;
 pub use list_ops::{
     insert::*,
     remove::*,
+    node_ref::*,
     find_equal::*,
+    iter::*,
+    payload::*,
+    cursor::*,
     pop::*,
     push::*,
+    batch::*,
+    rebase::*,
+    checkpoint::*,
+    collect::*,
+    sort::*,
+    merge::*,
+    transfer::*,
+    select::*,
+    random::*,
+    query::*,
+    format::*,
+    dump::*,
+    watermarks::*,
+    expire::*,
+    retain::*,
+    drain::*,
+    reposition::*,
+    persistent_cursor::*,
+    round_robin::*,
+    scrub::*,
+};
+#[cfg(feature = "age-stamps")]
+pub use list_ops::age::*;
+#[cfg(feature = "failure-injection")]
+pub use list_ops::failure::*;
+#[cfg(feature = "metrics")]
+pub use list_ops::metrics::*;
+#[cfg(feature = "reposition-hook")]
+pub use list_ops::reposition_hook::*;
+#[cfg(feature = "replay")]
+pub use list_ops::replay::*;
+#[cfg(feature = "std")]
+pub use list_ops::registry::*;
+#[allow(unused_imports)]
+pub use list_types::{
+    cell::*,
+    array_list::*,
+    index_list::*,
+    dma_ring::*,
+    counting::*,
+    min_max::*,
+    bounded::*,
+    padded::*,
+    deadline::*,
+    priority::*,
+    adaptive::*,
 };
+#[cfg(target_has_atomic = "ptr")]
+pub use list_types::steal_deque::*;
+#[cfg(feature = "critical-section")]
+pub use list_types::static_list::*;
+#[cfg(feature = "hashbrown")]
+pub use list_types::indexed::*;
+#[cfg(feature = "std")]
+pub use list_types::shared::*;
+#[cfg(feature = "async")]
+pub use list_types::stream::*;
 
 #[cfg(test)]
 mod tests {
@@ -35,6 +113,16 @@ mod tests {
         }
     }
 
+    assert_rusty_layout!(TestItem);
+
+    #[repr(C)]
+    struct LinkFirst {
+        link: RustyListNode<LinkFirst>,
+        tag: u8,
+    }
+
+    assert_rusty_layout!(LinkFirst, link);
+
     fn cmp(a: *const TestItem, b: *const TestItem) -> i32 {
         unsafe { (*a).value.cmp(&(*b).value) as i32 }
     }
@@ -46,6 +134,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn for_each_safe_allows_unlinking_the_current_element() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut done = RustyList::<TestItem>::new();
+        let mut items = [make_item(1), make_item(2), make_item(3), make_item(4), make_item(5)];
+        items.iter_mut().for_each(|item| list.push(item));
+
+        let mut visited = vec![];
+        rusty_for_each_safe!(item in list => {
+            let item = unsafe { &mut *item };
+            visited.push(item.value);
+            if item.value % 2 == 1 {
+                list.remove(item);
+                done.push(item);
+            }
+        });
+
+        assert_eq!(visited, vec![1, 2, 3, 4, 5]);
+        assert_eq!(list.iter().map(|item| item.value).collect::<std::vec::Vec<_>>(), vec![2, 4]);
+        assert_eq!(done.iter().map(|item| item.value).collect::<std::vec::Vec<_>>(), vec![1, 3, 5]);
+
+        rusty_for_each_safe!(item in done => {
+            done.remove(unsafe { &mut *item });
+        });
+        assert!(done.head.is_none() && done.len == 0);
+    }
+
     #[test]
     fn test_insert_sorted_and_order_is_correct() {
         let mut list = RustyList::<TestItem>::new_with_order(cmp);
@@ -68,7 +183,7 @@ mod tests {
         while let Some(node) = current {
             let item = unsafe { &*rusty_container_of(node.as_ptr(), list.offset) };
             values.push(item.value);
-            current = unsafe { (*node.as_ptr()).next };
+            current = unsafe { (*node.as_ptr()).next_node() };
         }
 
         assert_eq!(values, vec![10, 20, 30, 40, 50]);
@@ -108,7 +223,7 @@ mod tests {
 
         let target = make_item(20);
         let found_ptr = {
-            let temp = list.find_equal(&target).unwrap();
+            let temp = list.find_equal_mut(&target).unwrap();
             temp as *mut TestItem
         };
 
@@ -119,7 +234,7 @@ mod tests {
         while let Some(node) = current {
             let item = unsafe { &*rusty_container_of(node.as_ptr(), list.offset) };
             values.push(item.value);
-            current = unsafe { (*node.as_ptr()).next };
+            current = unsafe { (*node.as_ptr()).next_node() };
         }
 
         assert_eq!(list.len, 2);
@@ -172,4 +287,173 @@ mod tests {
         assert!(list.head.is_none());
         assert!(list.tail.is_none());
     }
+
+    // Layout matrix: the node first, last or between over-aligned fields, with zero-sized
+    // or SIMD-sized payloads. Each element type goes through the same operations.
+
+    trait Keyed: HasRustyNode {
+        fn key(&self) -> i32;
+        fn make(key: i32) -> Self;
+    }
+
+    fn by_key<T: Keyed>(a: *const T, b: *const T) -> i32 {
+        unsafe { (*a).key().cmp(&(*b).key()) as i32 }
+    }
+
+    fn exercise_layout<T: Keyed>() {
+        let mut items = [T::make(3), T::make(1), T::make(2), T::make(0)];
+        let mut list = RustyList::<T>::new_with_order(by_key::<T>);
+        items.iter_mut().for_each(|item| list.insert(item));
+
+        assert_eq!(list.iter().map(T::key).collect::<std::vec::Vec<_>>(), [0, 1, 2, 3]);
+        assert_eq!(list.iter_rev().map(T::key).collect::<std::vec::Vec<_>>(), [3, 2, 1, 0]);
+        for item in list.iter() {
+            assert!((item as *const T).is_aligned());
+            assert!(items.iter().any(|other| core::ptr::eq(other, item)));
+        }
+
+        // element indices survive the container's full size, padding included
+        let mut records = [0u32; 4];
+        assert_eq!(list.save_structure(items.as_ptr(), &mut records), 4);
+        assert_eq!(records, [3, 1, 2, 0]);
+
+        list.remove(&mut items[2]);
+        assert_eq!(list.pop().map(|item| unsafe { (*item).key() }), Some(0));
+        list.sort_by(|a, b| by_key(b, a));
+        assert_eq!(list.iter().map(T::key).collect::<std::vec::Vec<_>>(), [3, 1]);
+    }
+
+    #[repr(C)]
+    struct ZstPayload {
+        node: RustyListNode<ZstPayload>,
+        tag: core::marker::PhantomData<[u64; 0]>,
+        unit: (),
+    }
+
+    #[repr(C, align(64))]
+    #[derive(Clone, Copy)]
+    struct Lanes([f32; 16]);
+
+    #[repr(C, align(64))]
+    struct SimdNodeLast {
+        lanes: Lanes,
+        node: RustyListNode<SimdNodeLast>,
+    }
+
+    #[repr(C)]
+    struct SimdNodeFirst {
+        node: RustyListNode<SimdNodeFirst>,
+        lanes: Lanes,
+    }
+
+    #[repr(C)]
+    struct SimdNodeBetween {
+        head: Lanes,
+        node: RustyListNode<SimdNodeBetween>,
+        tail: Lanes,
+    }
+
+    assert_rusty_layout!(ZstPayload);
+    assert_rusty_layout!(SimdNodeLast);
+    assert_rusty_layout!(SimdNodeFirst);
+    assert_rusty_layout!(SimdNodeBetween);
+
+    impl HasRustyNode for ZstPayload {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    macro_rules! simd_keyed {
+        ($ty:ident, $field:ident) => {
+            impl HasRustyNode for $ty {
+                fn rusty_offset() -> usize {
+                    rusty_offset(|x: &Self| &x.node)
+                }
+            }
+
+            impl Keyed for $ty {
+                fn key(&self) -> i32 {
+                    self.$field.0[15] as i32
+                }
+
+                fn make(key: i32) -> Self {
+                    let mut lanes = Lanes([0.0; 16]);
+                    lanes.0[15] = key as f32;
+                    let mut item: Self = unsafe { core::mem::zeroed() };
+                    item.$field = lanes;
+                    item.node = RustyListNode::new();
+                    item
+                }
+            }
+        };
+    }
+
+    simd_keyed!(SimdNodeLast, lanes);
+    simd_keyed!(SimdNodeFirst, lanes);
+    simd_keyed!(SimdNodeBetween, tail);
+
+    #[test]
+    fn layout_matrix_offsets() {
+        assert_eq!(ZstPayload::rusty_offset(), 0);
+        assert_eq!(SimdNodeLast::rusty_offset(), 64);
+        assert_eq!(SimdNodeFirst::rusty_offset(), 0);
+        assert_eq!(SimdNodeBetween::rusty_offset(), 64);
+        assert_eq!(core::mem::size_of::<SimdNodeBetween>(), 192);
+    }
+
+    #[test]
+    fn layout_matrix_over_aligned_elements() {
+        exercise_layout::<SimdNodeLast>();
+        exercise_layout::<SimdNodeFirst>();
+        exercise_layout::<SimdNodeBetween>();
+    }
+
+    #[test]
+    fn layout_matrix_zero_sized_payload() {
+        let mut items = [0, 1, 2].map(|_| ZstPayload {
+            node: RustyListNode::new(),
+            tag: core::marker::PhantomData,
+            unit: (),
+        });
+        assert_eq!(core::mem::size_of::<ZstPayload>(), core::mem::size_of::<RustyListNode<ZstPayload>>());
+
+        let mut list = RustyList::<ZstPayload>::new();
+        items.iter_mut().for_each(|item| list.push(item));
+        let mut records = [0u32; 3];
+        assert_eq!(list.save_structure(items.as_ptr(), &mut records), 3);
+        assert_eq!(records, [0, 1, 2]);
+
+        list.remove(&mut items[1]);
+        let rest: std::vec::Vec<*const ZstPayload> = list.iter().map(|item| item as *const _).collect();
+        assert_eq!(rest, [&items[0] as *const _, &items[2] as *const _]);
+    }
+
+    #[test]
+    #[should_panic(expected = "rusty_offset: the field is not inside the struct")]
+    fn rusty_offset_rejects_outside_fields() {
+        static ELSEWHERE: u32 = 0;
+        rusty_offset(|_: &TestItem| &ELSEWHERE);
+    }
+
+    #[test]
+    fn test_is_linked_tracks_membership() {
+        let mut list = RustyList::<TestItem>::new_with_order(cmp);
+        let mut a = make_item(1);
+        let mut b = make_item(2);
+
+        list.insert(&mut a);
+        assert!(a.node.is_linked(), "sole node of a list is linked");
+
+        list.push(&mut b);
+        assert!(b.node.is_linked());
+        assert!(b.node.next_node().is_none());
+        assert!(b.node.prev_node().is_some());
+
+        list.remove(&mut a);
+        assert!(!a.node.is_linked());
+
+        let popped = list.pop().unwrap();
+        assert!(!unsafe { &*popped }.node.is_linked());
+    }
 }
@@ -0,0 +1,144 @@
+// age.rs
+// Arrival-order queries on lists ordered by something else (feature `age-stamps`).
+use crate::{RustyList, RustyListNode};
+use crate::list_ops::borrow_flag::TraversalGuard;
+use crate::list_ops::links::Link;
+
+impl<T> RustyListNode<T> {
+    /// The sequence stamp recorded when the node was last pushed or inserted.
+    ///
+    /// Stamps come from the list's counter, so they only compare meaningfully between
+    /// nodes of the same list.
+    pub fn stamp(&self) -> u64 {
+        self.stamp
+    }
+}
+
+impl<T> RustyList<T> {
+    /// The stamp the next pushed or inserted element will get.
+    ///
+    /// `next_stamp() - node.stamp()` is how many elements arrived after that node, which
+    /// makes "queued for more than X arrivals" a starvation check that works even when
+    /// the list is ordered by priority.
+    pub fn next_stamp(&self) -> u64 {
+        self.next_stamp
+    }
+
+    /// Records the next stamp on `node`. Called when an element is pushed or inserted.
+    #[inline(always)]
+    pub(crate) fn stamp_node(&mut self, node: *mut RustyListNode<T>) {
+        unsafe { (*node).stamp = self.next_stamp };
+        self.next_stamp = self.next_stamp.wrapping_add(1);
+    }
+
+    /// Returns the element that has been in the list the longest, in one walk.
+    pub fn oldest(&self) -> Option<&T> {
+        self.oldest_after(None).map(|node| unsafe { &*self.item_of(node) })
+    }
+
+    /// Iterates over the elements from oldest to newest arrival, whatever the list order.
+    ///
+    /// Needs no allocation, so each step walks the list to find the next stamp: visiting
+    /// every element costs O(n²). Use `oldest` when only the head of the age order matters.
+    pub fn iter_by_age(&self) -> AgeIter<'_, T> {
+        AgeIter {
+            list: self,
+            last: None,
+            _guard: self.begin_traversal(),
+        }
+    }
+
+    /// Finds the node with the smallest stamp greater than `after` (or the smallest overall).
+    fn oldest_after(&self, after: Option<u64>) -> Option<Link<T>> {
+        let mut best: Option<Link<T>> = None;
+        let mut current = self.head;
+
+        while let Some(node) = current {
+            let stamp = unsafe { (*node.as_ptr()).stamp };
+            let newer = after.is_none_or(|after| stamp > after);
+            if newer && best.is_none_or(|b| stamp < unsafe { (*b.as_ptr()).stamp }) {
+                best = Some(node);
+            }
+            current = unsafe { (*node.as_ptr()).next };
+        }
+
+        best
+    }
+}
+
+/// Iterator returned by `RustyList::iter_by_age`.
+pub struct AgeIter<'a, T> {
+    list: &'a RustyList<T>,
+    last: Option<u64>,
+    _guard: TraversalGuard<'a, T>,
+}
+
+impl<'a, T> Iterator for AgeIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let node = self.list.oldest_after(self.last)?;
+        self.last = Some(unsafe { (*node.as_ptr()).stamp });
+        Some(unsafe { &*self.list.item_of(node) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+    use crate::{RustyList, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub priority: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn cmp(a: *const TestItem, b: *const TestItem) -> i32 {
+        unsafe { (*a).priority.cmp(&(*b).priority) as i32 }
+    }
+
+    fn make_item(priority: i32) -> TestItem {
+        TestItem {
+            priority,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn age_order_is_independent_of_priority_order() {
+        let mut list = RustyList::<TestItem>::new_with_order(cmp);
+        let mut items = [make_item(5), make_item(1), make_item(3), make_item(2)];
+        for item in items.iter_mut() {
+            list.insert(item);
+        }
+
+        assert_eq!(list.oldest().unwrap().priority, 5);
+        let by_age: Vec<i32> = list.iter_by_age().map(|item| item.priority).collect();
+        assert_eq!(by_age, [5, 1, 3, 2]);
+
+        // the oldest leaves, and re-inserting it makes it the newest
+        list.remove(&mut items[0]);
+        assert_eq!(list.oldest().unwrap().priority, 1);
+        list.insert(&mut items[0]);
+        assert_eq!(items[0].node.stamp(), 4);
+        assert_eq!(list.next_stamp() - items[1].node.stamp(), 4);
+
+        let by_age: Vec<i32> = list.iter_by_age().map(|item| item.priority).collect();
+        assert_eq!(by_age, [1, 3, 2, 5]);
+    }
+
+    #[test]
+    fn empty_list_has_no_oldest() {
+        let list = RustyList::<TestItem>::new();
+        assert!(list.oldest().is_none());
+        assert_eq!(list.iter_by_age().count(), 0);
+    }
+}
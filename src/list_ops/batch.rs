@@ -0,0 +1,284 @@
+// batch.rs
+// Batched end operations for high-rate producer/consumer paths.
+use core::ptr::NonNull;
+use crate::RustyList;
+use crate::list_ops::links::Link;
+
+impl<T> RustyList<T> {
+    /// Appends every element of `chain` at the tail in O(1), leaving `chain` empty.
+    ///
+    /// A producer can link a group of elements into a local list without touching the
+    /// shared one, then publish the whole group with a single head/tail/len update. The
+    /// elements keep their order; the `order_function` is not consulted, like `push`.
+    ///
+    /// `chain` must hold the same element type with the node at the same offset.
+    pub fn push_many(&mut self, chain: &mut RustyList<T>) {
+        self.assert_not_traversing("push_many");
+        chain.assert_not_traversing("push_many");
+        debug_assert_eq!(self.offset, chain.offset, "RustyList::push_many: offsets differ");
+        let before = self.len;
+        self.splice_back(chain);
+        self.notify_watermarks(before);
+    }
+
+    /// Detaches the first `n` elements (or all of them, if there are fewer) and returns
+    /// them as a list, head first.
+    ///
+    /// Finding the split point walks `n` nodes, but the list itself is updated once, so a
+    /// consumer can take a batch and process it without going back to the shared list per
+    /// element. The returned list keeps this list's `order_function`.
+    pub fn pop_many(&mut self, n: usize) -> RustyList<T> {
+        self.assert_not_traversing("pop_many");
+        let before = self.len;
+        let batch = self.split_front(n);
+        self.notify_watermarks(before);
+        batch
+    }
+
+    /// Detaches the run of elements from `a` through `b`, inclusive, and returns it as a
+    /// list, `a` first.
+    ///
+    /// The surgery itself is O(1): the run's outer neighbours are joined and the run keeps
+    /// its inner links. `len` is the run's length if the caller knows it; with `None` it is
+    /// recounted by walking from `a` to `b`. Attached `PersistentCursor`s on the run also
+    /// cost a walk, to move them to the element after `b`. The returned list keeps this
+    /// list's `order_function`. If either element is unlinked, nothing is detached.
+    ///
+    /// # Safety
+    /// - `a` and `b` must point to live elements of this list, with `b` at or after `a`
+    ///   (they may be the same element).
+    /// - No reference to an element of the run may be live, since their links are written.
+    /// - `len`, if given, must be exactly the number of elements from `a` through `b`; release
+    ///   builds trust it. Debug builds check it, and that `b` is reachable from `a`.
+    pub unsafe fn nodes_between(&mut self, a: NonNull<T>, b: NonNull<T>, len: Option<usize>) -> RustyList<T> {
+        self.assert_not_traversing("nodes_between");
+        let mut run = self.empty_like();
+        let (first, last) = (self.node_of(a.as_ptr()), self.node_of(b.as_ptr()));
+        let (first_node, last_node) = unsafe { (&*first.as_ptr(), &*last.as_ptr()) };
+        if !first_node.linked || !last_node.linked {
+            rusty_warn!("RustyList::nodes_between: element is not linked, ignoring");
+            return run;
+        }
+        debug_assert!(
+            self.find_item(a.as_ptr()).is_some(),
+            "RustyList::nodes_between: `a` is not in this list",
+        );
+        let (before_run, after_run) = (first_node.prev, last_node.next);
+
+        let walked = match len {
+            Some(len) if !cfg!(debug_assertions) => Some(len),
+            _ => self.run_len(first, last),
+        };
+        debug_assert!(walked.is_some(), "RustyList::nodes_between: `b` is not reachable from `a`");
+        debug_assert!(
+            len.is_none() || len == walked,
+            "RustyList::nodes_between: `len` doesn't match the run",
+        );
+        let Some(count) = walked else {
+            return run;
+        };
+
+        // cursors on the detached elements move to the first one after the run
+        let mut moved = Some(first).filter(|_| self.has_cursors());
+        while let Some(node) = moved {
+            self.move_cursors(node, after_run);
+            moved = if node == last { None } else { unsafe { (*node.as_ptr()).next } };
+        }
+
+        #[cfg(feature = "replay")]
+        self.record_unlinks(first, last);
+        let before = self.len;
+        match before_run {
+            Some(p) => unsafe { (*p.as_ptr()).next = after_run },
+            None => self.head = after_run,
+        }
+        match after_run {
+            Some(n) => unsafe { (*n.as_ptr()).prev = before_run },
+            None => self.tail = before_run,
+        }
+        unsafe {
+            (*first.as_ptr()).prev = None;
+            (*last.as_ptr()).next = None;
+        }
+        self.len = self.len.saturating_sub(count);
+
+        run.head = Some(first);
+        run.tail = Some(last);
+        run.len = count;
+        self.notify_watermarks(before);
+        run
+    }
+
+    /// Number of nodes from `first` through `last`, or `None` if `last` doesn't follow.
+    fn run_len(&self, first: Link<T>, last: Link<T>) -> Option<usize> {
+        let mut count = 0;
+        let mut node = Some(first);
+        while let Some(n) = node {
+            count += 1;
+            if n == last {
+                return Some(count);
+            }
+            node = unsafe { (*n.as_ptr()).next };
+        }
+        None
+    }
+
+    /// Deals the elements round-robin onto the tails of the first `n` lists of `out`,
+    /// leaving this list empty.
+    ///
+    /// One pass: element 0 goes to `out[0]`, element 1 to `out[1]`, and so on, wrapping
+    /// after `n`, so the shard lengths differ by at most one and each shard keeps the
+    /// relative order of its elements. Meant for re-sharding a backlog across worker
+    /// queues. `n` is clamped to `out.len()`; with `n == 0` nothing moves. The destination
+    /// lists' `order_function`s are not consulted.
+    pub fn split_evenly(&mut self, n: usize, out: &mut [RustyList<T>]) {
+        self.assert_not_traversing("split_evenly");
+        let n = n.min(out.len());
+        if n == 0 {
+            return;
+        }
+
+        let before = self.len;
+        let mut shard = 0;
+        while let Some(node) = self.head {
+            let Some(dest) = out.get_mut(shard) else {
+                break;
+            };
+            debug_assert_eq!(self.offset, dest.offset, "RustyList::split_evenly: offsets differ");
+            let dest_before = dest.len;
+            unsafe {
+                self.unlink_node(node);
+                dest.link_after(dest.tail, node);
+            }
+            dest.notify_watermarks(dest_before);
+            shard = (shard + 1) % n;
+        }
+
+        self.notify_watermarks(before);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::ptr::NonNull;
+    use std::vec;
+    use std::vec::Vec;
+    use crate::{RustyList, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn push_many_links_chain_at_tail() {
+        let mut items = [make_item(1), make_item(2), make_item(3), make_item(4)];
+        let (first, rest) = items.split_at_mut(1);
+        let mut list = RustyList::<TestItem>::new();
+        let mut chain = RustyList::<TestItem>::new();
+        list.push(&mut first[0]);
+        rest.iter_mut().for_each(|item| chain.push(item));
+
+        list.push_many(&mut chain);
+        assert_eq!(list.len, 4);
+        assert_eq!(chain.len, 0);
+        assert!(chain.head.is_none() && chain.tail.is_none());
+
+        // pushing an empty chain is a no-op
+        list.push_many(&mut chain);
+        assert_eq!(list.iter().map(|item| item.value).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn split_evenly_deals_round_robin() {
+        let mut items: Vec<TestItem> = (1..=7).map(make_item).collect();
+        let mut list = RustyList::<TestItem>::new();
+        items.iter_mut().for_each(|item| list.push(item));
+
+        let mut shards = [RustyList::new(), RustyList::new(), RustyList::new(), RustyList::new()];
+        list.split_evenly(3, &mut shards);
+
+        assert_eq!(list.len, 0);
+        assert!(list.head.is_none() && list.tail.is_none());
+        let [a, b, c, unused] = &mut shards;
+        assert_eq!(a.iter().map(|item| item.value).collect::<Vec<_>>(), vec![1, 4, 7]);
+        assert_eq!(b.iter().map(|item| item.value).collect::<Vec<_>>(), vec![2, 5]);
+        assert_eq!(c.iter().map(|item| item.value).collect::<Vec<_>>(), vec![3, 6]);
+        assert_eq!(unused.len, 0);
+    }
+
+    #[test]
+    fn pop_many_detaches_front_batch() {
+        let mut items = [make_item(1), make_item(2), make_item(3)];
+        let mut list = RustyList::<TestItem>::new();
+        items.iter_mut().for_each(|item| list.push(item));
+
+        let batch = list.pop_many(2);
+        assert_eq!(batch.len, 2);
+        assert_eq!(list.len, 1);
+        assert_eq!(batch.iter().map(|item| item.value).collect::<Vec<_>>(), vec![1, 2]);
+
+        let rest = list.pop_many(10);
+        assert_eq!(list.len, 0);
+        assert!(list.head.is_none());
+        assert_eq!(rest.iter().map(|item| item.value).collect::<Vec<_>>(), vec![3]);
+        assert_eq!(list.pop_many(1).len, 0);
+    }
+
+    #[test]
+    fn nodes_between_detaches_a_run() {
+        let mut items: Vec<TestItem> = (0..6).map(make_item).collect();
+        let mut list = RustyList::<TestItem>::new();
+        items.iter_mut().for_each(|item| list.push(item));
+        let at: Vec<NonNull<TestItem>> = items.iter_mut().map(NonNull::from).collect();
+
+        let run = unsafe { list.nodes_between(at[2], at[4], None) };
+        assert_eq!((run.len, list.len), (3, 3));
+
+        // head and tail runs, with a known length
+        let head_run = unsafe { list.nodes_between(at[0], at[1], Some(2)) };
+        let tail_run = unsafe { list.nodes_between(at[5], at[5], Some(1)) };
+        assert_eq!(run.iter().map(|item| item.value).collect::<Vec<_>>(), vec![2, 3, 4]);
+        assert_eq!(head_run.iter().map(|item| item.value).collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(tail_run.iter().map(|item| item.value).collect::<Vec<_>>(), vec![5]);
+        assert!(list.head.is_none() && list.tail.is_none() && list.len == 0);
+    }
+
+    #[test]
+    fn nodes_between_ignores_unlinked_elements() {
+        let mut a = make_item(1);
+        let mut b = make_item(2);
+        let mut list = RustyList::<TestItem>::new();
+        list.push(&mut a);
+
+        let run = unsafe { list.nodes_between(NonNull::from(&mut a), NonNull::from(&mut b), None) };
+        assert_eq!((run.len, list.len), (0, 1));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "`b` is not reachable from `a`")]
+    fn nodes_between_checks_reachability() {
+        let mut items = [make_item(1), make_item(2)];
+        let mut list = RustyList::<TestItem>::new();
+        items.iter_mut().for_each(|item| list.push(item));
+        let at: Vec<NonNull<TestItem>> = items.iter_mut().map(NonNull::from).collect();
+
+        unsafe { list.nodes_between(at[1], at[0], Some(2)) };
+    }
+}
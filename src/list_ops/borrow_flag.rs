@@ -0,0 +1,147 @@
+// borrow_flag.rs
+// Runtime protection against structural changes during a traversal or a callback
+// (feature `borrow-check`).
+use crate::RustyList;
+
+/// Marks a traversal as active for as long as it is alive.
+///
+/// Without the `borrow-check` feature this is a zero-sized no-op.
+pub(crate) struct TraversalGuard<'a, T> {
+    #[cfg(feature = "borrow-check")]
+    list: &'a RustyList<T>,
+    #[cfg(not(feature = "borrow-check"))]
+    _list: core::marker::PhantomData<&'a RustyList<T>>,
+}
+
+impl<T> RustyList<T> {
+    /// Starts a traversal; structural operations panic until the guard is dropped.
+    #[inline(always)]
+    pub(crate) fn begin_traversal(&self) -> TraversalGuard<'_, T> {
+        #[cfg(feature = "borrow-check")]
+        {
+            self.borrow_flag.set(self.borrow_flag.get() + 1);
+            TraversalGuard { list: self }
+        }
+        #[cfg(not(feature = "borrow-check"))]
+        TraversalGuard { _list: core::marker::PhantomData }
+    }
+
+    /// Runs a user callback with the list marked busy, like a traversal.
+    ///
+    /// Hooks (watermarks, reposition) and eviction callbacks (`on_removed`, `on_expired`)
+    /// run in the middle of an operation, sometimes with the list half relinked. A
+    /// callback that reaches back into the same list through an alias and changes it
+    /// would corrupt it; with `borrow-check` that change panics instead.
+    #[inline(always)]
+    pub(crate) fn run_callback<R>(&self, f: impl FnOnce() -> R) -> R {
+        let _guard = self.begin_traversal();
+        f()
+    }
+
+    /// Panics if a traversal or callback is active. Called by every structural operation.
+    #[inline(always)]
+    #[track_caller]
+    pub(crate) fn assert_not_traversing(&self, op: &str) {
+        #[cfg(feature = "borrow-check")]
+        if self.borrow_flag.get() != 0 {
+            panic!("RustyList: `{op}` called while the list is being traversed or running a callback");
+        }
+        #[cfg(not(feature = "borrow-check"))]
+        let _ = op;
+    }
+}
+
+#[cfg(feature = "borrow-check")]
+impl<T> Drop for TraversalGuard<'_, T> {
+    fn drop(&mut self) {
+        self.list.borrow_flag.set(self.list.borrow_flag.get() - 1);
+    }
+}
+
+#[cfg(all(test, feature = "borrow-check"))]
+mod tests {
+    use crate::{RustyList, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "`push` called while the list is being traversed")]
+    fn push_during_traversal_through_alias_panics() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut a = make_item(1);
+        let mut b = make_item(2);
+        list.push(&mut a);
+
+        let alias = &mut list as *mut RustyList<TestItem>;
+        let b_ptr = &mut b as *mut TestItem;
+        list.any(|_| {
+            unsafe { (*alias).push(&mut *b_ptr) };
+            false
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "`remove` called while the list is being traversed")]
+    fn remove_during_find_panics() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut a = make_item(1);
+        list.push(&mut a);
+
+        let alias = &mut list as *mut RustyList<TestItem>;
+        let a_ptr = &mut a as *mut TestItem;
+        list.count_if(|_| {
+            unsafe { (*alias).remove(&mut *a_ptr) };
+            true
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "`push` called while the list is being traversed or running a callback")]
+    fn push_from_eviction_callback_panics() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut items = [make_item(1), make_item(2), make_item(3)];
+        items.iter_mut().for_each(|item| list.push(item));
+        let mut extra = make_item(4);
+
+        // the callback runs while `keep_smallest_k_by` has the list taken apart
+        let alias = &mut list as *mut RustyList<TestItem>;
+        let extra_ptr = &mut extra as *mut TestItem;
+        list.keep_smallest_k_by(
+            1,
+            |a, b| unsafe { (*a).value.cmp(&(*b).value) as i32 },
+            |_| unsafe { (*alias).push(&mut *extra_ptr) },
+        );
+    }
+
+    #[test]
+    fn flag_is_released_after_traversal() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut a = make_item(1);
+        let mut b = make_item(2);
+        list.push(&mut a);
+
+        assert!(list.any(|item| item.value == 1));
+        assert_eq!(list.borrow_flag.get(), 0);
+
+        list.push(&mut b);
+        assert_eq!(list.len, 2);
+    }
+}
@@ -0,0 +1,163 @@
+use core::mem::size_of;
+use crate::{RustyList, rusty_container_of};
+
+impl<T> RustyList<T> {
+    /// Saves the order of the list as element indices relative to the array at `base`.
+    ///
+    /// Pointers can't survive a reboot but indices can, so this is how a queue kept in
+    /// battery-backed RAM is persisted. Record `i` is the index into `base` of the `i`-th
+    /// element of the list.
+    ///
+    /// # Returns
+    /// The number of records written to `out`. This is less than `len` if `out` is too
+    /// small or if an element lies before `base` or off an element boundary, in which case
+    /// the records stop right before that element. The array length is not known here, so
+    /// indices past its end are not detected.
+    pub fn save_structure(&self, base: *const T, out: &mut [u32]) -> usize {
+        let size = size_of::<T>();
+        if size == 0 {
+            return 0;
+        }
+
+        let mut written = 0;
+        let mut current = self.head;
+
+        for slot in out.iter_mut() {
+            let Some(node_ptr) = current else {
+                break;
+            };
+
+            let item = unsafe { rusty_container_of(node_ptr.as_ptr(), self.offset) };
+            let delta = item.addr().wrapping_sub(base.addr());
+            if item.addr() < base.addr() || delta % size != 0 {
+                break;
+            }
+
+            match u32::try_from(delta / size) {
+                Ok(index) => *slot = index,
+                Err(_) => break,
+            }
+            written += 1;
+
+            current = unsafe { (*node_ptr.as_ptr()).next };
+        }
+
+        written
+    }
+
+    /// Rebuilds the list from records written by `save_structure`.
+    ///
+    /// The list is reset and the elements `base[records[0]]`, `base[records[1]]`, ... are
+    /// linked in that order. The nodes' old links are never read, so this is safe to use on
+    /// storage whose pointers are stale after a reboot.
+    ///
+    /// # Safety
+    /// - Every index in `records` must be in bounds of the array at `base`, and no index
+    ///   may appear twice.
+    /// - The elements must stay valid for as long as they are in the list.
+    /// - Elements that were in the list before are dropped from it without being unlinked.
+    pub unsafe fn restore_structure(&mut self, base: *mut T, records: &[u32]) {
+        self.assert_not_traversing("restore_structure");
+        let before = self.len;
+        self.park_cursors();
+        #[cfg(feature = "replay")]
+        self.record_clear();
+        self.head = None;
+        self.tail = None;
+        self.len = 0;
+
+        for &index in records {
+            let item = unsafe { base.add(index as usize) };
+            // the `linked` flag is as stale as the links, so don't warn about it
+            unsafe { (*self.node_of(item).as_ptr()).linked = false };
+            let node = self.claim_node(item, "restore_structure");
+            unsafe { self.link_after(self.tail, node) };
+        }
+        self.notify_watermarks(before);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec;
+    use std::vec::Vec;
+    use crate::{RustyList, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn save_then_restore_round_trips_order() {
+        let mut pool = [make_item(0), make_item(1), make_item(2), make_item(3)];
+        let base = pool.as_mut_ptr();
+
+        let mut list = RustyList::<TestItem>::new();
+        list.push(&mut pool[2]);
+        list.push(&mut pool[0]);
+        list.push(&mut pool[3]);
+
+        let mut records = [0u32; 4];
+        let written = list.save_structure(base, &mut records);
+        assert_eq!(written, 3);
+        assert_eq!(&records[..written], &[2, 0, 3]);
+
+        // simulate a reboot: the old list head and node links are garbage
+        let mut restored = RustyList::<TestItem>::new();
+        for item in pool.iter_mut() {
+            item.node.next = Some(core::ptr::NonNull::dangling());
+        }
+
+        unsafe { restored.restore_structure(base, &records[..written]) };
+
+        assert_eq!(restored.len, 3);
+        assert_eq!(restored.iter().map(|item| item.value).collect::<Vec<_>>(), vec![2, 0, 3]);
+    }
+
+    #[test]
+    fn save_stops_when_buffer_is_full() {
+        let mut pool = [make_item(0), make_item(1), make_item(2)];
+        let base = pool.as_ptr();
+
+        let mut list = RustyList::<TestItem>::new();
+        for item in pool.iter_mut() {
+            list.push(item);
+        }
+
+        let mut records = [0u32; 2];
+        assert_eq!(list.save_structure(base, &mut records), 2);
+        assert_eq!(records, [0, 1]);
+    }
+
+    #[test]
+    fn save_stops_at_element_before_base() {
+        let mut pool = [make_item(0), make_item(1), make_item(2)];
+        let base = unsafe { pool.as_ptr().add(1) };
+
+        let mut list = RustyList::<TestItem>::new();
+        list.push(&mut pool[2]);
+        list.push(&mut pool[0]);
+        list.push(&mut pool[1]);
+
+        let mut records = [0u32; 3];
+        let written = list.save_structure(base, &mut records);
+        assert_eq!(written, 1);
+        assert_eq!(records[0], 1);
+    }
+}
@@ -0,0 +1,129 @@
+use core::mem::MaybeUninit;
+use crate::{RustyList, rusty_container_of_mut};
+
+impl<T> RustyList<T> {
+    /// Fills `out` with pointers to the elements of the list, head to tail, in one walk.
+    ///
+    /// This gives a stable snapshot that can be sorted or inspected offline while the
+    /// live list keeps changing. The pointers are only valid while the elements are.
+    ///
+    /// # Returns
+    /// The number of pointers written; the first that many slots of `out` are initialized.
+    /// This is `min(len, out.len())`.
+    pub fn collect_ptrs(&self, out: &mut [MaybeUninit<*mut T>]) -> usize {
+        let mut written = 0;
+        let mut current = self.head;
+
+        for slot in out.iter_mut() {
+            let Some(node_ptr) = current else {
+                break;
+            };
+
+            slot.write(unsafe { rusty_container_of_mut(node_ptr.as_ptr(), self.offset) });
+            written += 1;
+
+            current = unsafe { (*node_ptr.as_ptr()).next };
+        }
+
+        written
+    }
+
+    /// Gathers shared references to the elements of the list, head to tail, into a `Vec`.
+    ///
+    /// A convenience for handing a snapshot to std-side code such as serializers or test
+    /// assertions. The borrow of the list keeps it from changing while the references live.
+    #[cfg(feature = "alloc")]
+    pub fn collect_refs(&self) -> alloc::vec::Vec<&T> {
+        let _guard = self.begin_traversal();
+        let mut refs = alloc::vec::Vec::with_capacity(self.len);
+        let mut current = self.head;
+
+        while let Some(node_ptr) = current {
+            refs.push(unsafe { &*rusty_container_of_mut(node_ptr.as_ptr(), self.offset) });
+            current = unsafe { (*node_ptr.as_ptr()).next };
+        }
+
+        refs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem::MaybeUninit;
+    use crate::{RustyList, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn collect_ptrs_snapshots_in_order() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut a = make_item(1);
+        let mut b = make_item(2);
+        let mut c = make_item(3);
+
+        list.push(&mut a);
+        list.push(&mut b);
+        list.push(&mut c);
+
+        let mut out = [MaybeUninit::<*mut TestItem>::uninit(); 4];
+        let written = list.collect_ptrs(&mut out);
+        assert_eq!(written, 3);
+
+        // the snapshot stays put while the live list changes
+        list.pop();
+
+        let values: [i32; 3] = core::array::from_fn(|i| unsafe { (*out[i].assume_init()).value });
+        assert_eq!(values, [1, 2, 3]);
+    }
+
+    #[test]
+    fn collect_ptrs_stops_at_buffer_end() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut a = make_item(1);
+        let mut b = make_item(2);
+
+        list.push(&mut a);
+        list.push(&mut b);
+
+        let mut out = [MaybeUninit::<*mut TestItem>::uninit(); 1];
+        assert_eq!(list.collect_ptrs(&mut out), 1);
+        assert_eq!(unsafe { (*out[0].assume_init()).value }, 1);
+
+        let mut empty: [MaybeUninit<*mut TestItem>; 0] = [];
+        assert_eq!(list.collect_ptrs(&mut empty), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn collect_refs_gathers_in_order() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut a = make_item(1);
+        let mut b = make_item(2);
+
+        assert!(list.collect_refs().is_empty());
+
+        list.push(&mut a);
+        list.push(&mut b);
+
+        let values: std::vec::Vec<i32> = list.collect_refs().iter().map(|item| item.value).collect();
+        assert_eq!(values, [1, 2]);
+    }
+}
@@ -0,0 +1,369 @@
+// cursor.rs
+// Borrowing cursors for navigating the list in both directions, and for editing it at
+// the cursor.
+use crate::RustyList;
+use crate::list_ops::borrow_flag::TraversalGuard;
+use crate::list_ops::links::Link;
+
+impl<T> RustyList<T> {
+    /// Returns a read-only cursor at the head, or at the "ghost" position if the list is
+    /// empty.
+    pub fn cursor_front(&self) -> Cursor<'_, T> {
+        Cursor { list: self, current: self.head, _guard: self.begin_traversal() }
+    }
+
+    /// Returns a read-only cursor at the tail, or at the "ghost" position if the list is
+    /// empty.
+    pub fn cursor_back(&self) -> Cursor<'_, T> {
+        Cursor { list: self, current: self.tail, _guard: self.begin_traversal() }
+    }
+
+    /// Returns an editing cursor at the head, or at the ghost position if the list is
+    /// empty.
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut { current: self.head, list: self }
+    }
+
+    /// Returns an editing cursor at the tail, or at the ghost position if the list is
+    /// empty.
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut { current: self.tail, list: self }
+    }
+}
+
+/// A read-only cursor over a `RustyList`, moved freely in both directions.
+///
+/// Like the cursors of `std::collections::LinkedList`, it sits either on an element or on
+/// a "ghost" position between the tail and the head, which `current` reports as `None`:
+/// moving next from the tail or previous from the head lands on the ghost, and moving
+/// from the ghost wraps to the head or tail. The list is borrowed for the cursor's
+/// lifetime, so it can't change underneath it; use a `PersistentCursor` for a position
+/// that survives modifications.
+pub struct Cursor<'a, T> {
+    list: &'a RustyList<T>,
+    current: Option<Link<T>>,
+    _guard: TraversalGuard<'a, T>,
+}
+
+impl<'a, T> Cursor<'a, T> {
+    /// The element under the cursor, or `None` at the ghost position.
+    pub fn current(&self) -> Option<&'a T> {
+        self.current.map(|node| self.element(node))
+    }
+
+    /// Moves to the next element, from the tail to the ghost, and from the ghost to the
+    /// head.
+    pub fn move_next(&mut self) {
+        self.current = match self.current {
+            Some(node) => unsafe { (*node.as_ptr()).next },
+            None => self.list.head,
+        };
+    }
+
+    /// Moves to the previous element, from the head to the ghost, and from the ghost to
+    /// the tail.
+    pub fn move_prev(&mut self) {
+        self.current = match self.current {
+            Some(node) => unsafe { (*node.as_ptr()).prev },
+            None => self.list.tail,
+        };
+    }
+
+    /// The element `move_next` would move to, without moving.
+    pub fn peek_next(&self) -> Option<&'a T> {
+        let next = match self.current {
+            Some(node) => unsafe { (*node.as_ptr()).next },
+            None => self.list.head,
+        };
+        next.map(|node| self.element(node))
+    }
+
+    /// The element `move_prev` would move to, without moving.
+    pub fn peek_prev(&self) -> Option<&'a T> {
+        let prev = match self.current {
+            Some(node) => unsafe { (*node.as_ptr()).prev },
+            None => self.list.tail,
+        };
+        prev.map(|node| self.element(node))
+    }
+
+    /// The list the cursor walks.
+    pub fn list(&self) -> &'a RustyList<T> {
+        self.list
+    }
+
+    fn element(&self, node: Link<T>) -> &'a T {
+        unsafe { &*self.list.item_of(node) }
+    }
+}
+
+/// A cursor that can change the list where it stands, moved like `Cursor`.
+///
+/// Walking to a position once and then inserting, removing or splitting there saves the
+/// search from the head that `insert`/`remove` would repeat. Elements are placed exactly
+/// where the cursor says, without consulting the `order_function`, so on a sorted list
+/// keep them in order yourself. `PersistentCursor`s attached to the list follow removed
+/// elements as usual.
+pub struct CursorMut<'a, T> {
+    list: &'a mut RustyList<T>,
+    current: Option<Link<T>>,
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    /// The element under the cursor, or `None` at the ghost position.
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.current.map(|node| unsafe { &mut *self.list.item_of(node) })
+    }
+
+    /// Moves to the next element, from the tail to the ghost, and from the ghost to the
+    /// head.
+    pub fn move_next(&mut self) {
+        self.current = self.next_link();
+    }
+
+    /// Moves to the previous element, from the head to the ghost, and from the ghost to
+    /// the tail.
+    pub fn move_prev(&mut self) {
+        self.current = self.prev_link();
+    }
+
+    /// The element `move_next` would move to, without moving.
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        self.next_link().map(|node| unsafe { &mut *self.list.item_of(node) })
+    }
+
+    /// The element `move_prev` would move to, without moving.
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        self.prev_link().map(|node| unsafe { &mut *self.list.item_of(node) })
+    }
+
+    /// Links `item` right before the cursor; at the ghost position, at the tail.
+    ///
+    /// The cursor stays on its element.
+    pub fn insert_before(&mut self, item: &mut T) {
+        let prev = self.prev_link();
+        self.link(prev, item, "insert_before");
+    }
+
+    /// Links `item` right after the cursor; at the ghost position, at the head.
+    ///
+    /// The cursor stays on its element.
+    pub fn insert_after(&mut self, item: &mut T) {
+        let prev = self.current;
+        self.link(prev, item, "insert_after");
+    }
+
+    /// Unlinks the element under the cursor and moves the cursor to the next one.
+    ///
+    /// # Returns
+    /// The detached element, or `None` at the ghost position, where nothing is removed.
+    pub fn remove_current(&mut self) -> Option<*mut T> {
+        let node = self.current?;
+        self.list.assert_not_traversing("remove_current");
+        let before = self.list.len;
+        self.current = unsafe { (*node.as_ptr()).next };
+        unsafe { self.list.unlink_node(node) };
+        self.list.notify_watermarks(before);
+        Some(self.list.item_of(node))
+    }
+
+    /// Detaches every element after the cursor into a new list and returns it.
+    ///
+    /// At the ghost position the whole list is detached. The new list has the same node
+    /// offset and `order_function`, and none of the cursors, watermarks or hooks;
+    /// `PersistentCursor`s on the detached elements are moved past the end.
+    pub fn split_after(&mut self) -> RustyList<T> {
+        self.list.assert_not_traversing("split_after");
+        let list = &mut *self.list;
+        let mut back = list.empty_like();
+        let Some(first) = (match self.current {
+            Some(node) => unsafe { (*node.as_ptr()).next },
+            None => list.head,
+        }) else {
+            return back;
+        };
+
+        let before = list.len;
+        let mut moved = 0;
+        let mut node = Some(first);
+        while let Some(n) = node {
+            list.move_cursors(n, None);
+            moved += 1;
+            node = unsafe { (*n.as_ptr()).next };
+        }
+
+        #[cfg(feature = "replay")]
+        if let Some(last) = list.tail {
+            list.record_unlinks(first, last);
+        }
+        match self.current {
+            Some(last) => unsafe {
+                (*last.as_ptr()).next = None;
+                (*first.as_ptr()).prev = None;
+            },
+            None => list.head = None,
+        }
+        back.head = Some(first);
+        back.tail = list.tail;
+        back.len = moved;
+
+        list.tail = self.current;
+        list.len = list.len.saturating_sub(moved);
+        list.notify_watermarks(before);
+        back
+    }
+
+    /// The list the cursor edits.
+    pub fn list(&self) -> &RustyList<T> {
+        self.list
+    }
+
+    fn next_link(&self) -> Option<Link<T>> {
+        match self.current {
+            Some(node) => unsafe { (*node.as_ptr()).next },
+            None => self.list.head,
+        }
+    }
+
+    fn prev_link(&self) -> Option<Link<T>> {
+        match self.current {
+            Some(node) => unsafe { (*node.as_ptr()).prev },
+            None => self.list.tail,
+        }
+    }
+
+    fn link(&mut self, prev: Option<Link<T>>, item: &mut T, op: &str) {
+        self.list.assert_not_traversing(op);
+        let before = self.list.len;
+        let node = self.list.claim_node(item, op);
+        unsafe { self.list.link_after(prev, node) };
+        self.list.notify_watermarks(before);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+    use crate::{RustyList, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    fn value(item: Option<&TestItem>) -> Option<i32> {
+        item.map(|item| item.value)
+    }
+
+    #[test]
+    fn cursor_moves_both_ways_through_the_ghost() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut items = [make_item(1), make_item(2), make_item(3)];
+        items.iter_mut().for_each(|item| list.push(item));
+
+        let mut cursor = list.cursor_front();
+        assert_eq!(value(cursor.current()), Some(1));
+        assert_eq!(value(cursor.peek_prev()), None);
+        assert_eq!(value(cursor.peek_next()), Some(2));
+
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(value(cursor.current()), Some(3));
+        cursor.move_next();
+        assert_eq!(value(cursor.current()), None);
+        assert_eq!((value(cursor.peek_next()), value(cursor.peek_prev())), (Some(1), Some(3)));
+        cursor.move_next();
+        assert_eq!(value(cursor.current()), Some(1));
+
+        let mut back = list.cursor_back();
+        back.move_prev();
+        assert_eq!(value(back.current()), Some(2));
+        back.move_prev();
+        back.move_prev();
+        assert_eq!(value(back.current()), None);
+        back.move_prev();
+        assert_eq!(value(back.current()), Some(3));
+    }
+
+    #[test]
+    fn cursor_on_empty_list_stays_on_the_ghost() {
+        let list = RustyList::<TestItem>::new();
+        let mut cursor = list.cursor_front();
+        assert!(cursor.current().is_none());
+        cursor.move_next();
+        cursor.move_prev();
+        assert!(cursor.current().is_none() && cursor.peek_next().is_none());
+        assert!(list.cursor_back().current().is_none());
+    }
+
+    #[test]
+    fn cursor_mut_edits_in_place() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut items = [make_item(1), make_item(3), make_item(5)];
+        items.iter_mut().for_each(|item| list.push(item));
+        let (mut two, mut four, mut zero, mut six) = (make_item(2), make_item(4), make_item(0), make_item(6));
+
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        cursor.insert_before(&mut two);
+        cursor.insert_after(&mut four);
+        assert_eq!(value(cursor.current().map(|item| &*item)), Some(3));
+        cursor.current().unwrap().value = 30;
+
+        // at the ghost, before means the tail and after means the head
+        cursor.move_prev();
+        cursor.move_prev();
+        cursor.move_prev();
+        assert!(cursor.current().is_none());
+        cursor.insert_after(&mut zero);
+        cursor.insert_before(&mut six);
+        assert_eq!(list.iter().map(|item| item.value).collect::<Vec<_>>(), [0, 1, 2, 30, 4, 5, 6]);
+        assert_eq!(list.len, 7);
+
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        let removed = cursor.remove_current().unwrap();
+        assert_eq!(unsafe { (*removed).value }, 1);
+        assert_eq!(value(cursor.current().map(|item| &*item)), Some(2));
+        assert!(!items[0].node.is_linked());
+
+        cursor.move_next();
+        let back = cursor.split_after();
+        assert_eq!(back.iter().map(|item| item.value).collect::<Vec<_>>(), [4, 5, 6]);
+        assert_eq!(list.iter().map(|item| item.value).collect::<Vec<_>>(), [0, 2, 30]);
+        assert_eq!((list.len, back.len), (3, 3));
+        assert_eq!(unsafe { (*list.tail_item().unwrap()).value }, 30);
+    }
+
+    #[test]
+    fn cursor_mut_at_the_ghost() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut items = [make_item(1), make_item(2)];
+        items.iter_mut().for_each(|item| list.push(item));
+
+        let mut cursor = list.cursor_back_mut();
+        cursor.move_next();
+        assert!(cursor.remove_current().is_none());
+        let all = cursor.split_after();
+        assert_eq!(all.iter().map(|item| item.value).collect::<Vec<_>>(), [1, 2]);
+        assert!(list.head.is_none() && list.tail.is_none() && list.len == 0);
+
+        let mut cursor = list.cursor_front_mut();
+        assert!(cursor.split_after().head.is_none());
+    }
+}
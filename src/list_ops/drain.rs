@@ -0,0 +1,180 @@
+// drain.rs
+// Emptying the list, or a subset of it, through an iterator.
+use core::ptr::NonNull;
+use crate::RustyList;
+use crate::list_ops::links::Link;
+
+impl<T> RustyList<T> {
+    /// Returns an iterator that unlinks and yields the elements from head to tail.
+    ///
+    /// Each element is popped as it is yielded, so it is fully detached by the time the
+    /// caller sees it and can be freed or pushed onto another list right away. The list
+    /// is empty once the iterator is dropped, even if it wasn't run to the end.
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        Drain { list: self }
+    }
+
+    /// Returns an iterator that walks the list from head to tail, unlinking and yielding
+    /// the elements `pred` accepts.
+    ///
+    /// Rejected elements stay linked, in their order. Like `drain`, each element is fully
+    /// detached when it is yielded, so moving a subset of work items to another list is
+    /// `for item in pending.extract_if(is_ready) { ready.push(...) }`. Dropping the
+    /// iterator early stops the walk: the elements it hasn't reached stay in the list.
+    pub fn extract_if<F: FnMut(&T) -> bool>(&mut self, pred: F) -> ExtractIf<'_, T, F> {
+        self.assert_not_traversing("extract_if");
+        ExtractIf { next: self.head, list: self, pred }
+    }
+}
+
+/// Iterator returned by `RustyList::drain`.
+pub struct Drain<'a, T> {
+    list: &'a mut RustyList<T>,
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = NonNull<T>;
+
+    fn next(&mut self) -> Option<NonNull<T>> {
+        self.list.pop().and_then(NonNull::new)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len, Some(self.list.len))
+    }
+}
+
+impl<T> ExactSizeIterator for Drain<'_, T> {}
+
+impl<T> core::iter::FusedIterator for Drain<'_, T> {}
+
+/// Unlinks whatever the caller didn't take.
+impl<T> Drop for Drain<'_, T> {
+    fn drop(&mut self) {
+        while self.list.pop().is_some() {}
+    }
+}
+
+/// Iterator returned by `RustyList::extract_if`, head to tail.
+pub struct ExtractIf<'a, T, F> {
+    list: &'a mut RustyList<T>,
+    next: Option<Link<T>>,
+    pred: F,
+}
+
+impl<T, F: FnMut(&T) -> bool> Iterator for ExtractIf<'_, T, F> {
+    type Item = NonNull<T>;
+
+    fn next(&mut self) -> Option<NonNull<T>> {
+        while let Some(node) = self.next {
+            self.next = unsafe { (*node.as_ptr()).next };
+            let item = self.list.item_of(node);
+
+            if (self.pred)(unsafe { &*item }) {
+                let before = self.list.len;
+                unsafe { self.list.unlink_node(node) };
+                self.list.notify_watermarks(before);
+                return NonNull::new(item);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.list.len))
+    }
+}
+
+impl<T, F: FnMut(&T) -> bool> core::iter::FusedIterator for ExtractIf<'_, T, F> {}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+    use crate::{RustyList, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn drain_yields_detached_elements_in_order() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut other = RustyList::<TestItem>::new();
+        let mut items = [make_item(1), make_item(2), make_item(3)];
+        items.iter_mut().for_each(|item| list.push(item));
+
+        let mut drain = list.drain();
+        assert_eq!(drain.len(), 3);
+        let values: Vec<i32> = drain
+            .by_ref()
+            .map(|mut item| {
+                let item = unsafe { item.as_mut() };
+                assert!(!item.node.is_linked());
+                other.push(item);
+                item.value
+            })
+            .collect();
+        drop(drain);
+
+        assert_eq!(values, [1, 2, 3]);
+        assert!(list.head.is_none() && list.len == 0);
+        assert_eq!(other.len, 3);
+    }
+
+    #[test]
+    fn dropped_drain_empties_the_list() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut items = [make_item(1), make_item(2), make_item(3)];
+        items.iter_mut().for_each(|item| list.push(item));
+
+        let first = list.drain().next().map(|item| unsafe { item.as_ref().value });
+        assert_eq!(first, Some(1));
+        assert!(list.head.is_none() && list.tail.is_none() && list.len == 0);
+        assert!(items.iter().all(|item| !item.node.is_linked()));
+    }
+
+    #[test]
+    fn extract_if_moves_matching_elements_and_keeps_the_rest() {
+        let mut pending = RustyList::<TestItem>::new();
+        let mut ready = RustyList::<TestItem>::new();
+        let mut items: Vec<TestItem> = (1..=6).map(make_item).collect();
+        items.iter_mut().for_each(|item| pending.push(item));
+
+        for mut item in pending.extract_if(|item| item.value % 2 == 0) {
+            ready.push(unsafe { item.as_mut() });
+        }
+
+        assert_eq!(pending.iter().map(|item| item.value).collect::<Vec<_>>(), [1, 3, 5]);
+        assert_eq!(ready.iter().map(|item| item.value).collect::<Vec<_>>(), [2, 4, 6]);
+        assert_eq!((pending.len, ready.len), (3, 3));
+        assert_eq!(pending.extract_if(|_| false).count(), 0);
+    }
+
+    #[test]
+    fn dropped_extract_if_leaves_unvisited_elements_linked() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut items = [make_item(1), make_item(2), make_item(3), make_item(4)];
+        items.iter_mut().for_each(|item| list.push(item));
+
+        let first = list.extract_if(|item| item.value > 1).next();
+        assert_eq!(first.map(|item| unsafe { item.as_ref().value }), Some(2));
+        assert_eq!(list.iter().map(|item| item.value).collect::<Vec<_>>(), [1, 3, 4]);
+        assert!(!items[1].node.is_linked());
+    }
+}
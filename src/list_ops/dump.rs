@@ -0,0 +1,250 @@
+// dump.rs
+// Debug dumps of the raw list structure, for chasing link corruption.
+use core::fmt;
+use core::mem::size_of;
+use crate::RustyList;
+use crate::list_ops::links::Link;
+
+/// Extra nodes a dump follows past `len`, so a list whose `len` is off still shows the
+/// nodes around the damage. The walk always stops after `len + DUMP_SLACK` nodes.
+pub const DUMP_SLACK: usize = 4;
+
+/// Number of leading element bytes `dump_nodes` prints per node.
+pub const DUMP_BYTES: usize = 16;
+
+impl<T> RustyList<T> {
+    /// Writes the list structure as a Graphviz DOT graph.
+    ///
+    /// Every node reached from `head` becomes a graph node named after its address and
+    /// labelled by `label_fn`, which writes the label text (quotes in it must be escaped).
+    /// Both link directions are drawn: solid `next` edges and dashed `prev` edges, so on a
+    /// healthy list every pair of neighbours shows a matching pair of arrows, and a link
+    /// that points anywhere else stands out. `head` and `tail` are drawn as boxes.
+    ///
+    /// The walk follows `next` from `head` for at most `len + DUMP_SLACK` nodes, so it ends
+    /// even on a cyclic list.
+    pub fn dump_dot<W: fmt::Write>(
+        &self,
+        w: &mut W,
+        mut label_fn: impl FnMut(&mut W, &T) -> fmt::Result,
+    ) -> fmt::Result {
+        let _guard = self.begin_traversal();
+        writeln!(w, "digraph rusty_list {{")?;
+        writeln!(w, "    head [shape=box];")?;
+        writeln!(w, "    tail [shape=box];")?;
+        if let Some(head) = self.head {
+            writeln!(w, "    head -> \"{:p}\";", head.as_ptr())?;
+        }
+        if let Some(tail) = self.tail {
+            writeln!(w, "    tail -> \"{:p}\";", tail.as_ptr())?;
+        }
+
+        let mut current = self.head;
+        for _ in 0..self.len.saturating_add(DUMP_SLACK) {
+            let Some(node) = current else { break };
+            let (prev, next) = unsafe { ((*node.as_ptr()).prev, (*node.as_ptr()).next) };
+
+            write!(w, "    \"{:p}\" [label=\"", node.as_ptr())?;
+            label_fn(w, unsafe { &*self.item_of(node) })?;
+            writeln!(w, "\"];")?;
+            write_dot_edge(w, node, next, "next", "solid")?;
+            write_dot_edge(w, node, prev, "prev", "dashed")?;
+
+            current = next;
+        }
+
+        writeln!(w, "}}")
+    }
+}
+
+impl<T> RustyList<T> {
+    /// Writes a raw dump of the list header and every node, one line each.
+    ///
+    /// Each node line shows the node's index in the walk, its address, its `prev` and
+    /// `next` links, its `dynamic` and `linked` flags, and the first `DUMP_BYTES` bytes of
+    /// the containing element in hex. Nothing is interpreted beyond following `next`, so
+    /// this is the dump to take from a wedged device; the walk stops after
+    /// `len + DUMP_SLACK` nodes even if the list loops.
+    ///
+    /// # Safety
+    /// The first `DUMP_BYTES` bytes of `T` (all of it, if `T` is smaller) must hold no
+    /// padding, since they are read as initialized `u8`s. A `#[repr(C)]` element that
+    /// starts with integer fields covering those bytes meets this.
+    pub unsafe fn dump_nodes<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        let _guard = self.begin_traversal();
+        writeln!(
+            w,
+            "list len={} offset={} head={:?} tail={:?}",
+            self.len,
+            self.offset,
+            self.head.map(|n| n.as_ptr()),
+            self.tail.map(|n| n.as_ptr()),
+        )?;
+
+        let mut current = self.head;
+        for index in 0..self.len.saturating_add(DUMP_SLACK) {
+            let Some(node) = current else { break };
+            let node_ref = unsafe { &*node.as_ptr() };
+
+            write!(
+                w,
+                "#{} node={:p} prev={:?} next={:?} dynamic={} linked={} bytes=",
+                index,
+                node.as_ptr(),
+                node_ref.prev.map(|n| n.as_ptr()),
+                node_ref.next.map(|n| n.as_ptr()),
+                node_ref.dynamic,
+                node_ref.linked,
+            )?;
+
+            let bytes = self.item_of(node) as *const u8;
+            for i in 0..size_of::<T>().min(DUMP_BYTES) {
+                // SAFETY: the caller guarantees these bytes are initialized; volatile so the
+                // read isn't reasoned away on a corrupted element
+                let byte = unsafe { bytes.add(i).read_volatile() };
+                write!(w, "{}{:02x}", if i == 0 { "" } else { " " }, byte)?;
+            }
+            writeln!(w)?;
+
+            current = node_ref.next;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes one link as an edge, skipping empty links.
+fn write_dot_edge<T, W: fmt::Write>(
+    w: &mut W,
+    from: Link<T>,
+    to: Option<Link<T>>,
+    label: &str,
+    style: &str,
+) -> fmt::Result {
+    match to {
+        Some(to) => writeln!(
+            w,
+            "    \"{:p}\" -> \"{:p}\" [label=\"{}\", style={}];",
+            from.as_ptr(),
+            to.as_ptr(),
+            label,
+            style,
+        ),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt::Write;
+    use std::format;
+    use std::string::String;
+    use crate::{RustyList, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn dump_dot_draws_both_link_directions() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut a = make_item(1);
+        let mut b = make_item(2);
+        list.push(&mut a);
+        list.push(&mut b);
+
+        let mut out = String::new();
+        list.dump_dot(&mut out, |w, item| write!(w, "v={}", item.value)).unwrap();
+
+        let (pa, pb) = (&a.node as *const RustyListNode<TestItem>, &b.node as *const RustyListNode<TestItem>);
+        assert!(out.starts_with("digraph rusty_list {\n"));
+        assert!(out.ends_with("}\n"));
+        assert!(out.contains(&format!("head -> \"{pa:p}\";")));
+        assert!(out.contains(&format!("tail -> \"{pb:p}\";")));
+        assert!(out.contains(&format!("\"{pa:p}\" [label=\"v=1\"];")));
+        assert!(out.contains(&format!("\"{pa:p}\" -> \"{pb:p}\" [label=\"next\", style=solid];")));
+        assert!(out.contains(&format!("\"{pb:p}\" -> \"{pa:p}\" [label=\"prev\", style=dashed];")));
+        assert_eq!(out.matches("->").count(), 4);
+    }
+
+    /// No padding in the first `DUMP_BYTES` bytes, as `dump_nodes` requires.
+    #[repr(C)]
+    struct PlainItem {
+        pub value: i32,
+        pub tag: u32,
+        pub extra: u64,
+        pub node: RustyListNode<PlainItem>,
+    }
+
+    impl HasRustyNode for PlainItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn make_plain(val: i32) -> PlainItem {
+        PlainItem {
+            value: val,
+            tag: 0,
+            extra: 0,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn dump_nodes_prints_links_flags_and_bytes() {
+        let mut list = RustyList::<PlainItem>::new();
+        let mut a = make_plain(0x0102_0304);
+        let mut b = make_plain(-1);
+        list.push(&mut a);
+        list.push(&mut b);
+
+        let mut out = String::new();
+        unsafe { list.dump_nodes(&mut out) }.unwrap();
+        let lines: std::vec::Vec<&str> = out.lines().collect();
+
+        let (pa, pb) = (&a.node as *const RustyListNode<PlainItem>, &b.node as *const RustyListNode<PlainItem>);
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("list len=2 offset=16 "));
+        assert!(lines[1].starts_with(&format!("#0 node={pa:p} prev=None next=Some({pb:p}) dynamic=false linked=true bytes=")));
+        assert!(lines[2].starts_with(&format!("#1 node={pb:p} prev=Some({pa:p}) next=None ")));
+
+        let first_word = if cfg!(target_endian = "little") { "04 03 02 01" } else { "01 02 03 04" };
+        assert!(lines[1].contains(&format!("bytes={first_word} ")));
+        assert!(lines[2].contains("bytes=ff ff ff ff "));
+        assert_eq!(lines[1].split("bytes=").nth(1).unwrap().split(' ').count(), super::DUMP_BYTES);
+    }
+
+    #[test]
+    fn dump_dot_terminates_on_a_cycle() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut a = make_item(1);
+        let mut b = make_item(2);
+        list.push(&mut a);
+        list.push(&mut b);
+
+        // corrupt the tail into pointing back at the head
+        unsafe { (*list.tail.unwrap().as_ptr()).next = list.head };
+
+        let mut out = String::new();
+        list.dump_dot(&mut out, |w, item| write!(w, "{}", item.value)).unwrap();
+        assert_eq!(out.matches("[label=\"1\"]").count(), 1 + super::DUMP_SLACK / 2);
+        assert!(out.ends_with("}\n"));
+    }
+}
@@ -0,0 +1,121 @@
+use crate::RustyList;
+
+impl<T> RustyList<T> {
+    /// Unlinks every element that `is_expired` reports as expired at `now`, in one pass.
+    ///
+    /// Each unlinked element is handed to `on_expired` once it is fully detached, in list
+    /// order. If the list has an `order_function` it is taken to order by deadline, so the
+    /// sweep stops at the first element that isn't expired and never looks past it; a
+    /// timeout manager only pays for the elements it actually expires. Lists without an
+    /// `order_function` are swept completely.
+    ///
+    /// # Returns
+    /// The number of elements removed.
+    pub fn remove_expired<N: Copy>(
+        &mut self,
+        now: N,
+        mut is_expired: impl FnMut(&T, N) -> bool,
+        mut on_expired: impl FnMut(*mut T),
+    ) -> usize {
+        self.assert_not_traversing("remove_expired");
+        let before = self.len;
+        let sorted = self.order_function.is_some();
+        let mut removed = 0;
+        let mut current = self.head;
+
+        while let Some(node) = current {
+            current = unsafe { (*node.as_ptr()).next };
+            let item = self.item_of(node);
+
+            if is_expired(unsafe { &*item }, now) {
+                unsafe { self.unlink_node(node) };
+                removed += 1;
+                self.run_callback(|| on_expired(item));
+            } else if sorted {
+                break;
+            }
+        }
+
+        self.notify_watermarks(before);
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec;
+    use std::vec::Vec;
+    use crate::{RustyList, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub deadline: u32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn cmp(a: *const TestItem, b: *const TestItem) -> i32 {
+        unsafe { (*a).deadline.cmp(&(*b).deadline) as i32 }
+    }
+
+    fn make_item(deadline: u32) -> TestItem {
+        TestItem {
+            deadline,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn sorted_sweep_stops_at_first_unexpired() {
+        let mut list = RustyList::<TestItem>::new_with_order(cmp);
+        let mut items = [make_item(30), make_item(10), make_item(20), make_item(40)];
+        for item in items.iter_mut() {
+            list.insert(item);
+        }
+
+        let mut checked = 0;
+        let mut expired = vec![];
+        let removed = list.remove_expired(
+            25,
+            |item, now| {
+                checked += 1;
+                item.deadline <= now
+            },
+            |item| {
+                assert!(!unsafe { &*item }.node.is_linked());
+                expired.push(unsafe { (*item).deadline });
+            },
+        );
+
+        assert_eq!(removed, 2);
+        assert_eq!(expired, vec![10, 20]);
+        assert_eq!(checked, 3);
+        assert_eq!(list.len, 2);
+    }
+
+    #[test]
+    fn unsorted_sweep_checks_everything() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut items = [make_item(30), make_item(10), make_item(50), make_item(20)];
+        for item in items.iter_mut() {
+            list.push(item);
+        }
+
+        let mut expired: Vec<u32> = vec![];
+        let removed = list.remove_expired(25, |item, now| item.deadline <= now, |item| {
+            expired.push(unsafe { (*item).deadline });
+        });
+
+        assert_eq!(removed, 2);
+        assert_eq!(expired, vec![10, 20]);
+        assert_eq!(unsafe { (*list.pop().unwrap()).deadline }, 30);
+        assert_eq!(unsafe { (*list.pop().unwrap()).deadline }, 50);
+        assert!(list.pop().is_none());
+    }
+}
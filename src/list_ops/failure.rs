@@ -0,0 +1,223 @@
+// failure.rs
+// Failure injection for testing how code copes with a panicking comparator or callback.
+use core::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use crate::{RustyList, ScrubCursor};
+
+/// A call counter that panics on one chosen call.
+///
+/// Put `tick()` at the top of a comparator, predicate or callback handed to a list
+/// operation, or wrap it with `wrap`/`wrap_cmp`, and call number `fail_at` (counting from
+/// 0) panics with "injected failure". `inject_failures` drives one of these per run.
+#[derive(Debug)]
+pub struct FailPoint {
+    fail_at: usize,
+    calls: Cell<usize>,
+}
+
+impl FailPoint {
+    /// Creates a fail point that panics on call number `fail_at`.
+    pub const fn new(fail_at: usize) -> Self {
+        Self { fail_at, calls: Cell::new(0) }
+    }
+
+    /// Counts a call, and panics if it is the one chosen to fail.
+    pub fn tick(&self) {
+        let call = self.calls.get();
+        self.calls.set(call.saturating_add(1));
+        if call == self.fail_at {
+            panic!("injected failure at call {call}");
+        }
+    }
+
+    /// Number of calls counted so far, including the one that panicked.
+    pub fn calls(&self) -> usize {
+        self.calls.get()
+    }
+
+    /// Returns `true` once the chosen call has been reached.
+    pub fn fired(&self) -> bool {
+        self.calls.get() > self.fail_at
+    }
+
+    /// Wraps a closure taking one argument by value, such as an `on_removed` callback or a
+    /// `shuffle` random source, to `tick` first. Predicates taking a reference call `tick`
+    /// themselves.
+    pub fn wrap<'a, A, R>(&'a self, mut f: impl FnMut(A) -> R + 'a) -> impl FnMut(A) -> R + 'a {
+        move |arg| {
+            self.tick();
+            f(arg)
+        }
+    }
+
+    /// Wraps a comparator to `tick` first.
+    pub fn wrap_cmp<'a, T>(
+        &'a self,
+        mut cmp: impl FnMut(*const T, *const T) -> i32 + 'a,
+    ) -> impl FnMut(*const T, *const T) -> i32 + 'a {
+        move |a, b| {
+            self.tick();
+            cmp(a, b)
+        }
+    }
+}
+
+/// Runs `op` repeatedly, failing a different comparator or callback call each time, and
+/// checks after every run that `list` is still well formed.
+///
+/// Run `k` hands `op` a `FailPoint` that panics on call `k`; `op` routes its closures
+/// through it. The panic is caught, then every node's links and `len` are checked. Runs
+/// go on until one finishes without reaching its fail point, so every call `op` makes
+/// gets to fail once. The list is not reset between runs: each run starts from whatever
+/// the previous one left behind, which is exactly the state a caller recovering from the
+/// panic would see. Each injected panic goes through the panic hook as usual.
+///
+/// ```ignore
+/// inject_failures(&mut list, |list, fail| list.sort_by(fail.wrap_cmp(by_deadline)));
+/// ```
+///
+/// # Returns
+/// The number of failures injected.
+///
+/// # Panics
+/// Panics if the list is malformed after a run, naming the call that failed.
+pub fn inject_failures<T>(list: &mut RustyList<T>, mut op: impl FnMut(&mut RustyList<T>, &FailPoint)) -> usize {
+    let mut fail_at = 0;
+    loop {
+        let fail = FailPoint::new(fail_at);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| op(list, &fail)));
+        check_well_formed(list, fail_at);
+
+        if result.is_ok() && !fail.fired() {
+            return fail_at;
+        }
+        fail_at += 1;
+    }
+}
+
+/// Panics unless every node is linked both ways and the nodes add up to `len`.
+fn check_well_formed<T>(list: &RustyList<T>, fail_at: usize) {
+    let mut cursor = ScrubCursor::new();
+    // one more than `len`, so a list with extra (or cyclic) links can't run forever
+    let report = list.scrub(list.len.saturating_add(1), &mut cursor);
+
+    if let Some(fault) = report.fault {
+        panic!("list malformed after failing call {fail_at}: {:?}", fault.kind);
+    }
+    if report.checked != list.len || (list.len > 0 && !report.completed_pass) {
+        panic!(
+            "list malformed after failing call {fail_at}: reached {} nodes, len is {}",
+            report.checked, list.len
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+    use super::*;
+    use crate::{MergeTie, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn cmp(a: *const TestItem, b: *const TestItem) -> i32 {
+        unsafe { (*a).value.cmp(&(*b).value) as i32 }
+    }
+
+    fn make_items(values: &[i32]) -> Vec<TestItem> {
+        values.iter().map(|&value| TestItem { value, node: RustyListNode::new() }).collect()
+    }
+
+    const SCRAMBLED: [i32; 9] = [5, 9, 1, 4, 4, 8, 2, 7, 3];
+
+    fn sorted_values(list: &RustyList<TestItem>) -> Vec<i32> {
+        let mut values: Vec<i32> = list.iter().map(|item| item.value).collect();
+        values.sort();
+        values
+    }
+
+    #[test]
+    fn fail_point_panics_on_the_chosen_call() {
+        let fail = FailPoint::new(2);
+        fail.tick();
+        fail.tick();
+        assert!(!fail.fired());
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| fail.tick())).is_err());
+        assert!(fail.fired());
+        assert_eq!(fail.calls(), 3);
+    }
+
+    #[test]
+    fn sort_and_select_survive_comparator_panics() {
+        let mut items = make_items(&SCRAMBLED);
+        let mut list = RustyList::<TestItem>::new();
+        items.iter_mut().for_each(|item| list.push(item));
+
+        let injected = inject_failures(&mut list, |list, fail| list.sort_by(fail.wrap_cmp(cmp)));
+        assert!(injected > 0);
+        assert_eq!(list.iter().map(|item| item.value).collect::<Vec<_>>(), [1, 2, 3, 4, 4, 5, 7, 8, 9]);
+
+        list.shuffle(|bound| 7 % bound);
+        inject_failures(&mut list, |list, fail| {
+            list.select_nth_by(4, fail.wrap_cmp(cmp));
+        });
+        inject_failures(&mut list, |list, fail| list.shuffle(fail.wrap(|bound| 5 % bound)));
+        assert_eq!(sorted_values(&list), [1, 2, 3, 4, 4, 5, 7, 8, 9]);
+    }
+
+    #[test]
+    fn filtering_survives_callback_panics() {
+        let mut items = make_items(&SCRAMBLED);
+        let mut list = RustyList::<TestItem>::new();
+        items.iter_mut().for_each(|item| list.push(item));
+
+        inject_failures(&mut list, |list, fail| {
+            list.keep_smallest_k_by(6, cmp, fail.wrap(|_| ()));
+        });
+        assert_eq!(list.len, 6);
+        inject_failures(&mut list, |list, fail| {
+            list.retain(|item| {
+                fail.tick();
+                item.value != 4
+            });
+        });
+        inject_failures(&mut list, |list, fail| {
+            list.extract_if(|item| {
+                fail.tick();
+                item.value > 2
+            })
+            .count();
+        });
+        assert_eq!(sorted_values(&list), [1, 2]);
+        inject_failures(&mut list, |list, fail| list.drain().for_each(fail.wrap(|_| ())));
+        assert!(list.head.is_none() && list.len == 0);
+    }
+
+    #[test]
+    fn merge_survives_comparator_and_combine_panics() {
+        let mut ours = make_items(&[1, 3, 5, 7]);
+        let mut theirs = make_items(&[2, 3, 6, 7, 8]);
+        let mut a = RustyList::<TestItem>::new();
+        let mut b = RustyList::<TestItem>::new();
+        ours.iter_mut().for_each(|item| a.push(item));
+        theirs.iter_mut().for_each(|item| b.push(item));
+
+        inject_failures(&mut a, |a, fail| {
+            a.merge_by(&mut b, fail.wrap_cmp(cmp), |_, _| MergeTie::Coalesce, |_, _| fail.tick());
+            check_well_formed(&b, fail.calls());
+        });
+        assert!(b.head.is_none());
+        assert_eq!(a.len + 2, 9);
+    }
+}
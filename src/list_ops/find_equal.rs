@@ -1,25 +1,193 @@
+use core::fmt;
 use crate::RustyList;
 use crate::HasRustyNode;
 use crate::rusty_container_of;
+use crate::list_ops::borrow_flag::TraversalGuard;
+use crate::list_ops::links::Link;
+
+/// Returned by `try_find_equal` when the list has no `order_function` to compare with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoComparator;
+
+impl fmt::Display for NoComparator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RustyList has no order_function")
+    }
+}
 
 impl<T: HasRustyNode> RustyList<T> {
     /// Safe version of `find_equal`, compares items using the order function.
-    pub fn find_equal(&self, target: &T) -> Option<&mut T> {
+    pub fn find_equal(&self, target: &T) -> Option<&T> {
+        let raw_ptr = target as *const T;
+        unsafe { self.find_equal_raw(raw_ptr).map(|p| &*p) }
+    }
+
+    /// Like `find_equal`, but returns the element mutably.
+    pub fn find_equal_mut(&mut self, target: &T) -> Option<&mut T> {
         let raw_ptr = target as *const T;
         unsafe { self.find_equal_raw(raw_ptr).map(|p| &mut *p) }
     }
 
+    /// Like `find_equal`, but tells a missing key apart from a missing comparator.
+    ///
+    /// `find_equal` returns `None` in both cases, which makes a list created with `new`
+    /// instead of `new_with_order` look like it simply never contains the key.
+    pub fn try_find_equal(&mut self, target: &T) -> Result<Option<&mut T>, NoComparator> {
+        if self.order_function.is_none() {
+            return Err(NoComparator);
+        }
+        Ok(self.find_equal_mut(target))
+    }
+
+    /// Like `find_equal`, but scans from the tail towards the head.
+    ///
+    /// Returns the last matching element, which makes it the cheaper choice for keys that
+    /// sit near the tail (large keys in a sorted list, recent entries in an appended one).
+    pub fn find_equal_from_tail(&self, target: &T) -> Option<&T> {
+        let raw_ptr = target as *const T;
+        unsafe { self.find_equal_from_tail_raw(raw_ptr).map(|p| &*p) }
+    }
+
+    /// Like `find_equal_from_tail`, but returns the element mutably.
+    pub fn find_equal_from_tail_mut(&mut self, target: &T) -> Option<&mut T> {
+        let raw_ptr = target as *const T;
+        unsafe { self.find_equal_from_tail_raw(raw_ptr).map(|p| &mut *p) }
+    }
+
+    /// Like `find_equal`, but uses the sort order to bound the search.
+    ///
+    /// The list is assumed to be in `order_function` order, as maintained by `insert`.
+    /// Keys outside the head..tail range are rejected after two comparisons; otherwise the
+    /// list is walked from both ends at once, stopping as soon as either side passes the
+    /// key, so the cost is bounded by the distance to the nearer end. When several
+    /// elements are equal, any one of them may be returned.
+    pub fn find_equal_auto(&self, target: &T) -> Option<&T> {
+        let raw_ptr = target as *const T;
+        unsafe { self.find_equal_auto_raw(raw_ptr).map(|p| &*p) }
+    }
+
+    /// Like `find_equal_auto`, but returns the element mutably.
+    pub fn find_equal_auto_mut(&mut self, target: &T) -> Option<&mut T> {
+        let raw_ptr = target as *const T;
+        unsafe { self.find_equal_auto_raw(raw_ptr).map(|p| &mut *p) }
+    }
+
+    /// Iterates over every element that compares equal to `target`, head to tail.
+    ///
+    /// The list is assumed to be in `order_function` order, as maintained by `insert`, so
+    /// duplicates sit next to each other: the walk stops at the first element greater than
+    /// `target`, right after the last match, instead of going on to the tail. Yields
+    /// nothing if the list has no `order_function`.
+    pub fn find_all_equal<'a>(&'a self, target: &'a T) -> FindAllEqual<'a, T> {
+        let cmp = self.comparator_for("find_all_equal");
+        FindAllEqual {
+            list: self,
+            next: cmp.and(self.head),
+            target,
+            cmp: cmp.unwrap_or(|_, _| 0),
+            _guard: self.begin_traversal(),
+            #[cfg(feature = "metrics")]
+            scan: self.begin_scan(crate::list_ops::metrics::ScanKind::Find),
+        }
+    }
+
+    /// Internal unsafe implementation of find_equal_from_tail
+    unsafe fn find_equal_from_tail_raw(&self, target: *const T) -> Option<*mut T> {
+        let _guard = self.begin_traversal();
+        if target.is_null() || self.len == 0 {
+            return None;
+        }
+        let cmp_fn = self.comparator_for("find_equal")?;
+        #[cfg(feature = "metrics")]
+        let mut scan = self.begin_scan(crate::list_ops::metrics::ScanKind::Find);
+
+        let mut current = self.tail.map(|nn| nn.as_ptr());
+
+        while let Some(node_ptr) = current {
+            let current_item = unsafe{rusty_container_of(node_ptr, self.offset)};
+
+            #[cfg(feature = "metrics")]
+            scan.tick();
+            if cmp_fn(current_item, target) == 0 {
+                return Some(current_item as *mut T);
+            }
+
+            current = unsafe{(*node_ptr).prev.map(|nn| nn.as_ptr())};
+        }
+
+        None
+    }
+
+    /// Internal unsafe implementation of find_equal_auto
+    unsafe fn find_equal_auto_raw(&self, target: *const T) -> Option<*mut T> {
+        let _guard = self.begin_traversal();
+        if target.is_null() || self.len == 0 {
+            return None;
+        }
+        let cmp_fn = self.comparator_for("find_equal")?;
+        #[cfg(feature = "metrics")]
+        let mut scan = self.begin_scan(crate::list_ops::metrics::ScanKind::Find);
+
+        let mut front = self.head?;
+        let mut back = self.tail?;
+        let mut steps = self.len.div_ceil(2);
+
+        loop {
+            let front_item = unsafe{rusty_container_of(front.as_ptr(), self.offset)};
+            #[cfg(feature = "metrics")]
+            scan.tick();
+            match cmp_fn(front_item, target) {
+                0 => return Some(front_item as *mut T),
+                // sorted: everything from here on is larger
+                c if c > 0 => return None,
+                _ => {}
+            }
+
+            let back_item = unsafe{rusty_container_of(back.as_ptr(), self.offset)};
+            #[cfg(feature = "metrics")]
+            scan.tick();
+            match cmp_fn(back_item, target) {
+                0 => return Some(back_item as *mut T),
+                // sorted: everything before here is smaller
+                c if c < 0 => return None,
+                _ => {}
+            }
+
+            steps -= 1;
+            if steps == 0 {
+                return None;
+            }
+
+            front = unsafe{(*front.as_ptr()).next}?;
+            back = unsafe{(*back.as_ptr()).prev}?;
+        }
+    }
+
+    /// Returns the `order_function`, warning when a lookup is made without one.
+    fn comparator_for(&self, _op: &str) -> Option<fn(*const T, *const T) -> i32> {
+        if self.order_function.is_none() {
+            rusty_warn!("RustyList::{}: no order_function set, nothing can match", _op);
+        }
+        self.order_function
+    }
+
     /// Internal unsafe implementation of find_equal
     unsafe fn find_equal_raw(&self, target: *const T) -> Option<*mut T> {
-        if target.is_null() || self.len == 0 || self.order_function.is_none() {
+        let _guard = self.begin_traversal();
+        if target.is_null() || self.len == 0 {
             return None;
         }
+        let cmp_fn = self.comparator_for("find_equal")?;
+        #[cfg(feature = "metrics")]
+        let mut scan = self.begin_scan(crate::list_ops::metrics::ScanKind::Find);
 
         let mut current = self.head.map(|nn| nn.as_ptr());
 
         while let Some(node_ptr) = current {
             let current_item = unsafe{rusty_container_of(node_ptr, self.offset)};
-            let cmp = self.order_function.unwrap()(current_item, target);
+            #[cfg(feature = "metrics")]
+            scan.tick();
+            let cmp = cmp_fn(current_item, target);
 
             if cmp == 0 {
                 return Some(current_item as *mut T);
@@ -32,6 +200,41 @@ impl<T: HasRustyNode> RustyList<T> {
     }
 }
 
+/// Iterator returned by `RustyList::find_all_equal`.
+pub struct FindAllEqual<'a, T> {
+    list: &'a RustyList<T>,
+    next: Option<Link<T>>,
+    target: &'a T,
+    cmp: fn(*const T, *const T) -> i32,
+    _guard: TraversalGuard<'a, T>,
+    #[cfg(feature = "metrics")]
+    scan: crate::list_ops::metrics::ScanGuard<'a, T>,
+}
+
+impl<'a, T> Iterator for FindAllEqual<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        while let Some(node) = self.next {
+            let item = self.list.item_of(node);
+            #[cfg(feature = "metrics")]
+            self.scan.tick();
+            match (self.cmp)(item, self.target) {
+                0 => {
+                    self.next = unsafe { (*node.as_ptr()).next };
+                    return Some(unsafe { &*item });
+                }
+                // sorted: everything from here on is larger
+                c if c > 0 => self.next = None,
+                _ => self.next = unsafe { (*node.as_ptr()).next },
+            }
+        }
+        None
+    }
+}
+
+impl<T> core::iter::FusedIterator for FindAllEqual<'_, T> {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -98,6 +301,99 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn find_from_tail_returns_last_match() {
+        let mut list = RustyList::<TestItem>::new_with_order(cmp);
+        let mut a = make_item(1);
+        let mut b = make_item(2);
+        let mut c = make_item(2);
+        let mut d = make_item(3);
+
+        list.insert(&mut a);
+        list.insert(&mut b);
+        list.insert(&mut c);
+        list.insert(&mut d);
+
+        let target = make_item(2);
+        let found = list.find_equal_from_tail_mut(&target).unwrap() as *mut TestItem;
+        assert_eq!(found, &mut c as *mut TestItem);
+
+        let found = list.find_equal_mut(&target).unwrap() as *mut TestItem;
+        assert_eq!(found, &mut b as *mut TestItem);
+
+        assert!(list.find_equal_from_tail(&make_item(9)).is_none());
+    }
+
+    #[test]
+    fn find_auto_finds_every_key_and_rejects_missing() {
+        let mut list = RustyList::<TestItem>::new_with_order(cmp);
+        let mut items = [make_item(10), make_item(20), make_item(30), make_item(40), make_item(50)];
+        for item in items.iter_mut() {
+            list.insert(item);
+        }
+
+        for value in [10, 20, 30, 40, 50] {
+            let found = list.find_equal_auto(&make_item(value));
+            assert_eq!(found.map(|f| f.value), Some(value));
+        }
+
+        for value in [5, 15, 35, 55] {
+            assert!(list.find_equal_auto(&make_item(value)).is_none());
+        }
+
+        let found = list.find_equal_auto_mut(&make_item(40)).unwrap() as *mut TestItem;
+        assert_eq!(found, &mut items[3] as *mut TestItem);
+    }
+
+    #[test]
+    fn find_auto_without_comparator_or_items() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut a = make_item(1);
+        list.push(&mut a);
+        assert!(list.find_equal_auto(&make_item(1)).is_none());
+        assert!(list.find_equal_from_tail(&make_item(1)).is_none());
+
+        let empty = RustyList::<TestItem>::new_with_order(cmp);
+        assert!(empty.find_equal_auto(&make_item(1)).is_none());
+    }
+
+    #[test]
+    fn try_find_reports_missing_comparator() {
+        let mut unordered = RustyList::<TestItem>::new();
+        let mut a = make_item(1);
+        unordered.push(&mut a);
+        assert_eq!(unordered.try_find_equal(&make_item(1)).map(|f| f.map(|f| f.value)), Err(NoComparator));
+
+        let mut list = RustyList::<TestItem>::new_with_order(cmp);
+        let mut b = make_item(2);
+        list.insert(&mut b);
+        assert_eq!(list.try_find_equal(&make_item(2)).map(|f| f.map(|f| f.value)), Ok(Some(2)));
+        assert_eq!(list.try_find_equal(&make_item(3)).map(|f| f.map(|f| f.value)), Ok(None));
+    }
+
+    #[test]
+    fn find_all_yields_every_duplicate_and_stops_past_them() {
+        let mut list = RustyList::<TestItem>::new_with_order(cmp);
+        let mut items = [make_item(3), make_item(1), make_item(2), make_item(2), make_item(4), make_item(2)];
+        for item in items.iter_mut() {
+            list.insert(item);
+        }
+
+        let found: std::vec::Vec<*const TestItem> =
+            list.find_all_equal(&make_item(2)).map(|item| item as *const TestItem).collect();
+        assert_eq!(found, [&items[2] as *const TestItem, &items[3], &items[5]]);
+        // 1, the three 2s and the 3 that ends the run; 4 is never compared
+        #[cfg(feature = "metrics")]
+        assert_eq!(list.stats().comparisons.find, 5);
+        assert_eq!(list.find_all_equal(&make_item(5)).count(), 0);
+
+        // nothing compares equal without a comparator
+        let mut unordered = RustyList::<TestItem>::new();
+        let mut a = make_item(1);
+        unordered.push(&mut a);
+        assert_eq!(unordered.find_all_equal(&make_item(1)).count(), 0);
+    }
+
     #[test]
     fn find_in_empty_list() {
         let list = RustyList::<TestItem>::new_with_order(cmp);
@@ -0,0 +1,105 @@
+use core::fmt;
+use crate::RustyList;
+
+impl<T> RustyList<T> {
+    /// Streams the elements into `writer`, head to tail, with `sep` between them.
+    ///
+    /// Each element is written by `fmt_fn`, so a status line like `"1, 2, 3"` can go
+    /// straight to a UART or a fixed buffer without building an intermediate string.
+    /// Stops at the first error from the writer or `fmt_fn` and returns it.
+    pub fn write_joined<W: fmt::Write>(
+        &self,
+        writer: &mut W,
+        sep: &str,
+        mut fmt_fn: impl FnMut(&mut W, &T) -> fmt::Result,
+    ) -> fmt::Result {
+        let _guard = self.begin_traversal();
+        let mut current = self.head;
+
+        while let Some(node) = current {
+            if current != self.head {
+                writer.write_str(sep)?;
+            }
+            fmt_fn(writer, unsafe { &*self.item_of(node) })?;
+            current = unsafe { (*node.as_ptr()).next };
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt::{self, Write};
+    use std::string::String;
+    use crate::{RustyList, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn write_joined_separates_elements() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut out = String::new();
+        list.write_joined(&mut out, ", ", |w, item| write!(w, "{}", item.value)).unwrap();
+        assert_eq!(out, "");
+
+        let mut a = make_item(1);
+        let mut b = make_item(2);
+        let mut c = make_item(3);
+        list.push(&mut a);
+        list.push(&mut b);
+        list.push(&mut c);
+
+        list.write_joined(&mut out, ", ", |w, item| write!(w, "{}", item.value)).unwrap();
+        assert_eq!(out, "1, 2, 3");
+    }
+
+    /// Accepts at most 4 bytes, like a small fixed UART buffer.
+    struct Fixed {
+        buf: [u8; 4],
+        len: usize,
+    }
+
+    impl fmt::Write for Fixed {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.len + s.len();
+            if end > self.buf.len() {
+                return Err(fmt::Error);
+            }
+            self.buf[self.len..end].copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn write_joined_stops_at_writer_error() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut a = make_item(10);
+        let mut b = make_item(20);
+        list.push(&mut a);
+        list.push(&mut b);
+
+        let mut out = Fixed { buf: [0; 4], len: 0 };
+        assert!(list.write_joined(&mut out, "|", |w, item| write!(w, "{}", item.value)).is_err());
+        assert_eq!(&out.buf[..out.len], b"10|");
+    }
+}
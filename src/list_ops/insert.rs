@@ -1,5 +1,6 @@
-use crate::{RustyList, RustyListNode, rusty_container_of};
-use core::ptr::NonNull;
+use crate::{RustyList, RustyListNode, LenOverflow};
+use crate::list_ops::links::Link;
+use core::mem::MaybeUninit;
 
 impl<T> RustyList<T> {
     /// Inserts a new node into the `RustyList` at the appropriate position based on the
@@ -44,141 +45,132 @@ impl<T> RustyList<T> {
         }
     }
 
-    unsafe fn insert_node_at_head(&mut self, node: *mut RustyListNode<T>) {
-        let new_node = unsafe { NonNull::new_unchecked(node) };
-        if self.len == 0 {
-            self.head = Some(new_node);
-            self.tail = Some(new_node);
-            unsafe {
-                (*node).prev = None;
-                (*node).next = None
-            };
-        } else {
-            // set the next pointer of the new node to the current head
-            unsafe { (*node).next = self.head };
-            // set the prev pointer of the current head to the new node
-            unsafe { (*self.head.unwrap().as_ptr()).prev = Some(new_node) };
-            // set the head pointer of the list to the new node
-            self.head = Some(new_node);
-            // set the prev pointer of the new node to None
-            unsafe { (*node).prev = None };
-        }
+    /// Like `insert`, but leaves `item` unlinked and fails if the length can't be counted;
+    /// see `try_push`.
+    pub fn try_insert(&mut self, item: &mut T) -> Result<(), LenOverflow> {
+        self.check_len_room()?;
+        self.insert(item);
+        Ok(())
     }
 
-    unsafe fn _insert_node_at_tail(&mut self, node: *mut RustyListNode<T>) {
-        let new_node = unsafe { NonNull::new_unchecked(node) };
+    /// Initializes the node embedded in `slot` in place and inserts the element.
+    ///
+    /// This is for pool/arena code that builds elements in raw storage: the node is
+    /// written straight into `slot` (never copied from the stack and never read first),
+    /// then the element is inserted like `insert` does.
+    ///
+    /// # Safety
+    /// - Every field of `T` other than the embedded `RustyListNode<T>` must already be
+    ///   initialized, since the order function may read the element.
+    /// - `slot` must stay valid for as long as the element is in the list.
+    pub unsafe fn insert_uninit_slot<'a>(&mut self, slot: &'a mut MaybeUninit<T>) -> &'a mut T {
+        let item = slot.as_mut_ptr();
+        let node_ptr = unsafe { (item as *mut u8).add(self.offset) } as *mut MaybeUninit<RustyListNode<T>>;
+        RustyListNode::init_in(unsafe { &mut *node_ptr });
 
-     
+        unsafe {
+            self.insert_raw(item);
+            slot.assume_init_mut()
+        }
+    }
 
-        // set the next pointer of the current tail node to the new node
-        unsafe { (*self.tail.unwrap().as_ptr()).next = Some(new_node) };
-        // set the prev pointer of the new node to the current tail
-        unsafe { (*node).prev = Some(self.tail.unwrap()) };
-        // set the tail of the list to the new node
-        self.tail = Some(new_node);
-        // set the next pointer of the new node to None
-        unsafe { (*node).next = None };
+    /// Inserts `item` at the position given by `cmp` for this call only.
+    ///
+    /// `cmp` follows the `order_function` convention and the list's own `order_function` is
+    /// ignored: the element goes after the last element that does not compare greater than
+    /// it, checking the tail first so in-order arrivals stay O(1). Meant for the occasional
+    /// element placed by a different criterion (an urgent job jumping a deadline-ordered
+    /// queue); later `insert`s still use the `order_function` and assume it holds.
+    pub fn insert_sorted_by(&mut self, mut cmp: impl FnMut(*const T, *const T) -> i32, item: &mut T) {
+        self.assert_not_traversing("insert");
+        let before = self.len;
+        let item = item as *mut T;
+        let node = self.claim_node(item, "insert_sorted_by");
 
-        // if the list has only one node, set the heads next pointer to the new node
-        if self.len == 1 {
-            unsafe { (*self.head.unwrap().as_ptr()).next = Some(new_node) };
+        let mut prev = self.tail;
+        if let Some(tail) = self.tail
+            && cmp(item, self.item_of(tail)) < 0
+        {
+            // walk from the head to the first element that compares greater
+            prev = None;
+            let mut current = self.head;
+            while let Some(c) = current {
+                if cmp(item, self.item_of(c)) < 0 {
+                    break;
+                }
+                prev = Some(c);
+                current = unsafe { (*c.as_ptr()).next };
+            }
         }
+
+        unsafe { self.link_after(prev, node) };
+        self.notify_watermarks(before);
     }
 
     /// Unsafe internal function to insert a raw pointer into the `RustyList`.
     unsafe fn insert_raw(&mut self, item: *mut T) {
+        self.assert_not_traversing("insert");
         if item.is_null() {
             return;
         }
+        let before = self.len;
+        let node = self.claim_node(item, "insert");
 
-        // SAFETY: We are assuming that the item is valid and properly aligned.
-        // We are also assuming that the offset is valid and that the item is a valid pointer to T.
-        let node_ptr = unsafe { (item as *mut u8).add(self.offset) } as *mut RustyListNode<T>;
-        let item_container = unsafe { rusty_container_of(node_ptr, self.offset) };
+        let prev = match self.order_function {
+            // if there is no order function the new node is appended at the tail
+            None => self.tail,
+            Some(order) => self.sorted_prev(order, item),
+        };
 
-        let node = unsafe { &mut *node_ptr };
-        node.prev = None;
-        node.next = None;
+        unsafe { self.link_after(prev, node) };
+        self.paranoid_check_sorted(node);
+        self.notify_watermarks(before);
+    }
 
-        let new_node = unsafe { NonNull::new_unchecked(node_ptr) };
+    /// Finds the node `item` goes after in `order` order, after any elements equal to it.
+    ///
+    /// The tail and the head are checked first, so arrivals at either end cost one or two
+    /// comparisons; otherwise the list is walked from the head.
+    fn sorted_prev(&self, order: fn(*const T, *const T) -> i32, item: *const T) -> Option<Link<T>> {
+        let (Some(head), Some(tail)) = (self.head, self.tail) else {
+            return None;
+        };
 
-        if self.len == 0 {
-            // List is empty
-            self.head = Some(new_node);
-            self.tail = Some(new_node);
+        // counts the comparisons for the `metrics` histograms
+        let mut _comparisons = 0usize;
+        let mut cmp_fn = |a: *const T, b: *const T| {
+            _comparisons = _comparisons.saturating_add(1);
+            order(a, b)
+        };
 
-            // set the next and prev pointers to None
-            node.prev = None;
-            node.next = None;
+        let prev = if cmp_fn(item, self.item_of(tail)) > 0 {
+            Some(tail)
+        } else if cmp_fn(item, self.item_of(head)) < 0 {
+            None
         } else {
-            // list is not empty find the correct position to insert the new node
-            let cmp_fn = self.order_function;
-            let mut current = self.head.unwrap().as_ptr();
-
-            // if there is no order function OR the new node should be inserted at the tail
-            if cmp_fn.is_none() || {
-                let tail_node = self.tail.unwrap().as_ptr();
-                let tail_item = unsafe { rusty_container_of(tail_node, self.offset) };
-                cmp_fn.unwrap()(item_container, tail_item) > 0
-            } {
-                // Insert at tail
-                unsafe { self._insert_node_at_tail(node_ptr) };
-            }
-            // if we have an order function and the new node should be inserted at the head
-            else if {
-                let head_node = self.head.unwrap().as_ptr();
-                let head_item = unsafe { rusty_container_of(head_node, self.offset) };
-                cmp_fn.unwrap()(item_container, head_item) < 0
-            } {
-                // Insert at head
-                unsafe { self.insert_node_at_head(node_ptr) };
-            }
-            // other wise we are inserting in the middle of the list
-            else {
-                while !current.is_null() {
-                    // look for a position to insert the new node
-                    let current_item = unsafe { rusty_container_of(current, self.offset) };
-
-                    // if the new item is less than the current item, break the loop
-                    if cmp_fn.unwrap()(item_container, current_item) < 0 {
-                        break;
-                    }
-                    // move to the next node
-                    current = unsafe {
-                        match (*current).next {
-                            Some(next_node) => next_node.as_ptr(),
-                            None => core::ptr::null_mut(),
-                        }
-                    };
-                }
-
-                // check to see if current is null, this should not happen but just in case
-                if current.is_null() {
-                    // Insert at tail
-                    unsafe { self._insert_node_at_tail(node_ptr) };
-                } else {
-                    // Insert in the middle
-                    let prev_ptr = unsafe { (*current).prev.unwrap().as_ptr() };
-                    // set the pointer of the new node to the current node
-                    unsafe { (*node_ptr).next = Some(NonNull::new_unchecked(current)) };
-                    // set the prev pointer of the new node to the previous node
-                    unsafe { (*node_ptr).prev = Some(NonNull::new_unchecked(prev_ptr)) };
-                    // set the next pointer of the previous node to the new node
-                    unsafe { (*prev_ptr).next = Some(new_node) };
-                    // set the prev pointer of the current node to the new node
-                    unsafe { (*current).prev = Some(new_node) };
+            // walk to the first element that compares greater
+            let mut prev = None;
+            let mut current = Some(head);
+            while let Some(c) = current {
+                if cmp_fn(item, self.item_of(c)) < 0 {
+                    break;
                 }
+                prev = Some(c);
+                current = unsafe { (*c.as_ptr()).next };
             }
-        }
-        self.len += 1;
+            prev
+        };
+
+        #[cfg(feature = "metrics")]
+        self.record_scan(crate::list_ops::metrics::ScanKind::Insert, _comparisons);
+        prev
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{HasRustyNode, RustyList, RustyListNode, rusty_offset};
-    use core::marker::PhantomData;
+    use crate::{HasRustyNode, RustyList, RustyListNode, rusty_offset, rusty_container_of};
     use std::vec;
 
     #[repr(C)]
@@ -208,43 +200,21 @@ mod tests {
 
     #[test]
     fn insert_sorted_into_list() {
-        let mut list = RustyList::<TestItem> {
-            len: 0,
-            dynamic: false,
-            head: None,
-            tail: None,
-            offset: TestItem::rusty_offset(),
-            order_function: Some(cmp),
-        };
+        let mut list = RustyList::<TestItem>::new_with_order(cmp);
 
         let mut one = TestItem {
             value: 1,
-            node: RustyListNode {
-                dynamic: false,
-                _marker: PhantomData,
-                prev: None,
-                next: None,
-            },
+            node: RustyListNode::new(),
         };
 
         let mut three = TestItem {
             value: 3,
-            node: RustyListNode {
-                dynamic: false,
-                _marker: PhantomData,
-                prev: None,
-                next: None,
-            },
+            node: RustyListNode::new(),
         };
 
         let mut two = TestItem {
             value: 2,
-            node: RustyListNode {
-                dynamic: false,
-                _marker: PhantomData,
-                prev: None,
-                next: None,
-            },
+            node: RustyListNode::new(),
         };
 
         list.insert(&mut three);
@@ -265,4 +235,57 @@ mod tests {
 
         assert_eq!(values, vec![1, 2, 3]);
     }
+
+    #[test]
+    fn insert_sorted_by_ignores_order_function() {
+        let mut list = RustyList::<TestItem>::new_with_order(cmp);
+        let mut items = [10, 20, 30].map(|value| TestItem { value, node: RustyListNode::new() });
+        for item in items.iter_mut() {
+            list.insert(item);
+        }
+
+        // descending for this call: 99 goes in front of the smaller head
+        let mut urgent = TestItem { value: 99, node: RustyListNode::new() };
+        list.insert_sorted_by(|a, b| cmp(b, a), &mut urgent);
+        let mut late = TestItem { value: 40, node: RustyListNode::new() };
+        list.insert_sorted_by(cmp, &mut late);
+
+        let mut cursor = list.head;
+        let mut values = std::vec::Vec::new();
+        while let Some(ptr) = cursor {
+            let item = unsafe { rusty_container_of(ptr.as_ptr(), list.offset) };
+            values.push(unsafe { (*item).value });
+            cursor = unsafe { (*ptr.as_ptr()).next_node() };
+        }
+
+        assert_eq!(values, vec![99, 10, 20, 30, 40]);
+        assert_eq!(list.len, 5);
+        assert!(urgent.node.is_linked());
+    }
+
+    #[test]
+    fn insert_uninit_slot_initializes_node_in_place() {
+        use core::ptr::addr_of_mut;
+
+        let mut list = RustyList::<TestItem>::new_with_order(cmp);
+        let mut pool: [MaybeUninit<TestItem>; 3] = [const { MaybeUninit::uninit() }; 3];
+
+        for (slot, value) in pool.iter_mut().zip([20, 30, 10]) {
+            unsafe { addr_of_mut!((*slot.as_mut_ptr()).value).write(value) };
+            let item = unsafe { list.insert_uninit_slot(slot) };
+            assert!(item.node.is_linked());
+        }
+
+        assert_eq!(list.len, 3);
+
+        let mut cursor = list.head;
+        let mut values = std::vec::Vec::new();
+        while let Some(ptr) = cursor {
+            let item = unsafe { rusty_container_of(ptr.as_ptr(), list.offset) };
+            values.push(unsafe { (*item).value });
+            cursor = unsafe { (*ptr.as_ptr()).next_node() };
+        }
+
+        assert_eq!(values, vec![10, 20, 30]);
+    }
 }
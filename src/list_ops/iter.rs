@@ -0,0 +1,337 @@
+// iter.rs
+// Borrowing iteration over the elements.
+use core::marker::PhantomData;
+use crate::RustyList;
+use crate::list_ops::borrow_flag::TraversalGuard;
+use crate::list_ops::links::Link;
+
+impl<T> RustyList<T> {
+    /// Iterates over the elements from head to tail.
+    ///
+    /// The order is guaranteed: it is the list order, i.e. `order_function` order for a
+    /// list maintained with `insert`, and push order for one maintained with `push`.
+    /// Downstream code may rely on it.
+    ///
+    /// This is the safe replacement for walking `next_node()` by hand and recovering each
+    /// element with `rusty_container_of`: the list's own node offset is used, and the
+    /// shared borrow keeps the list from being changed while the iterator is alive.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            list: self,
+            next: self.head,
+            back: self.tail,
+            remaining: self.len,
+            _guard: self.begin_traversal(),
+        }
+    }
+
+    /// Iterates over the elements from tail to head, most recently pushed first for a
+    /// list maintained with `push`. Same as `iter().rev()`.
+    pub fn iter_rev(&self) -> core::iter::Rev<Iter<'_, T>> {
+        self.iter().rev()
+    }
+
+    /// Iterates mutably over the elements from head to tail, in the same order as `iter`.
+    ///
+    /// The list stays mutably borrowed while the iterator is alive, so it can't be
+    /// restructured mid-walk. The elements' nodes must not be written through the
+    /// references, e.g. by assigning or swapping whole elements; implement
+    /// `RustyPayload` and use `iter_payload_mut` to rule that out.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            next: self.head,
+            back: self.tail,
+            remaining: self.len,
+            offset: self.offset,
+            _guard: self.begin_traversal(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Iterates over every element exactly once, in no particular order.
+    ///
+    /// Today this walks head to tail like `iter`, but only the set of elements is
+    /// guaranteed: list layouts that have a faster relaxed-order walk (sharded or unrolled
+    /// variants) are free to use it. Use this when the order doesn't matter, e.g. for
+    /// sums, counts or bulk updates.
+    pub fn iter_unordered(&self) -> UnorderedIter<'_, T> {
+        UnorderedIter { inner: self.iter() }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RustyList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut RustyList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+/// Iterator returned by `RustyList::iter`, head to tail.
+pub struct Iter<'a, T> {
+    list: &'a RustyList<T>,
+    next: Option<Link<T>>,
+    back: Option<Link<T>>,
+    remaining: usize,
+    _guard: TraversalGuard<'a, T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        // the two ends meet when `remaining` runs out
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = self.next?;
+        self.next = unsafe { (*node.as_ptr()).next };
+        self.remaining -= 1;
+        Some(unsafe { &*self.list.item_of(node) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = self.back?;
+        self.back = unsafe { (*node.as_ptr()).prev };
+        self.remaining -= 1;
+        Some(unsafe { &*self.list.item_of(node) })
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> core::iter::FusedIterator for Iter<'_, T> {}
+
+/// Cloning starts a second traversal from the same position.
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Iter {
+            list: self.list,
+            next: self.next,
+            back: self.back,
+            remaining: self.remaining,
+            _guard: self.list.begin_traversal(),
+        }
+    }
+}
+
+/// Iterator returned by `RustyList::iter_mut`, head to tail.
+pub struct IterMut<'a, T> {
+    next: Option<Link<T>>,
+    back: Option<Link<T>>,
+    remaining: usize,
+    offset: usize,
+    _guard: TraversalGuard<'a, T>,
+    _marker: PhantomData<&'a mut T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = self.next?;
+        self.next = unsafe { (*node.as_ptr()).next };
+        self.remaining -= 1;
+        Some(unsafe { self.item_mut(node) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = self.back?;
+        self.back = unsafe { (*node.as_ptr()).prev };
+        self.remaining -= 1;
+        Some(unsafe { self.item_mut(node) })
+    }
+}
+
+impl<'a, T> IterMut<'a, T> {
+    /// # Safety
+    /// `node` must be in the list and not yielded before: each element is handed out once
+    /// from either end, so the borrows never overlap.
+    unsafe fn item_mut(&self, node: Link<T>) -> &'a mut T {
+        unsafe { &mut *(node.as_ptr() as *mut u8).sub(self.offset).cast::<T>() }
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> core::iter::FusedIterator for IterMut<'_, T> {}
+
+/// Iterator returned by `RustyList::iter_unordered`; the order is unspecified.
+pub struct UnorderedIter<'a, T> {
+    inner: Iter<'a, T>,
+}
+
+impl<'a, T> Iterator for UnorderedIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for UnorderedIter<'_, T> {}
+
+impl<T> core::iter::FusedIterator for UnorderedIter<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+    use crate::{RustyList, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn cmp(a: *const TestItem, b: *const TestItem) -> i32 {
+        unsafe { (*a).value.cmp(&(*b).value) as i32 }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn iter_follows_list_order() {
+        let mut sorted = RustyList::<TestItem>::new_with_order(cmp);
+        let mut pushed = RustyList::<TestItem>::new();
+        let mut a = [make_item(3), make_item(1), make_item(2)];
+        let mut b = [make_item(3), make_item(1), make_item(2)];
+        a.iter_mut().for_each(|item| sorted.insert(item));
+        b.iter_mut().for_each(|item| pushed.push(item));
+
+        assert_eq!(sorted.iter().map(|item| item.value).collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(pushed.iter().map(|item| item.value).collect::<Vec<_>>(), [3, 1, 2]);
+        assert_eq!(sorted.iter().size_hint(), (3, Some(3)));
+        assert!(RustyList::<TestItem>::new().iter().next().is_none());
+    }
+
+    #[test]
+    fn iter_counts_down_and_stays_exhausted() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut items = [make_item(1), make_item(2)];
+        items.iter_mut().for_each(|item| list.push(item));
+
+        let mut iter = list.iter();
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next().map(|item| item.value), Some(1));
+        let rest = iter.clone();
+        assert_eq!(iter.len(), 1);
+        assert_eq!(iter.next().map(|item| item.value), Some(2));
+        assert!(iter.next().is_none() && iter.next().is_none());
+        assert_eq!(iter.len(), 0);
+        assert_eq!(rest.map(|item| item.value).collect::<Vec<_>>(), [2]);
+    }
+
+    #[test]
+    fn iter_mut_updates_in_place() {
+        let mut list = RustyList::<TestItem>::new_with_order(cmp);
+        let mut items = [make_item(2), make_item(1), make_item(3)];
+        items.iter_mut().for_each(|item| list.insert(item));
+
+        assert_eq!(list.iter_mut().len(), 3);
+        for (bonus, item) in list.iter_mut().enumerate() {
+            item.value += bonus as i32 * 10;
+        }
+        assert_eq!(list.iter().map(|item| item.value).collect::<Vec<_>>(), [1, 12, 23]);
+        assert_eq!(list.len, 3);
+    }
+
+    #[test]
+    fn iter_walks_from_both_ends() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut items = [make_item(1), make_item(2), make_item(3), make_item(4)];
+        items.iter_mut().for_each(|item| list.push(item));
+
+        assert_eq!(list.iter_rev().map(|item| item.value).collect::<Vec<_>>(), [4, 3, 2, 1]);
+        assert_eq!(list.iter().rev().map(|item| item.value).collect::<Vec<_>>(), [4, 3, 2, 1]);
+
+        for item in list.iter_mut().rev().take(2) {
+            item.value *= -1;
+        }
+
+        // the ends meet in the middle without yielding an element twice
+        let mut iter = list.iter();
+        assert_eq!(iter.next().map(|item| item.value), Some(1));
+        assert_eq!(iter.next_back().map(|item| item.value), Some(-4));
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next_back().map(|item| item.value), Some(-3));
+        assert_eq!(iter.next().map(|item| item.value), Some(2));
+        assert!(iter.next().is_none() && iter.next_back().is_none());
+    }
+
+    #[test]
+    fn list_references_work_in_for_loops() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut items = [make_item(1), make_item(2), make_item(3)];
+        items.iter_mut().for_each(|item| list.push(item));
+
+        for item in &mut list {
+            item.value += 1;
+        }
+        let mut sum = 0;
+        for item in &list {
+            sum += item.value;
+        }
+        assert_eq!(sum, 9);
+        assert_eq!((&list).into_iter().rev().map(|item| item.value).collect::<Vec<_>>(), [4, 3, 2]);
+    }
+
+    #[test]
+    fn iter_unordered_visits_every_element_once() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut items = [make_item(4), make_item(8), make_item(15)];
+        items.iter_mut().for_each(|item| list.push(item));
+
+        let mut seen: Vec<i32> = list.iter_unordered().map(|item| item.value).collect();
+        seen.sort();
+        assert_eq!(seen, [4, 8, 15]);
+        assert_eq!(list.iter_unordered().map(|item| item.value).sum::<i32>(), 27);
+    }
+}
@@ -0,0 +1,264 @@
+// links.rs
+// Internal link primitives shared by the list operations.
+use core::ptr::NonNull;
+use crate::{RustyList, RustyListNode};
+
+/// A link to a node, as stored in `head`, `tail`, `prev` and `next`.
+pub(crate) type Link<T> = NonNull<RustyListNode<T>>;
+
+impl<T> RustyList<T> {
+    /// Returns the element that embeds `node`.
+    #[inline(always)]
+    pub(crate) fn item_of(&self, node: Link<T>) -> *mut T {
+        unsafe { crate::rusty_container_of_mut(node.as_ptr(), self.offset) }
+    }
+
+    /// Returns the node embedded in `item`.
+    #[inline(always)]
+    pub(crate) fn node_of(&self, item: *mut T) -> Link<T> {
+        let node = item.wrapping_byte_add(self.offset) as *mut RustyListNode<T>;
+        unsafe { NonNull::new_unchecked(node) }
+    }
+
+    /// The head element, if any.
+    pub(crate) fn head_item(&self) -> Option<*mut T> {
+        self.head.map(|node| self.item_of(node))
+    }
+
+    /// The tail element, if any.
+    pub(crate) fn tail_item(&self) -> Option<*mut T> {
+        self.tail.map(|node| self.item_of(node))
+    }
+
+    /// Returns the `*mut` for `item` if it is an element of this list, checking the tail
+    /// first and then walking from the head.
+    pub(crate) fn find_item(&self, item: *const T) -> Option<*mut T> {
+        let _guard = self.begin_traversal();
+        if let Some(tail) = self.tail_item().filter(|&tail| core::ptr::eq(tail, item)) {
+            return Some(tail);
+        }
+
+        let mut current = self.head;
+        while let Some(node) = current {
+            let candidate = self.item_of(node);
+            if core::ptr::eq(candidate, item) {
+                return Some(candidate);
+            }
+            current = unsafe { (*node.as_ptr()).next };
+        }
+        None
+    }
+
+    /// Links `node` right after `prev`, or at the head when `prev` is `None`.
+    ///
+    /// # Safety
+    /// `node` must be unlinked and `prev` must be a node of this list.
+    pub(crate) unsafe fn link_after(&mut self, prev: Option<Link<T>>, node: Link<T>) {
+        self.assert_not_traversing("link");
+        let next = match prev {
+            Some(p) => unsafe { (*p.as_ptr()).next },
+            None => self.head,
+        };
+
+        let n = unsafe { &mut *node.as_ptr() };
+        n.prev = prev;
+        n.next = next;
+        n.linked = true;
+
+        match prev {
+            Some(p) => unsafe { (*p.as_ptr()).next = Some(node) },
+            None => self.head = Some(node),
+        }
+        match next {
+            Some(nx) => unsafe { (*nx.as_ptr()).prev = Some(node) },
+            None => self.tail = Some(node),
+        }
+
+        self.len = self.len.saturating_add(1);
+        self.paranoid_check_links(node);
+        #[cfg(feature = "replay")]
+        self.record_link(node);
+    }
+
+    /// Resets the node of `item`, about to be linked into this list, and returns it.
+    ///
+    /// A node that still looks linked could carry a stale flag from a discarded list, so it
+    /// is relinked anyway, with a warning naming `op`.
+    pub(crate) fn claim_node(&mut self, item: *mut T, op: &str) -> Link<T> {
+        self.paranoid_check_item(item);
+        let node = self.node_of(item);
+        let n = unsafe { &mut *node.as_ptr() };
+        if n.linked {
+            rusty_warn!("RustyList::{}: node is already linked", op);
+        }
+        #[cfg(not(feature = "log"))]
+        let _ = op;
+        n.clear_links();
+        n.next_generation();
+        #[cfg(feature = "age-stamps")]
+        self.stamp_node(node.as_ptr());
+        node
+    }
+
+    /// Unlinks `node` from the list and clears its links.
+    ///
+    /// Cursors at `node` move on to the next element.
+    ///
+    /// # Safety
+    /// `node` must be a node of this list.
+    pub(crate) unsafe fn unlink_node(&mut self, node: Link<T>) {
+        self.move_cursors(node, unsafe { (*node.as_ptr()).next });
+        unsafe { self.unlink_for_move(node) };
+    }
+
+    /// Like `unlink_node`, for a node that is linked back into this list right away:
+    /// cursors stay anchored to it.
+    ///
+    /// # Safety
+    /// `node` must be a node of this list.
+    pub(crate) unsafe fn unlink_for_move(&mut self, node: Link<T>) {
+        self.assert_not_traversing("unlink");
+        self.paranoid_check_links(node);
+        #[cfg(feature = "replay")]
+        self.record_unlink(node);
+        let n = unsafe { &mut *node.as_ptr() };
+
+        match n.prev {
+            Some(p) => unsafe { (*p.as_ptr()).next = n.next },
+            None => self.head = n.next,
+        }
+        match n.next {
+            Some(nx) => unsafe { (*nx.as_ptr()).prev = n.prev },
+            None => self.tail = n.prev,
+        }
+
+        n.clear_links();
+        self.len = self.len.saturating_sub(1);
+    }
+
+    /// Moves `node` to right after `prev` (to the head when `prev` is `None`), keeping
+    /// cursors anchored to it. The list is well formed again as soon as this returns.
+    ///
+    /// # Safety
+    /// `node` and `prev` must be nodes of this list, and `prev` must not be `node`.
+    pub(crate) unsafe fn move_after(&mut self, prev: Option<Link<T>>, node: Link<T>) {
+        if unsafe { (*node.as_ptr()).prev } == prev {
+            return;
+        }
+        unsafe {
+            self.unlink_for_move(node);
+            self.link_after(prev, node);
+        }
+    }
+
+    /// Empties the list header and returns the old head.
+    ///
+    /// The nodes keep their `next` links so the caller can walk the detached chain; each
+    /// node must then be relinked or have its links cleared.
+    pub(crate) fn take_chain(&mut self) -> Option<Link<T>> {
+        self.assert_not_traversing("take");
+        self.park_cursors();
+        #[cfg(feature = "replay")]
+        self.record_clear();
+        let head = self.head.take();
+        self.tail = None;
+        self.len = 0;
+        head
+    }
+
+    /// Returns an empty list with the same offset, used as a temporary partition.
+    pub(crate) fn empty_like(&self) -> Self {
+        Self {
+            len: 0,
+            dynamic: self.dynamic,
+            head: None,
+            tail: None,
+            offset: self.offset,
+            order_function: self.order_function,
+            #[cfg(feature = "persistent-cursors")]
+            cursors: None,
+            #[cfg(feature = "watermarks")]
+            watermarks: None,
+            #[cfg(feature = "reposition-hook")]
+            reposition_hook: None,
+            #[cfg(feature = "age-stamps")]
+            next_stamp: 0,
+            #[cfg(feature = "metrics")]
+            stats: core::cell::Cell::new(crate::list_ops::metrics::ScanStats::default()),
+            #[cfg(feature = "replay")]
+            replay: None,
+            #[cfg(feature = "borrow-check")]
+            borrow_flag: core::cell::Cell::new(0),
+        }
+    }
+
+    /// Moves every node of `other` to the tail of this list in O(1), leaving `other` empty.
+    pub(crate) fn splice_back(&mut self, other: &mut Self) {
+        let Some(other_head) = other.head else {
+            return;
+        };
+
+        match self.tail {
+            Some(tail) => unsafe {
+                (*tail.as_ptr()).next = Some(other_head);
+                (*other_head.as_ptr()).prev = Some(tail);
+            },
+            None => self.head = Some(other_head),
+        }
+
+        self.tail = other.tail;
+        self.len = self.len.saturating_add(other.len);
+        other.take_chain();
+        #[cfg(feature = "replay")]
+        self.record_links_from(Some(other_head));
+    }
+
+    /// Detaches the first `n` nodes into a new list and returns it.
+    ///
+    /// Takes every node if `n >= len`.
+    pub(crate) fn split_front(&mut self, n: usize) -> Self {
+        let mut front = self.empty_like();
+        if n == 0 {
+            return front;
+        }
+        if n >= self.len {
+            front.splice_back(self);
+            return front;
+        }
+
+        let mut last = self.head;
+        for _ in 1..n {
+            last = last.and_then(|l| unsafe { (*l.as_ptr()).next });
+        }
+        let Some(last) = last else {
+            return front;
+        };
+        let Some(rest) = (unsafe { (*last.as_ptr()).next }) else {
+            return front;
+        };
+        #[cfg(feature = "replay")]
+        if let Some(head) = self.head {
+            self.record_unlinks(head, last);
+        }
+
+        // cursors on the detached elements move to the first one that stays
+        let mut moved = self.head.filter(|_| self.has_cursors());
+        while let Some(node) = moved {
+            self.move_cursors(node, Some(rest));
+            moved = if node == last { None } else { unsafe { (*node.as_ptr()).next } };
+        }
+
+        unsafe {
+            (*last.as_ptr()).next = None;
+            (*rest.as_ptr()).prev = None;
+        }
+
+        front.head = self.head;
+        front.tail = Some(last);
+        front.len = n;
+
+        self.head = Some(rest);
+        self.len -= n;
+        front
+    }
+}
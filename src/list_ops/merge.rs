@@ -0,0 +1,239 @@
+// merge.rs
+// Operations that combine a second list into this one.
+use crate::RustyList;
+
+/// What `merge_by` does with two elements whose keys compare equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeTie {
+    /// This list's element goes first; the other one is compared with the next element.
+    SelfFirst,
+    /// The other list's element goes first, right before this list's element.
+    OtherFirst,
+    /// The two are combined into this list's element and the other one is dropped from
+    /// both lists.
+    Coalesce,
+}
+
+impl<T> RustyList<T> {
+    /// Moves the elements of `other` into this list, alternating between the two.
+    ///
+    /// The result is `self[0], other[0], self[1], other[1], ...`; whichever list is longer
+    /// contributes its remaining elements at the end, in order. No comparator is involved,
+    /// which makes this a cheap way to mix two traffic classes fairly. `other` is left
+    /// empty and must hold the same element type with the node at the same offset.
+    pub fn interleave(&mut self, other: &mut RustyList<T>) {
+        self.assert_not_traversing("interleave");
+        other.assert_not_traversing("interleave");
+        debug_assert_eq!(self.offset, other.offset, "RustyList::interleave: offsets differ");
+        let (before, other_before) = (self.len, other.len);
+        let mut current = self.head;
+
+        while let (Some(ours), Some(theirs)) = (current, other.head) {
+            current = unsafe { (*ours.as_ptr()).next };
+            unsafe {
+                other.unlink_node(theirs);
+                self.link_after(Some(ours), theirs);
+            }
+        }
+
+        self.splice_back(other);
+        self.notify_watermarks(before);
+        other.notify_watermarks(other_before);
+    }
+
+    /// Merges the elements of `other` into this list, both sorted by `cmp`, keeping the
+    /// result sorted.
+    ///
+    /// One pass over both lists, relinking in place. When an element of `other` compares
+    /// equal to one of this list, `on_tie` decides: keep this list's element first, put the
+    /// other first, or coalesce them. Coalescing unlinks the other element and hands it to
+    /// `combine` together with the element it is merged into, e.g. to add up the counts of
+    /// two events with the same sequence number. With `SelfFirst` the other element moves
+    /// on to this list's next element, so `on_tie` sees it again for each equal element.
+    /// `other` is left empty and must hold the same element type with the node at the same
+    /// offset; `cmp` follows the `order_function` convention.
+    ///
+    /// # Returns
+    /// The number of elements coalesced away.
+    pub fn merge_by(
+        &mut self,
+        other: &mut RustyList<T>,
+        mut cmp: impl FnMut(*const T, *const T) -> i32,
+        mut on_tie: impl FnMut(&T, &T) -> MergeTie,
+        mut combine: impl FnMut(&mut T, *mut T),
+    ) -> usize {
+        self.assert_not_traversing("merge_by");
+        other.assert_not_traversing("merge_by");
+        debug_assert_eq!(self.offset, other.offset, "RustyList::merge_by: offsets differ");
+        let (before, other_before) = (self.len, other.len);
+        let mut coalesced = 0;
+        let mut current = self.head;
+
+        // counts the comparisons for the `metrics` stats
+        let mut _comparisons = 0usize;
+        let mut cmp = |a: *const T, b: *const T| {
+            _comparisons = _comparisons.saturating_add(1);
+            cmp(a, b)
+        };
+
+        while let (Some(ours), Some(theirs)) = (current, other.head) {
+            let (ours_item, theirs_item) = (self.item_of(ours), other.item_of(theirs));
+            let order = match cmp(theirs_item, ours_item) {
+                0 => on_tie(unsafe { &*ours_item }, unsafe { &*theirs_item }),
+                c if c < 0 => MergeTie::OtherFirst,
+                _ => MergeTie::SelfFirst,
+            };
+
+            match order {
+                MergeTie::SelfFirst => current = unsafe { (*ours.as_ptr()).next },
+                MergeTie::OtherFirst => unsafe {
+                    other.unlink_node(theirs);
+                    self.link_after((*ours.as_ptr()).prev, theirs);
+                },
+                MergeTie::Coalesce => {
+                    unsafe { other.unlink_node(theirs) };
+                    coalesced += 1;
+                    self.run_callback(|| combine(unsafe { &mut *ours_item }, theirs_item));
+                }
+            }
+        }
+
+        #[cfg(feature = "metrics")]
+        self.record_scan(crate::list_ops::metrics::ScanKind::Merge, _comparisons);
+        self.splice_back(other);
+        self.notify_watermarks(before);
+        other.notify_watermarks(other_before);
+        coalesced
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+    use crate::{MergeTie, RustyList, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    fn list_of(items: &mut [TestItem]) -> RustyList<TestItem> {
+        let mut list = RustyList::new();
+        items.iter_mut().for_each(|item| list.push(item));
+        list
+    }
+
+    #[test]
+    fn interleave_alternates_and_appends_leftovers() {
+        let mut a_items: Vec<TestItem> = [1, 3].into_iter().map(make_item).collect();
+        let mut b_items: Vec<TestItem> = [2, 4, 6, 8].into_iter().map(make_item).collect();
+        let mut a = list_of(&mut a_items);
+        let mut b = list_of(&mut b_items);
+
+        a.interleave(&mut b);
+        assert_eq!(a.iter().map(|item| item.value).collect::<Vec<_>>(), [1, 2, 3, 4, 6, 8]);
+        assert_eq!(a.len, 6);
+        assert_eq!(b.len, 0);
+        assert!(b.head.is_none() && b.tail.is_none());
+        assert_eq!(unsafe { (*a.tail.unwrap().as_ptr()).next_node() }, None);
+    }
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct Event {
+        pub seq: u32,
+        pub count: u32,
+        pub node: RustyListNode<Event>,
+    }
+
+    impl HasRustyNode for Event {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn by_seq(a: *const Event, b: *const Event) -> i32 {
+        unsafe { (*a).seq.cmp(&(*b).seq) as i32 }
+    }
+
+    fn events(seqs: &[u32], count: u32) -> Vec<Event> {
+        seqs.iter().map(|&seq| Event { seq, count, node: RustyListNode::new() }).collect()
+    }
+
+    fn contents(list: &RustyList<Event>) -> Vec<(u32, u32)> {
+        list.iter().map(|event| (event.seq, event.count)).collect()
+    }
+
+    #[test]
+    fn merge_by_coalesces_duplicate_keys() {
+        let mut core0 = events(&[1, 3, 5, 7], 1);
+        let mut core1 = events(&[0, 3, 4, 7, 9], 10);
+        let mut a = RustyList::<Event>::new();
+        let mut b = RustyList::<Event>::new();
+        core0.iter_mut().for_each(|event| a.push(event));
+        core1.iter_mut().for_each(|event| b.push(event));
+
+        let mut dropped = Vec::new();
+        let coalesced = a.merge_by(&mut b, by_seq, |_, _| MergeTie::Coalesce, |kept, other| {
+            kept.count += unsafe { (*other).count };
+            dropped.push(other as *const Event);
+        });
+
+        assert_eq!(coalesced, 2);
+        assert_eq!(contents(&a), [(0, 10), (1, 1), (3, 11), (4, 10), (5, 1), (7, 11), (9, 10)]);
+        assert_eq!((a.len, b.len), (7, 0));
+        assert!(b.head.is_none() && b.tail.is_none());
+        assert_eq!(dropped, [&core1[1] as *const Event, &core1[3] as *const Event]);
+        assert!(!core1[1].node.is_linked() && !core1[3].node.is_linked());
+    }
+
+    #[test]
+    fn merge_by_orders_ties_as_told() {
+        let mut ours = events(&[2, 2], 0);
+        let mut theirs = events(&[2, 5], 1);
+        let mut a = RustyList::<Event>::new();
+        let mut b = RustyList::<Event>::new();
+        ours.iter_mut().for_each(|event| a.push(event));
+        theirs.iter_mut().for_each(|event| b.push(event));
+
+        // the other element is asked about once per equal element of ours
+        let mut asked = 0;
+        a.merge_by(&mut b, by_seq, |_, _| { asked += 1; MergeTie::SelfFirst }, |_, _| unreachable!());
+        assert_eq!(asked, 2);
+        assert_eq!(contents(&a), [(2, 0), (2, 0), (2, 1), (5, 1)]);
+
+        let mut front = events(&[2], 7);
+        b.push(&mut front[0]);
+        a.merge_by(&mut b, by_seq, |_, _| MergeTie::OtherFirst, |_, _| unreachable!());
+        assert_eq!(contents(&a), [(2, 7), (2, 0), (2, 0), (2, 1), (5, 1)]);
+    }
+
+    #[test]
+    fn interleave_with_longer_self_or_empty_other() {
+        let mut a_items: Vec<TestItem> = [1, 3, 5, 7].into_iter().map(make_item).collect();
+        let mut b_items: Vec<TestItem> = [2].into_iter().map(make_item).collect();
+        let mut a = list_of(&mut a_items);
+        let mut b = list_of(&mut b_items);
+
+        a.interleave(&mut b);
+        assert_eq!(a.iter().map(|item| item.value).collect::<Vec<_>>(), [1, 2, 3, 5, 7]);
+
+        a.interleave(&mut RustyList::new());
+        assert_eq!(a.len, 5);
+    }
+}
@@ -0,0 +1,246 @@
+// metrics.rs
+// Comparator call counts and scan-length histograms (feature `metrics`).
+use crate::RustyList;
+
+/// Number of buckets in a `ScanHistogram`.
+pub const SCAN_BUCKETS: usize = 8;
+
+/// Power-of-two histogram of how many comparator calls an operation made.
+///
+/// Bucket 0 counts scans with no comparisons, bucket 1 scans with exactly one, and bucket
+/// `i` scans with `2^(i-1)..2^i` comparisons; the last bucket also takes everything
+/// longer. Counts saturate instead of wrapping.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ScanHistogram {
+    pub buckets: [u32; SCAN_BUCKETS],
+}
+
+impl ScanHistogram {
+    /// The bucket a scan of `comparisons` comparator calls lands in.
+    pub fn bucket_of(comparisons: usize) -> usize {
+        let bits = (usize::BITS - comparisons.leading_zeros()) as usize;
+        bits.min(SCAN_BUCKETS - 1)
+    }
+
+    /// Number of scans recorded.
+    pub fn total(&self) -> u64 {
+        self.buckets.iter().map(|&count| u64::from(count)).sum()
+    }
+
+    fn record(&mut self, comparisons: usize) {
+        let bucket = &mut self.buckets[Self::bucket_of(comparisons)];
+        *bucket = bucket.saturating_add(1);
+    }
+}
+
+/// Running totals of comparator calls, per operation.
+///
+/// Meant for algorithmic regression tests: reset the stats, run an operation, and assert
+/// on the count it made (e.g. a sorted insert of ascending input makes one comparison).
+/// The totals saturate at `u64::MAX` instead of wrapping.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ComparisonCounts {
+    /// Comparisons made by `insert`.
+    pub insert: u64,
+    /// Comparisons made by `find_equal`, `find_equal_from_tail` and `find_equal_auto`.
+    pub find: u64,
+    /// Comparisons made by `sort`, `sort_by` and `resort`, including `resort`'s order check.
+    pub sort: u64,
+    /// Comparisons made by `merge_by`.
+    pub merge: u64,
+}
+
+/// Statistics collected by a list, returned by `RustyList::stats`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ScanStats {
+    /// Total comparator calls per operation.
+    pub comparisons: ComparisonCounts,
+    /// Comparisons made by each `insert` into a non-empty list with an `order_function`.
+    pub insert: ScanHistogram,
+    /// Comparisons made by each `find_equal`, `find_equal_from_tail` or `find_equal_auto`.
+    pub find: ScanHistogram,
+}
+
+/// Which operation a scan belongs to.
+#[derive(Clone, Copy)]
+pub(crate) enum ScanKind {
+    Insert,
+    Find,
+    /// Counted but not added to a histogram: a sort is one long scan, not many short ones.
+    Sort,
+    /// Counted but not added to a histogram, like `Sort`.
+    Merge,
+}
+
+impl<T> RustyList<T> {
+    /// Returns the comparison counts and scan-length histograms collected since the list was created or since
+    /// the last `reset_stats`.
+    ///
+    /// A find histogram piling up in the top buckets is the sign that lookups are walking
+    /// most of a long list, and that an indexed or tree-shaped structure would pay off.
+    pub fn stats(&self) -> ScanStats {
+        self.stats.get()
+    }
+
+    /// Clears the collected counts and histograms.
+    pub fn reset_stats(&mut self) {
+        self.stats.set(ScanStats::default());
+    }
+
+    /// Records one scan of `comparisons` comparator calls.
+    #[inline(always)]
+    pub(crate) fn record_scan(&self, kind: ScanKind, comparisons: usize) {
+        let mut stats = self.stats.get();
+        let counts = &mut stats.comparisons;
+        let comparisons_u64 = comparisons as u64;
+        match kind {
+            ScanKind::Insert => {
+                counts.insert = counts.insert.saturating_add(comparisons_u64);
+                stats.insert.record(comparisons);
+            }
+            ScanKind::Find => {
+                counts.find = counts.find.saturating_add(comparisons_u64);
+                stats.find.record(comparisons);
+            }
+            ScanKind::Sort => counts.sort = counts.sort.saturating_add(comparisons_u64),
+            ScanKind::Merge => counts.merge = counts.merge.saturating_add(comparisons_u64),
+        }
+        self.stats.set(stats);
+    }
+
+    /// Starts counting the comparisons of a lookup; the scan is recorded when the guard
+    /// is dropped, whichever way the lookup returns.
+    #[inline(always)]
+    pub(crate) fn begin_scan(&self, kind: ScanKind) -> ScanGuard<'_, T> {
+        ScanGuard { list: self, kind, comparisons: 0 }
+    }
+}
+
+/// Counts comparator calls for one scan and records them on drop.
+pub(crate) struct ScanGuard<'a, T> {
+    list: &'a RustyList<T>,
+    kind: ScanKind,
+    comparisons: usize,
+}
+
+impl<T> ScanGuard<'_, T> {
+    /// Counts one comparator call.
+    #[inline(always)]
+    pub(crate) fn tick(&mut self) {
+        self.comparisons = self.comparisons.saturating_add(1);
+    }
+}
+
+impl<T> Drop for ScanGuard<'_, T> {
+    fn drop(&mut self) {
+        self.list.record_scan(self.kind, self.comparisons);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn cmp(a: *const TestItem, b: *const TestItem) -> i32 {
+        unsafe { (*a).value.cmp(&(*b).value) as i32 }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn bucket_of_is_power_of_two() {
+        let buckets: std::vec::Vec<usize> = [0, 1, 2, 3, 4, 7, 8, 63, 64, 1000]
+            .iter()
+            .map(|&n| ScanHistogram::bucket_of(n))
+            .collect();
+        assert_eq!(buckets, [0, 1, 2, 2, 3, 3, 4, 6, 7, 7]);
+    }
+
+    #[test]
+    fn stats_record_insert_and_find_scans() {
+        let mut list = RustyList::<TestItem>::new_with_order(cmp);
+        let mut items = [make_item(10), make_item(20), make_item(30), make_item(15)];
+        for item in items.iter_mut() {
+            list.insert(item);
+        }
+
+        // 10: empty list, not a scan; 20: beats the tail; 30: beats the tail;
+        // 15: tail, head, then walks 10, 20
+        let stats = list.stats();
+        assert_eq!(stats.insert.total(), 3);
+        assert_eq!(stats.insert.buckets[1], 2);
+        assert_eq!(stats.insert.buckets[3], 1);
+
+        // 10 is the first element, 30 the last of four
+        assert!(list.find_equal(&make_item(10)).is_some());
+        assert!(list.find_equal(&make_item(30)).is_some());
+        let stats = list.stats();
+        assert_eq!(stats.find.total(), 2);
+        assert_eq!(stats.find.buckets[1], 1);
+        assert_eq!(stats.find.buckets[3], 1);
+
+        list.reset_stats();
+        assert_eq!(list.stats(), ScanStats::default());
+    }
+
+    #[test]
+    fn comparisons_are_counted_per_operation() {
+        let mut list = RustyList::<TestItem>::new_with_order(cmp);
+        let mut items: std::vec::Vec<TestItem> = (0..16).map(make_item).collect();
+
+        // ascending input only ever beats the tail: one comparison per insert
+        for item in items.iter_mut() {
+            list.reset_stats();
+            list.insert(item);
+            assert!(list.stats().comparisons.insert <= 1);
+        }
+
+        list.reset_stats();
+        assert!(!list.resort());
+        assert_eq!(list.stats().comparisons.sort, 15);
+
+        list.reset_stats();
+        assert!(list.find_equal_auto(&make_item(0)).is_some());
+        assert_eq!(list.stats().comparisons.find, 1);
+
+        // reversed: a single descending run, reversed in one pass
+        list.reset_stats();
+        list.sort_by(|a, b| cmp(b, a));
+        assert_eq!(list.stats().comparisons.sort, 15);
+        assert_eq!(list.stats().comparisons.insert, 0);
+    }
+
+    #[test]
+    fn merge_by_comparisons_are_counted() {
+        let mut ours: std::vec::Vec<TestItem> = [1, 3, 5].into_iter().map(make_item).collect();
+        let mut theirs: std::vec::Vec<TestItem> = [2, 4].into_iter().map(make_item).collect();
+        let mut a = RustyList::<TestItem>::new();
+        let mut b = RustyList::<TestItem>::new();
+        ours.iter_mut().for_each(|item| a.push(item));
+        theirs.iter_mut().for_each(|item| b.push(item));
+
+        // 2 vs 1, 2 vs 3, 4 vs 3, 4 vs 5; nothing is left to compare after that
+        a.merge_by(&mut b, cmp, |_, _| crate::MergeTie::SelfFirst, |_, _| {});
+        assert_eq!(a.stats().comparisons.merge, 4);
+        assert_eq!(a.stats().comparisons.sort, 0);
+    }
+}
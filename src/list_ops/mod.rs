@@ -1,6 +1,45 @@
+pub(crate) mod links;
+pub(crate) mod borrow_flag;
+pub(crate) mod paranoid;
 pub mod new;
 pub mod pop;
 pub mod push;
+pub mod batch;
 pub mod insert;
 pub mod remove;
+pub mod node_ref;
 pub mod find_equal;
+pub mod iter;
+pub mod payload;
+pub mod cursor;
+pub mod rebase;
+pub mod checkpoint;
+pub mod collect;
+pub mod sort;
+pub mod merge;
+pub mod transfer;
+pub mod select;
+pub mod random;
+pub mod query;
+pub mod format;
+pub mod dump;
+pub mod watermarks;
+pub mod expire;
+pub mod retain;
+pub mod drain;
+pub mod reposition;
+pub mod persistent_cursor;
+pub mod round_robin;
+pub mod scrub;
+#[cfg(feature = "age-stamps")]
+pub mod age;
+#[cfg(feature = "failure-injection")]
+pub mod failure;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "reposition-hook")]
+pub mod reposition_hook;
+#[cfg(feature = "replay")]
+pub mod replay;
+#[cfg(feature = "std")]
+pub mod registry;
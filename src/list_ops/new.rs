@@ -1,4 +1,6 @@
 use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::ptr::NonNull;
 use crate::{RustyList, HasRustyNode, RustyListNode};
 
 impl<T> RustyListNode<T> {
@@ -6,9 +8,14 @@ impl<T> RustyListNode<T> {
     pub const fn new_const() -> Self {
         Self {
             dynamic: false,
+            linked: false,
             _marker: PhantomData,
             prev: None,
             next: None,
+            #[cfg(feature = "age-stamps")]
+            stamp: 0,
+            #[cfg(debug_assertions)]
+            generation: 0,
         }
     }
 
@@ -17,16 +24,56 @@ impl<T> RustyListNode<T> {
         Self::new_const() // You can even make the original call the const one
     }
 
+    /// Initializes an unlinked node directly in `slot` and returns a reference to it.
+    ///
+    /// Whatever was in `slot` before is overwritten without being read, so this is safe
+    /// to use on raw pool/arena storage.
+    pub fn init_in(slot: &mut MaybeUninit<Self>) -> &mut Self {
+        slot.write(Self::new_const())
+    }
+
     /// Sets the `dynamic` property of the node and returns the modified instance.
     pub fn with_dynamic(mut self, dynamic: bool) -> Self {
         self.dynamic = dynamic;
         self
     }
 
-    /// reset the node to initial state (not dynamic)
-    pub fn clear_links(&mut self) {
+    /// Returns `true` if the node was created as dynamic.
+    pub fn is_dynamic(&self) -> bool {
+        self.dynamic
+    }
+
+    /// Returns `true` if the node is currently linked into a list.
+    ///
+    /// This is also `true` for the only node of a list, which has no neighbours.
+    pub fn is_linked(&self) -> bool {
+        self.linked
+    }
+
+    /// Returns the next node in the list, if any.
+    pub fn next_node(&self) -> Option<NonNull<RustyListNode<T>>> {
+        self.next
+    }
+
+    /// Returns the previous node in the list, if any.
+    pub fn prev_node(&self) -> Option<NonNull<RustyListNode<T>>> {
+        self.prev
+    }
+
+    /// reset the node to initial state (unlinked, no neighbours)
+    pub(crate) fn clear_links(&mut self) {
         self.prev = None;
         self.next = None;
+        self.linked = false;
+    }
+}
+
+/// An unlinked, non-dynamic node, same as `RustyListNode::new()`.
+///
+/// This lets structs that embed a node `#[derive(Default)]`.
+impl<T> Default for RustyListNode<T> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -43,14 +90,7 @@ impl<T: HasRustyNode> RustyList<T> {
     /// - `offset` is initialized using the `rusty_offset` method of the `HasRustyNode` trait.
     /// - `order_function` is set to `None`.
     pub fn new() -> Self {
-        Self {
-            len: 0,
-            dynamic: false,
-            head: None,
-            tail: None,
-            offset: T::rusty_offset(),
-            order_function: None,
-        }
+        Self::with_offset(T::rusty_offset())
     }
 
     /// Creates a new `RustyList` instance with a custom ordering function.
@@ -68,12 +108,8 @@ impl<T: HasRustyNode> RustyList<T> {
     /// - `order_function` is set to the provided `order` function.
     pub fn new_with_order(order: fn(*const T, *const T) -> i32) -> Self {
         Self {
-            len: 0,
-            dynamic: false,
-            head: None,
-            tail: None,
-            offset: T::rusty_offset(),
             order_function: Some(order),
+            ..Self::with_offset(T::rusty_offset())
         }
     }
 
@@ -90,12 +126,69 @@ impl<T: HasRustyNode> RustyList<T> {
     }
 }
 
+impl<T> RustyList<T> {
+    /// Creates an empty, unordered list whose nodes sit `offset` bytes into each element.
+    ///
+    /// For element types that embed several nodes, one per list they can be on.
+    pub(crate) fn with_offset(offset: usize) -> Self {
+        Self {
+            len: 0,
+            dynamic: false,
+            head: None,
+            tail: None,
+            offset,
+            order_function: None,
+            #[cfg(feature = "persistent-cursors")]
+            cursors: None,
+            #[cfg(feature = "watermarks")]
+            watermarks: None,
+            #[cfg(feature = "reposition-hook")]
+            reposition_hook: None,
+            #[cfg(feature = "age-stamps")]
+            next_stamp: 0,
+            #[cfg(feature = "metrics")]
+            stats: core::cell::Cell::new(crate::list_ops::metrics::ScanStats::default()),
+            #[cfg(feature = "replay")]
+            replay: None,
+            #[cfg(feature = "borrow-check")]
+            borrow_flag: core::cell::Cell::new(0),
+        }
+    }
+
+    /// Installs `order` as the `order_function` and returns the list sorted by it.
+    ///
+    /// # Parameters
+    /// - `order`: The ordering function, with the same convention as `new_with_order`.
+    ///
+    /// # Returns
+    /// The modified `RustyList` instance; see `set_order`.
+    pub fn with_order(mut self, order: fn(*const T, *const T) -> i32) -> Self {
+        self.set_order(order);
+        self
+    }
+
+    /// Installs or replaces the `order_function` and sorts the list by it.
+    ///
+    /// This turns a bulk-loaded, `push`ed list into a sorted one in place, after which
+    /// `insert` and `find_equal` use the new order. An empty or already ordered list is
+    /// only checked, not relinked (see `resort`).
+    pub fn set_order(&mut self, order: fn(*const T, *const T) -> i32) {
+        self.assert_not_traversing("set_order");
+        self.order_function = Some(order);
+        self.resort();
+    }
+}
+
+/// An empty, unordered list, same as `RustyList::new()`.
+impl<T: HasRustyNode> Default for RustyList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use core::ptr::NonNull;
-    use core::marker::PhantomData;
     use crate::{RustyListNode, rusty_offset};
 
     #[repr(C)]
@@ -145,22 +238,12 @@ mod tests {
 
         let a = Dummy {
             id: 1,
-            node: RustyListNode {
-                dynamic: false,
-                _marker: PhantomData,
-                prev: None,
-                next: None,
-            },
+            node: RustyListNode::new(),
         };
 
         let b = Dummy {
             id: 2,
-            node: RustyListNode {
-                dynamic: false,
-                _marker: PhantomData,
-                prev: None,
-                next: None,
-            },
+            node: RustyListNode::new(),
         };
 
         let cmp_fn = list.order_function.unwrap();
@@ -177,42 +260,107 @@ mod tests {
         assert!(!list.dynamic);
     }
 
+    #[test]
+    fn test_default_list_matches_new() {
+        let list = RustyList::<Dummy>::default();
+
+        assert_eq!(list.len, 0);
+        assert!(!list.dynamic);
+        assert!(list.head.is_none());
+        assert!(list.tail.is_none());
+        assert!(list.order_function.is_none());
+        assert_eq!(list.offset, Dummy::rusty_offset());
+    }
+
+    #[test]
+    fn test_default_derive_on_container() {
+        #[repr(C)]
+        #[derive(Default)]
+        struct Derived {
+            id: u32,
+            node: RustyListNode<Derived>,
+        }
+
+        impl HasRustyNode for Derived {
+            fn rusty_offset() -> usize {
+                rusty_offset(|x: &Self| &x.node)
+            }
+        }
+
+        let mut a = Derived { id: 7, ..Default::default() };
+        assert!(!a.node.is_linked());
+
+        let mut list = RustyList::<Derived>::default();
+        list.push(&mut a);
+        assert_eq!(list.len, 1);
+        assert_eq!(unsafe { (*list.pop().unwrap()).id }, 7);
+    }
+
     // ListNode tests
+    #[test]
+    fn test_node_init_in_uninit_storage() {
+        let mut slot = MaybeUninit::<RustyListNode<u32>>::uninit();
+        let node = RustyListNode::init_in(&mut slot);
+        assert!(!node.is_linked());
+        assert!(!node.is_dynamic());
+        assert!(node.prev_node().is_none());
+        assert!(node.next_node().is_none());
+    }
+
     #[test]
     fn test_node_new_defaults() {
         let node = RustyListNode::<u32>::new();
-        assert!(!node.dynamic, "default node should not be dynamic");
-        assert!(node.prev.is_none());
-        assert!(node.next.is_none());
+        assert!(!node.is_dynamic(), "default node should not be dynamic");
+        assert!(!node.is_linked());
+        assert!(node.prev_node().is_none());
+        assert!(node.next_node().is_none());
     }
 
     #[test]
     fn test_node_with_dynamic_true() {
         let node = RustyListNode::<u32>::new().with_dynamic(true);
-        assert!(node.dynamic, "node should be marked dynamic");
+        assert!(node.is_dynamic(), "node should be marked dynamic");
     }
 
     #[test]
     fn test_node_with_dynamic_false() {
         let node = RustyListNode::<u32>::new().with_dynamic(false);
-        assert!(!node.dynamic, "node should be non-dynamic");
+        assert!(!node.is_dynamic(), "node should be non-dynamic");
     }
 
     #[test]
     fn test_node_clear_links() {
         // Pretend pointers (not dereferenced, just testing state change)
-        let dummy_prev = 0x1 as *mut RustyListNode<u32>;
-        let dummy_next = 0x2 as *mut RustyListNode<u32>;
-
-        let mut node = RustyListNode {
-            dynamic: false,
-            _marker: PhantomData,
-            prev: Some(unsafe { NonNull::new_unchecked(dummy_prev) }),
-            next: Some(unsafe { NonNull::new_unchecked(dummy_next) }),
-        };
+        let mut node = RustyListNode::<u32>::new();
+        node.linked = true;
+        node.prev = Some(NonNull::dangling());
+        node.next = Some(NonNull::dangling());
 
         node.clear_links();
-        assert!(node.prev.is_none());
-        assert!(node.next.is_none());
+        assert!(!node.is_linked());
+        assert!(node.prev_node().is_none());
+        assert!(node.next_node().is_none());
+    }
+
+    #[test]
+    fn with_order_sorts_a_bulk_loaded_list() {
+        let mut items = [3, 1, 2].map(|id| Dummy { id, node: RustyListNode::new() });
+        let mut list = RustyList::<Dummy>::new();
+        for item in items.iter_mut() {
+            list.push(item);
+        }
+
+        let mut list = list.with_order(dummy_cmp);
+        assert!(list.order_function.is_some());
+        let mut ids = [0; 3];
+        for (slot, item) in ids.iter_mut().zip(list.iter()) {
+            *slot = item.id;
+        }
+        assert_eq!(ids, [1, 2, 3]);
+
+        let mut item = Dummy { id: 0, node: RustyListNode::new() };
+        list.insert(&mut item);
+        assert_eq!(list.peek_ref().map(|item| item.id), Some(0));
+        assert_eq!(list.len, 4);
     }
 }
@@ -0,0 +1,206 @@
+// node_ref.rs
+// Handles to linked elements for O(1) targeted operations.
+use core::ptr::NonNull;
+use crate::{RustyList, RustyListNode};
+use crate::list_ops::links::Link;
+
+/// A lightweight handle to an element, returned by `push_handle`/`insert_handle`.
+///
+/// Holds the element's node pointer and, in debug builds, the generation the node had
+/// when it was linked. The `*_by_handle` operations reject a handle whose element has
+/// been removed since, and in debug builds also one whose element was removed and linked
+/// again, so a stale handle is caught instead of acting on whatever the node is now.
+#[derive(Debug)]
+pub struct NodeRef<T> {
+    node: Link<T>,
+    #[cfg(debug_assertions)]
+    generation: u32,
+}
+
+impl<T> Clone for NodeRef<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for NodeRef<T> {}
+
+impl<T> PartialEq for NodeRef<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.node == other.node
+    }
+}
+
+impl<T> Eq for NodeRef<T> {}
+
+impl<T> NodeRef<T> {
+    /// The node the handle points to.
+    pub fn node(&self) -> NonNull<RustyListNode<T>> {
+        self.node
+    }
+}
+
+impl<T> RustyListNode<T> {
+    /// Starts a new generation of the node. Called whenever an element is newly linked;
+    /// a no-op in release builds.
+    #[inline(always)]
+    pub(crate) fn next_generation(&mut self) {
+        #[cfg(debug_assertions)]
+        {
+            self.generation = self.generation.wrapping_add(1);
+        }
+    }
+}
+
+impl<T> RustyList<T> {
+    /// Appends `item` like `push` and returns a handle to it.
+    pub fn push_handle(&mut self, item: &mut T) -> NodeRef<T> {
+        self.push(item);
+        self.handle_of(item)
+    }
+
+    /// Inserts `item` like `insert` and returns a handle to it.
+    pub fn insert_handle(&mut self, item: &mut T) -> NodeRef<T> {
+        self.insert(item);
+        self.handle_of(item)
+    }
+
+    /// Removes the element behind `handle` and returns it.
+    ///
+    /// Returns `None`, leaving the list unchanged, if the handle is stale.
+    ///
+    /// # Safety
+    /// The element behind `handle` must still be alive.
+    pub unsafe fn remove_by_handle(&mut self, handle: NodeRef<T>) -> Option<*mut T> {
+        if !self.handle_is_live(handle) {
+            return None;
+        }
+        unsafe { self.remove_by_node(handle.node) }
+    }
+
+    /// Moves the element behind `handle` to the head in O(1), ignoring the
+    /// `order_function`. Returns `false`, leaving the list unchanged, if the handle is
+    /// stale.
+    ///
+    /// # Safety
+    /// The element behind `handle` must still be alive.
+    pub unsafe fn move_to_front_by_handle(&mut self, handle: NodeRef<T>) -> bool {
+        self.assert_not_traversing("move_to_front_by_handle");
+        if !self.handle_is_live(handle) {
+            return false;
+        }
+        debug_assert!(
+            self.find_item(self.item_of(handle.node)).is_some(),
+            "RustyList::move_to_front_by_handle: node belongs to a different list"
+        );
+
+        if self.head != Some(handle.node) {
+            #[cfg(feature = "reposition-hook")]
+            let old = self.neighbors_of(handle.node);
+            unsafe {
+                self.unlink_for_move(handle.node);
+                self.link_after(None, handle.node);
+            }
+            #[cfg(feature = "reposition-hook")]
+            self.notify_reposition(handle.node, old);
+        }
+        true
+    }
+
+    fn handle_of(&self, item: &mut T) -> NodeRef<T> {
+        let node = self.node_of(item);
+        NodeRef {
+            node,
+            #[cfg(debug_assertions)]
+            generation: unsafe { (*node.as_ptr()).generation },
+        }
+    }
+
+    /// Checks that the handle's element is still linked, and in debug builds that it
+    /// wasn't relinked since the handle was made.
+    fn handle_is_live(&self, handle: NodeRef<T>) -> bool {
+        let node = unsafe { &*handle.node.as_ptr() };
+        #[cfg(debug_assertions)]
+        if node.generation != handle.generation {
+            rusty_warn!("RustyList: stale NodeRef, the element was relinked since");
+            return false;
+        }
+        if !node.linked {
+            rusty_warn!("RustyList: stale NodeRef, the element was removed");
+        }
+        node.linked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+    use crate::{RustyList, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn cmp(a: *const TestItem, b: *const TestItem) -> i32 {
+        unsafe { (*a).value.cmp(&(*b).value) as i32 }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn handles_target_their_element() {
+        let mut list = RustyList::<TestItem>::new_with_order(cmp);
+        let mut a = make_item(1);
+        let mut b = make_item(2);
+        let mut c = make_item(3);
+        list.insert_handle(&mut a);
+        let hb = list.insert_handle(&mut b);
+        let hc = list.push_handle(&mut c);
+
+        assert!(unsafe { list.move_to_front_by_handle(hc) });
+        assert_eq!(list.iter().map(|item| item.value).collect::<Vec<_>>(), [3, 1, 2]);
+
+        assert_eq!(unsafe { list.remove_by_handle(hb) }, Some(&mut b as *mut TestItem));
+        assert_eq!(list.iter().map(|item| item.value).collect::<Vec<_>>(), [3, 1]);
+        assert_eq!(hb.node(), core::ptr::NonNull::from(&mut b.node));
+    }
+
+    #[test]
+    fn stale_handles_are_rejected() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut a = make_item(1);
+        let mut b = make_item(2);
+        let ha = list.push_handle(&mut a);
+        list.push(&mut b);
+
+        list.remove(&mut a);
+        assert_eq!(unsafe { list.remove_by_handle(ha) }, None);
+        assert!(!unsafe { list.move_to_front_by_handle(ha) });
+        assert_eq!(list.len, 1);
+
+        // relinked: the old handle is caught in debug builds, a fresh one works
+        list.push(&mut a);
+        if cfg!(debug_assertions) {
+            assert_eq!(unsafe { list.remove_by_handle(ha) }, None);
+            assert_eq!(list.len, 2);
+        }
+        list.remove(&mut a);
+        let fresh = list.push_handle(&mut a);
+        assert!(unsafe { list.move_to_front_by_handle(fresh) });
+        assert_eq!(list.iter().map(|item| item.value).collect::<Vec<_>>(), [1, 2]);
+    }
+}
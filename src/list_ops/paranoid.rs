@@ -0,0 +1,138 @@
+// paranoid.rs
+// Exhaustive internal consistency checks, compiled in by the `paranoid` feature.
+use core::mem::size_of;
+use crate::{RustyList, RustyListNode};
+use crate::list_ops::links::Link;
+
+impl<T> RustyList<T> {
+    /// Checks that `item`'s node lies inside it and maps back to the same element.
+    #[inline(always)]
+    #[track_caller]
+    pub(crate) fn paranoid_check_item(&self, item: *const T) {
+        if !cfg!(feature = "paranoid") {
+            return;
+        }
+
+        assert!(
+            self.offset + size_of::<RustyListNode<T>>() <= size_of::<T>(),
+            "RustyList paranoid: node offset {} does not fit inside the element",
+            self.offset,
+        );
+        assert!(item.is_aligned(), "RustyList paranoid: element {item:p} is misaligned");
+        let node = item.wrapping_byte_add(self.offset) as *const RustyListNode<T>;
+        assert!(
+            unsafe { crate::rusty_container_of(node, self.offset) } == item,
+            "RustyList paranoid: node offset does not round-trip to the element",
+        );
+    }
+
+    /// Checks that `node`'s neighbours link back to it, and that the ends match the header.
+    #[inline(always)]
+    #[track_caller]
+    pub(crate) fn paranoid_check_links(&self, node: Link<T>) {
+        if !cfg!(feature = "paranoid") {
+            return;
+        }
+
+        let n = unsafe { &*node.as_ptr() };
+        assert!(n.linked, "RustyList paranoid: touched node is not marked linked");
+        match n.prev {
+            Some(p) => assert!(
+                unsafe { (*p.as_ptr()).next } == Some(node),
+                "RustyList paranoid: prev.next does not point back to the node",
+            ),
+            None => assert!(self.head == Some(node), "RustyList paranoid: node without prev is not the head"),
+        }
+        match n.next {
+            Some(nx) => assert!(
+                unsafe { (*nx.as_ptr()).prev } == Some(node),
+                "RustyList paranoid: next.prev does not point back to the node",
+            ),
+            None => assert!(self.tail == Some(node), "RustyList paranoid: node without next is not the tail"),
+        }
+    }
+
+    /// Checks that `node` sits between neighbours it is ordered with.
+    #[inline(always)]
+    #[track_caller]
+    pub(crate) fn paranoid_check_sorted(&self, node: Link<T>) {
+        if !cfg!(feature = "paranoid") {
+            return;
+        }
+        let Some(cmp_fn) = self.order_function else {
+            return;
+        };
+
+        let n = unsafe { &*node.as_ptr() };
+        let item = self.item_of(node);
+        if let Some(p) = n.prev {
+            assert!(cmp_fn(self.item_of(p), item) <= 0, "RustyList paranoid: node sorts before its prev");
+        }
+        if let Some(nx) = n.next {
+            assert!(cmp_fn(item, self.item_of(nx)) <= 0, "RustyList paranoid: node sorts after its next");
+        }
+    }
+}
+
+#[cfg(all(test, feature = "paranoid"))]
+mod tests {
+    use crate::{RustyList, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "prev.next does not point back")]
+    fn remove_detects_broken_link_symmetry() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut a = make_item(1);
+        let mut b = make_item(2);
+        let mut c = make_item(3);
+
+        list.push(&mut a);
+        list.push(&mut b);
+        list.push(&mut c);
+
+        // corrupt the list: a skips over b
+        unsafe { (*list.head.unwrap().as_ptr()).next = list.tail };
+        list.remove(&mut b);
+    }
+
+    #[test]
+    #[should_panic(expected = "node sorts before its prev")]
+    fn insert_detects_inconsistent_comparator() {
+        // claims every element is greater, which can't hold for both neighbours
+        let mut list = RustyList::<TestItem>::new_with_order(|_, _| 1);
+        let mut a = make_item(1);
+        let mut b = make_item(2);
+
+        list.insert(&mut a);
+        list.insert(&mut b);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit inside the element")]
+    fn bad_offset_is_caught() {
+        let mut list = RustyList::<TestItem>::new();
+        list.offset = 64;
+        let mut a = make_item(1);
+        list.push(&mut a);
+    }
+}
@@ -0,0 +1,145 @@
+// payload.rs
+// Mutable iteration over element payloads, with the node kept out of reach.
+use crate::{IterMut, RustyList};
+
+/// Implemented by element types: a view borrowing every field except the node.
+///
+/// A `&mut T` handed out during a walk lets the caller overwrite the embedded node, e.g.
+/// with `*item = other` or `mem::swap`, and corrupt the list. Iterating with
+/// `iter_payload_mut` instead yields `Fields`, which can only reach what the element
+/// chooses to expose:
+///
+/// ```ignore
+/// #[repr(C)]
+/// struct Task {
+///     id: u32,
+///     budget: u64,
+///     node: RustyListNode<Task>,
+/// }
+///
+/// struct TaskFields<'a> {
+///     id: &'a mut u32,
+///     budget: &'a mut u64,
+/// }
+///
+/// impl RustyPayload for Task {
+///     type Fields<'a> = TaskFields<'a>;
+///     fn payload_mut(&mut self) -> TaskFields<'_> {
+///         TaskFields { id: &mut self.id, budget: &mut self.budget }
+///     }
+/// }
+/// ```
+///
+/// `payload_mut` must not return a reference to the node, or to anything containing it.
+pub trait RustyPayload {
+    /// The mutable view of the payload fields.
+    type Fields<'a>
+    where
+        Self: 'a;
+
+    /// Splits the borrow of `self` into borrows of its payload fields.
+    fn payload_mut(&mut self) -> Self::Fields<'_>;
+}
+
+impl<T: RustyPayload> RustyList<T> {
+    /// Iterates over the payloads of the elements from head to tail, for updating them in
+    /// place.
+    ///
+    /// The list is borrowed mutably for the whole walk, so it can't be restructured
+    /// meanwhile, and the node of each element stays unreachable.
+    pub fn iter_payload_mut(&mut self) -> PayloadIterMut<'_, T> {
+        PayloadIterMut { inner: self.iter_mut() }
+    }
+
+    /// Calls `f` with the payload of every element, head to tail.
+    pub fn for_each_payload(&mut self, f: impl FnMut(T::Fields<'_>)) {
+        self.iter_payload_mut().for_each(f);
+    }
+}
+
+/// Iterator returned by `RustyList::iter_payload_mut`, head to tail.
+pub struct PayloadIterMut<'a, T> {
+    inner: IterMut<'a, T>,
+}
+
+impl<'a, T: RustyPayload + 'a> Iterator for PayloadIterMut<'a, T> {
+    type Item = T::Fields<'a>;
+
+    fn next(&mut self) -> Option<T::Fields<'a>> {
+        self.inner.next().map(T::payload_mut)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T: RustyPayload + 'a> DoubleEndedIterator for PayloadIterMut<'a, T> {
+    fn next_back(&mut self) -> Option<T::Fields<'a>> {
+        self.inner.next_back().map(T::payload_mut)
+    }
+}
+
+impl<'a, T: RustyPayload + 'a> ExactSizeIterator for PayloadIterMut<'a, T> {}
+
+impl<'a, T: RustyPayload + 'a> core::iter::FusedIterator for PayloadIterMut<'a, T> {}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+    use super::*;
+    use crate::{RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub hits: u32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    struct TestFields<'a> {
+        value: &'a mut i32,
+        hits: &'a mut u32,
+    }
+
+    impl RustyPayload for TestItem {
+        type Fields<'a> = TestFields<'a>;
+
+        fn payload_mut(&mut self) -> TestFields<'_> {
+            TestFields { value: &mut self.value, hits: &mut self.hits }
+        }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            hits: 0,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn payloads_update_in_place() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut items = [make_item(1), make_item(2), make_item(3)];
+        items.iter_mut().for_each(|item| list.push(item));
+
+        assert_eq!(list.iter_payload_mut().len(), 3);
+        for fields in list.iter_payload_mut() {
+            *fields.value *= 10;
+            *fields.hits += 1;
+        }
+        list.for_each_payload(|fields| *fields.hits += 1);
+
+        assert_eq!(list.iter().map(|item| item.value).collect::<Vec<_>>(), [10, 20, 30]);
+        assert!(list.iter().all(|item| item.hits == 2));
+        assert_eq!(list.len, 3);
+    }
+}
@@ -0,0 +1,462 @@
+// persistent_cursor.rs
+// Cursors anchored to an element that survive modifications of the list. Attaching them
+// needs the `persistent-cursors` feature; without it a cursor is a plain resumable position.
+#[cfg(feature = "persistent-cursors")]
+use core::fmt;
+#[cfg(feature = "persistent-cursors")]
+use core::ptr::NonNull;
+use crate::RustyList;
+use crate::list_ops::borrow_flag::TraversalGuard;
+use crate::list_ops::links::Link;
+
+/// A position in a `RustyList` that stays valid while the list is modified.
+///
+/// The cursor is anchored to an element, not to an index: inserting or pushing elements
+/// elsewhere doesn't move it, and if its element is removed (`remove`, `pop`,
+/// `remove_expired`, `retain_into`, ...) the list moves it to the element that followed,
+/// or past the end. That lets a long-running incremental scan do a bounded amount of work
+/// per call and pick up where it left off, instead of restarting from the head.
+///
+/// A cursor is attached to a list with the unsafe `RustyList::attach_cursor` (feature
+/// `persistent-cursors`) and is itself linked intrusively: like an element, it must not move
+/// or be dropped while attached. Reordering operations (`sort`, `shuffle`, `select_nth`,
+/// `keep_smallest_k`, ...) move elements one at a time, so a cursor stays on its element
+/// wherever it ends up. Only `restore_structure` and handing the whole chain to another
+/// list (`push_many`, `interleave` or `merge_by` with this list as the source) park its
+/// cursors past the end. A cursor that isn't attached is only valid while the list isn't
+/// modified between calls.
+pub struct PersistentCursor<T> {
+    pub(crate) position: Option<Link<T>>,
+    #[cfg(feature = "persistent-cursors")]
+    next_cursor: Option<NonNull<PersistentCursor<T>>>,
+    #[cfg(feature = "persistent-cursors")]
+    attached: bool,
+}
+
+impl<T> PersistentCursor<T> {
+    /// Creates a detached cursor.
+    pub const fn new() -> Self {
+        Self {
+            position: None,
+            #[cfg(feature = "persistent-cursors")]
+            next_cursor: None,
+            #[cfg(feature = "persistent-cursors")]
+            attached: false,
+        }
+    }
+
+    /// Returns `true` while the cursor is attached to a list.
+    #[cfg(feature = "persistent-cursors")]
+    pub fn is_attached(&self) -> bool {
+        self.attached
+    }
+
+    /// Returns `true` if the cursor is past the last element.
+    pub fn is_at_end(&self) -> bool {
+        self.position.is_none()
+    }
+}
+
+impl<T> Default for PersistentCursor<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returned by `RustyList::attach_cursor` when the cursor is attached to another list.
+#[cfg(feature = "persistent-cursors")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttachedElsewhere;
+
+#[cfg(feature = "persistent-cursors")]
+impl fmt::Display for AttachedElsewhere {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PersistentCursor is attached to another list")
+    }
+}
+
+impl<T> RustyList<T> {
+    /// Attaches `cursor` to this list, positioned at the head.
+    ///
+    /// Attaching a cursor that is already attached to this list just rewinds it. A cursor
+    /// attached to another list is left alone and `AttachedElsewhere` is returned; detach
+    /// it from that list first. Which list a cursor belongs to is read off the lists' own
+    /// cursor chains, so it stays right when a list value is moved.
+    ///
+    /// # Safety
+    /// The list keeps a pointer to `cursor` and writes through it whenever an element
+    /// leaves the list, so `cursor` must not move or be dropped until `detach_cursor` is
+    /// called with it.
+    #[cfg(feature = "persistent-cursors")]
+    pub unsafe fn attach_cursor(&mut self, cursor: &mut PersistentCursor<T>) -> Result<(), AttachedElsewhere> {
+        if cursor.attached {
+            if !self.has_cursor(NonNull::from(&mut *cursor)) {
+                return Err(AttachedElsewhere);
+            }
+            cursor.position = self.head;
+            return Ok(());
+        }
+        cursor.position = self.head;
+        cursor.attached = true;
+        cursor.next_cursor = self.cursors;
+        self.cursors = Some(NonNull::from(cursor));
+        Ok(())
+    }
+
+    /// Returns `true` if `target` is on this list's cursor chain.
+    #[cfg(feature = "persistent-cursors")]
+    fn has_cursor(&self, target: NonNull<PersistentCursor<T>>) -> bool {
+        let mut current = self.cursors;
+        while let Some(c) = current {
+            if c == target {
+                return true;
+            }
+            current = unsafe { (*c.as_ptr()).next_cursor };
+        }
+        false
+    }
+
+    /// Detaches `cursor` from this list. Does nothing if it isn't attached here.
+    #[cfg(feature = "persistent-cursors")]
+    pub fn detach_cursor(&mut self, cursor: &mut PersistentCursor<T>) {
+        let target = NonNull::from(&mut *cursor);
+        let mut link = &mut self.cursors;
+
+        while let Some(c) = *link {
+            if c == target {
+                *link = cursor.next_cursor;
+                cursor.next_cursor = None;
+                cursor.position = None;
+                cursor.attached = false;
+                return;
+            }
+            link = unsafe { &mut (*c.as_ptr()).next_cursor };
+        }
+    }
+
+    /// The element the cursor is at, or `None` past the end.
+    pub fn cursor_current(&self, cursor: &PersistentCursor<T>) -> Option<&T> {
+        cursor.position.map(|node| unsafe { &*self.item_of(node) })
+    }
+
+    /// Returns the element the cursor is at and moves it to the next one.
+    ///
+    /// Repeated calls visit the list head to tail; `None` means the scan reached the end.
+    pub fn cursor_next(&self, cursor: &mut PersistentCursor<T>) -> Option<&T> {
+        let node = cursor.position?;
+        cursor.position = unsafe { (*node.as_ptr()).next };
+        Some(unsafe { &*self.item_of(node) })
+    }
+
+    /// Moves the cursor back to the head.
+    pub fn cursor_rewind(&self, cursor: &mut PersistentCursor<T>) {
+        cursor.position = self.head;
+    }
+
+    /// Yields the next chunk of at most `n` elements from the cursor's position, advancing
+    /// the cursor past each element as it is yielded.
+    ///
+    /// Lets a cooperative task process a long list in time slices: take a chunk, drop it
+    /// (and any lock around the list) to yield, and call again for the next one. With the
+    /// cursor attached, the list may be modified between chunks and the scan still picks up
+    /// at the first element it hasn't visited. An empty chunk means the scan is done.
+    pub fn iter_chunks<'a>(&'a self, cursor: &'a mut PersistentCursor<T>, n: usize) -> Chunk<'a, T> {
+        Chunk {
+            list: self,
+            cursor,
+            remaining: n,
+            _guard: self.begin_traversal(),
+        }
+    }
+
+    /// Returns `true` if any cursor is attached, so bulk removals can skip the per-node
+    /// `move_cursors` walk. Always `false` without the `persistent-cursors` feature.
+    #[inline(always)]
+    pub(crate) fn has_cursors(&self) -> bool {
+        #[cfg(feature = "persistent-cursors")]
+        return self.cursors.is_some();
+        #[cfg(not(feature = "persistent-cursors"))]
+        false
+    }
+
+    /// Moves every cursor at `from` to `to`. Called before `from` leaves the list.
+    ///
+    /// Without the `persistent-cursors` feature this is a no-op.
+    #[inline(always)]
+    pub(crate) fn move_cursors(&self, from: Link<T>, to: Option<Link<T>>) {
+        #[cfg(feature = "persistent-cursors")]
+        {
+            let mut current = self.cursors;
+            while let Some(c) = current {
+                let cursor = unsafe { &mut *c.as_ptr() };
+                if cursor.position == Some(from) {
+                    cursor.position = to;
+                }
+                current = cursor.next_cursor;
+            }
+        }
+        #[cfg(not(feature = "persistent-cursors"))]
+        let _ = (from, to);
+    }
+
+    /// Replaces every cursor position with `f(position)`. Called when nodes change address.
+    ///
+    /// Without the `persistent-cursors` feature this is a no-op.
+    #[inline(always)]
+    pub(crate) fn map_cursors(&self, f: impl FnMut(Option<Link<T>>) -> Option<Link<T>>) {
+        #[cfg(feature = "persistent-cursors")]
+        {
+            let mut f = f;
+            let mut current = self.cursors;
+            while let Some(c) = current {
+                let cursor = unsafe { &mut *c.as_ptr() };
+                cursor.position = f(cursor.position);
+                current = cursor.next_cursor;
+            }
+        }
+        #[cfg(not(feature = "persistent-cursors"))]
+        let _ = f;
+    }
+
+    /// Moves every cursor past the end. Called when the whole chain is taken apart.
+    ///
+    /// Without the `persistent-cursors` feature this is a no-op.
+    #[inline(always)]
+    pub(crate) fn park_cursors(&self) {
+        #[cfg(feature = "persistent-cursors")]
+        {
+            let mut current = self.cursors;
+            while let Some(c) = current {
+                let cursor = unsafe { &mut *c.as_ptr() };
+                cursor.position = None;
+                current = cursor.next_cursor;
+            }
+        }
+    }
+}
+
+/// Iterator returned by `RustyList::iter_chunks`, one bounded chunk of a cursor's scan.
+pub struct Chunk<'a, T> {
+    list: &'a RustyList<T>,
+    cursor: &'a mut PersistentCursor<T>,
+    remaining: usize,
+    _guard: TraversalGuard<'a, T>,
+}
+
+impl<'a, T> Iterator for Chunk<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = self.cursor.position?;
+        self.cursor.position = unsafe { (*node.as_ptr()).next };
+        self.remaining -= 1;
+        Some(unsafe { &*self.list.item_of(node) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let upper = if self.cursor.position.is_some() { self.remaining } else { 0 };
+        (upper.min(1), Some(upper))
+    }
+}
+
+#[cfg(all(test, feature = "persistent-cursors"))]
+mod tests {
+    use core::mem::{size_of_val, MaybeUninit};
+    use core::ptr::NonNull;
+    use std::vec::Vec;
+    use crate::{AttachedElsewhere, PersistentCursor, RustyList, RustyListNode, HasRustyNode, ScrubCursor, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn cursor_skips_to_survivor_when_its_element_is_removed() {
+        let mut items: Vec<TestItem> = (1..=5).map(make_item).collect();
+        let mut list = RustyList::<TestItem>::new();
+        items.iter_mut().for_each(|item| list.push(item));
+
+        let mut cursor = PersistentCursor::new();
+        unsafe { list.attach_cursor(&mut cursor) }.unwrap();
+        assert_eq!(list.cursor_next(&mut cursor).map(|item| item.value), Some(1));
+        assert_eq!(list.cursor_current(&cursor).map(|item| item.value), Some(2));
+
+        // removing the anchor moves the cursor on; removing others doesn't touch it
+        list.remove(&mut items[1]);
+        list.remove(&mut items[3]);
+        assert_eq!(list.cursor_current(&cursor).map(|item| item.value), Some(3));
+        list.pop();
+        list.remove(&mut items[2]);
+        assert_eq!(list.cursor_next(&mut cursor).map(|item| item.value), Some(5));
+        assert!(cursor.is_at_end());
+
+        list.cursor_rewind(&mut cursor);
+        list.remove(&mut items[4]);
+        assert!(cursor.is_at_end());
+        assert!(list.head.is_none());
+
+        list.detach_cursor(&mut cursor);
+        assert!(!cursor.is_attached());
+    }
+
+    #[test]
+    fn several_cursors_and_bulk_operations() {
+        let mut items: Vec<TestItem> = (1..=6).map(make_item).collect();
+        let mut list = RustyList::<TestItem>::new();
+        items.iter_mut().for_each(|item| list.push(item));
+
+        let mut a = PersistentCursor::new();
+        let mut b = PersistentCursor::new();
+        unsafe {
+            list.attach_cursor(&mut a).unwrap();
+            list.attach_cursor(&mut b).unwrap();
+        }
+        list.cursor_next(&mut b);
+        list.cursor_next(&mut b);
+
+        // a sits on 1, b on 3: the batch takes both anchors
+        let batch = list.pop_many(3);
+        assert_eq!(batch.len, 3);
+        assert_eq!(list.cursor_current(&a).map(|item| item.value), Some(4));
+        assert_eq!(list.cursor_current(&b).map(|item| item.value), Some(4));
+
+        list.retain_into(|item| item.value != 4, &mut RustyList::new());
+        assert_eq!(list.cursor_current(&a).map(|item| item.value), Some(5));
+
+        list.detach_cursor(&mut a);
+        assert!(!a.is_attached());
+        assert!(b.is_attached());
+        list.remove(&mut items[4]);
+        assert_eq!(list.cursor_current(&b).map(|item| item.value), Some(6));
+        list.detach_cursor(&mut b);
+        assert!(list.cursors.is_none());
+    }
+
+    #[test]
+    fn cursor_attached_to_one_list_is_refused_by_another() {
+        let mut items: Vec<TestItem> = (1..=4).map(make_item).collect();
+        let (left, right) = items.split_at_mut(2);
+        let mut first = RustyList::<TestItem>::new();
+        let mut second = RustyList::<TestItem>::new();
+        left.iter_mut().for_each(|item| first.push(item));
+        right.iter_mut().for_each(|item| second.push(item));
+
+        let mut cursor = PersistentCursor::new();
+        unsafe { first.attach_cursor(&mut cursor) }.unwrap();
+        first.cursor_next(&mut cursor);
+        assert_eq!(unsafe { second.attach_cursor(&mut cursor) }, Err(AttachedElsewhere));
+        assert!(second.cursors.is_none());
+        assert_eq!(first.cursor_current(&cursor).map(|item| item.value), Some(2));
+
+        // removals in the other list don't touch it; detaching here does nothing
+        second.detach_cursor(&mut cursor);
+        second.pop();
+        first.remove(&mut left[1]);
+        assert!(cursor.is_attached() && cursor.is_at_end());
+
+        first.detach_cursor(&mut cursor);
+        unsafe { second.attach_cursor(&mut cursor) }.unwrap();
+        assert_eq!(second.cursor_current(&cursor).map(|item| item.value), Some(4));
+        second.detach_cursor(&mut cursor);
+    }
+
+    #[test]
+    fn chunks_resume_after_modifications() {
+        let mut items: Vec<TestItem> = (1..=7).map(make_item).collect();
+        let mut list = RustyList::<TestItem>::new();
+        items.iter_mut().for_each(|item| list.push(item));
+
+        let mut cursor = PersistentCursor::new();
+        unsafe { list.attach_cursor(&mut cursor) }.unwrap();
+        let first: Vec<i32> = list.iter_chunks(&mut cursor, 3).map(|item| item.value).collect();
+        assert_eq!(first, [1, 2, 3]);
+
+        // between slices: the next unvisited element goes away and a new one arrives
+        list.remove(&mut items[3]);
+        let mut late = make_item(8);
+        list.push(&mut late);
+
+        let second: Vec<i32> = list.iter_chunks(&mut cursor, 3).map(|item| item.value).collect();
+        assert_eq!(second, [5, 6, 7]);
+        let third: Vec<i32> = list.iter_chunks(&mut cursor, 3).map(|item| item.value).collect();
+        assert_eq!(third, [8]);
+        assert_eq!(list.iter_chunks(&mut cursor, 3).count(), 0);
+        list.detach_cursor(&mut cursor);
+    }
+
+    #[test]
+    fn cursor_moves_off_a_detached_run() {
+        let mut items: Vec<TestItem> = (0..6).map(make_item).collect();
+        let mut list = RustyList::<TestItem>::new();
+        items.iter_mut().for_each(|item| list.push(item));
+        let mut cursor = PersistentCursor::new();
+        unsafe { list.attach_cursor(&mut cursor) }.unwrap();
+        list.cursor_next(&mut cursor);
+        list.cursor_next(&mut cursor);
+
+        // the cursor was on 2 and moves to the element after the run
+        unsafe { list.nodes_between(NonNull::from(&mut items[2]), NonNull::from(&mut items[4]), None) };
+        assert_eq!(list.cursor_next(&mut cursor).map(|item| item.value), Some(5));
+        list.detach_cursor(&mut cursor);
+    }
+
+    #[test]
+    fn attached_scrub_cursor_survives_removals() {
+        let mut items: Vec<TestItem> = (0..5).map(make_item).collect();
+        let mut list = RustyList::<TestItem>::new();
+        items.iter_mut().for_each(|item| list.push(item));
+        let mut scrub = ScrubCursor::new();
+
+        unsafe { list.attach_cursor(scrub.cursor()) }.unwrap();
+        assert_eq!(list.scrub(1, &mut scrub).checked, 1);
+        list.remove(&mut items[1]);
+        let rest = list.scrub(10, &mut scrub);
+        assert_eq!((rest.checked, rest.completed_pass), (3, true));
+        list.detach_cursor(scrub.cursor());
+    }
+
+    #[test]
+    fn rebase_moves_cursors_into_the_new_block() {
+        let mut old = [make_item(1), make_item(2)];
+        let mut list = RustyList::<TestItem>::new();
+        old.iter_mut().for_each(|item| list.push(item));
+        let mut cursor = PersistentCursor::new();
+        unsafe { list.attach_cursor(&mut cursor) }.unwrap();
+        list.cursor_next(&mut cursor);
+
+        let mut new: [MaybeUninit<TestItem>; 2] = [const { MaybeUninit::uninit() }; 2];
+        let (old_base, new_base) = (old.as_ptr() as *const u8, new.as_mut_ptr() as *mut u8);
+        let len = size_of_val(&old);
+        unsafe {
+            core::ptr::copy_nonoverlapping(old_base, new_base, len);
+            list.rebase(old_base, new_base, len);
+        }
+        // scribble on the old block, the cursor must not see it anymore
+        for item in old.iter_mut() {
+            item.value = -1;
+        }
+
+        assert_eq!(list.cursor_current(&cursor).map(|item| item.value), Some(2));
+        assert_eq!(cursor.position, list.tail);
+        list.detach_cursor(&mut cursor);
+    }
+}
@@ -7,19 +7,47 @@ impl<T> RustyList<T> {
     /// - The returned pointer is to the outer `T`, not the node.
     /// - Caller must ensure the pointer is used safely.
     pub fn pop(&mut self) -> Option<*mut T> {
-        unsafe { self.pop_raw() }
+        self.assert_not_traversing("pop");
+        let before = self.len;
+        let item = unsafe { self.pop_raw() };
+        self.notify_watermarks(before);
+        item
+    }
+
+    /// Returns the element `pop` would remove, without unlinking it.
+    ///
+    /// Lets a consumer look at the next work item (e.g. compare its deadline to now)
+    /// before committing to dequeue it.
+    pub fn peek(&self) -> Option<*mut T> {
+        self.head_item()
+    }
+
+    /// Returns the tail element without unlinking it.
+    pub fn peek_back(&self) -> Option<*mut T> {
+        self.tail_item()
+    }
+
+    /// Borrows the head element, the checked counterpart of `peek`.
+    pub fn peek_ref(&self) -> Option<&T> {
+        self.head_item().map(|item| unsafe { &*item })
+    }
+
+    /// Borrows the tail element, the checked counterpart of `peek_back`.
+    pub fn peek_back_ref(&self) -> Option<&T> {
+        self.tail_item().map(|item| unsafe { &*item })
     }
 
     /// Unsafe internal function to remove the first node in the list.
     unsafe fn pop_raw(&mut self) -> Option<*mut T> {
-        if self.len == 0 || self.head.is_none() {
-            return None;
-        }
-
-        let node_ptr = self.head.unwrap().as_ptr();
+        let head = self.head?;
+        self.paranoid_check_links(head);
+        let node_ptr = head.as_ptr();
         let node = unsafe{&mut *node_ptr};
 
         let next = node.next;
+        self.move_cursors(head, next);
+        #[cfg(feature = "replay")]
+        self.record_unlink(head);
 
         self.head = next;
 
@@ -30,10 +58,9 @@ impl<T> RustyList<T> {
             self.tail = None;
         }
 
-        node.next = None;
-        node.prev = None;
+        node.clear_links();
 
-        self.len -= 1;
+        self.len = self.len.saturating_sub(1);
 
        unsafe{ Some(rusty_container_of_mut(node_ptr, self.offset))}
     }
@@ -90,4 +117,23 @@ mod tests {
         assert!(list.head.is_none());
         assert!(list.tail.is_none());
     }
+
+    #[test]
+    fn peek_leaves_list_unchanged() {
+        let mut list = RustyList::<TestItem>::new();
+        assert!(list.peek().is_none());
+        assert!(list.peek_back_ref().is_none());
+
+        let mut a = make_item(1);
+        let mut b = make_item(2);
+        list.push(&mut a);
+        list.push(&mut b);
+
+        assert_eq!(list.peek_ref().map(|item| item.value), Some(1));
+        assert_eq!(list.peek_back_ref().map(|item| item.value), Some(2));
+        assert_eq!(list.peek_back(), Some(&mut b as *mut TestItem));
+        assert_eq!(list.len, 2);
+        assert_eq!(list.peek(), list.pop());
+        assert_eq!(list.peek(), list.peek_back());
+    }
 }
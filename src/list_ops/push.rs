@@ -1,5 +1,16 @@
-use core::ptr::NonNull;
-use crate::{RustyList, RustyListNode};
+use core::fmt;
+use crate::RustyList;
+
+/// Returned by `try_push` and `try_insert` when the list already holds `usize::MAX`
+/// elements and its length can't be counted any further.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LenOverflow;
+
+impl fmt::Display for LenOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RustyList length would overflow usize")
+    }
+}
 
 impl<T> RustyList<T> {
     /// Adds a node to the end (tail) of the list.
@@ -9,9 +20,29 @@ impl<T> RustyList<T> {
     /// # Safety
     /// - `item` must be a valid pointer to a `T` with an embedded `RustyListNode<T>`.
     pub fn push(&mut self, item: &mut T) {
+        self.assert_not_traversing("push");
+        let before = self.len;
         unsafe {
             self.push_raw(item as *mut T);
         }
+        self.notify_watermarks(before);
+    }
+
+    /// Like `push`, but leaves `item` unlinked and fails if the length can't be counted.
+    ///
+    /// `push` never wraps `len` either: it saturates at `usize::MAX`, after which `len`
+    /// undercounts. Use this where a length that stops being exact must be caught, e.g.
+    /// when it drives capacity decisions.
+    pub fn try_push(&mut self, item: &mut T) -> Result<(), LenOverflow> {
+        self.check_len_room()?;
+        self.push(item);
+        Ok(())
+    }
+
+    /// Fails if one more element would overflow `len`.
+    #[inline(always)]
+    pub(crate) fn check_len_room(&self) -> Result<(), LenOverflow> {
+        if self.len == usize::MAX { Err(LenOverflow) } else { Ok(()) }
     }
 
     /// Unsafe internal function to add a raw pointer to the end (tail) of the list.
@@ -19,33 +50,15 @@ impl<T> RustyList<T> {
         if item.is_null() {
             return;
         }
-
-        let node_ptr = unsafe{(item as *mut u8).add(self.offset)} as *mut RustyListNode<T>;
-        let node = unsafe{&mut *node_ptr};
-
-        node.prev = None;
-        node.next = None;
-
-        let new_node =unsafe{ NonNull::new_unchecked(node_ptr)};
-
-        if self.len == 0 {
-            self.head = Some(new_node);
-            self.tail = Some(new_node);
-        } else {
-            let tail_ptr = self.tail.unwrap().as_ptr();
-            unsafe{(*tail_ptr).next = Some(new_node)};
-           unsafe{ node.prev = Some(NonNull::new_unchecked(tail_ptr))};
-            self.tail = Some(new_node);
-        }
-
-        self.len += 1;
+        let node = self.claim_node(item, "push");
+        unsafe { self.link_after(self.tail, node) };
     }
 }
 
 
 #[cfg(test)]
 mod tests {
-    use crate::{RustyList, RustyListNode, HasRustyNode, rusty_offset};
+    use crate::{RustyList, RustyListNode, HasRustyNode, LenOverflow, rusty_offset};
 
     #[repr(C)]
     #[derive(Debug, PartialEq)]
@@ -89,4 +102,22 @@ mod tests {
         assert_eq!(head_val, 10);
         assert_eq!(tail_val, 20);
     }
+
+    #[test]
+    fn try_push_refuses_at_max_len() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut a = make_item(1);
+        let mut b = make_item(2);
+        assert_eq!(list.try_push(&mut a), Ok(()));
+
+        // pretend the count is exhausted; the list itself is still one element long
+        list.len = usize::MAX;
+        assert_eq!(list.try_push(&mut b), Err(LenOverflow));
+        assert_eq!(list.try_insert(&mut b), Err(LenOverflow));
+        assert!(!b.node.is_linked());
+
+        // the infallible path saturates instead of wrapping to zero
+        list.push(&mut b);
+        assert_eq!(list.len, usize::MAX);
+    }
 }
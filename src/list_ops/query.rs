@@ -0,0 +1,438 @@
+use crate::RustyList;
+
+impl<T> RustyList<T> {
+    /// Returns `true` if the list has no elements.
+    ///
+    /// Like `is_singleton` and `len_at_least`, this reads the links rather than `len`, so
+    /// it stays right for lists whose `len` is untracked or no longer trusted.
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    /// Returns `true` if the list has exactly one element. O(1).
+    pub fn is_singleton(&self) -> bool {
+        self.head.is_some() && self.head == self.tail
+    }
+
+    /// Returns `true` if the list has at least `n` elements.
+    ///
+    /// Walks at most `n - 1` links from the head, so it stays cheap for small `n` however
+    /// long the list is, e.g. "is there more than one runnable task at this priority?".
+    pub fn len_at_least(&self, n: usize) -> bool {
+        if n == 0 {
+            return true;
+        }
+        let _guard = self.begin_traversal();
+        let mut current = self.head;
+
+        // stop on the n-th node instead of following its `next`
+        for _ in 1..n {
+            match current {
+                Some(node) => current = unsafe { (*node.as_ptr()).next },
+                None => return false,
+            }
+        }
+
+        current.is_some()
+    }
+
+    /// Returns `true` if `pred` returns `true` for any element, walking head to tail.
+    ///
+    /// Stops at the first match. An empty list returns `false`.
+    pub fn any(&self, mut pred: impl FnMut(&T) -> bool) -> bool {
+        let _guard = self.begin_traversal();
+        let mut current = self.head;
+
+        while let Some(node) = current {
+            if pred(unsafe { &*self.item_of(node) }) {
+                return true;
+            }
+            current = unsafe { (*node.as_ptr()).next };
+        }
+
+        false
+    }
+
+    /// Returns `true` if `pred` returns `true` for every element, walking head to tail.
+    ///
+    /// Stops at the first mismatch. An empty list returns `true`.
+    pub fn all(&self, mut pred: impl FnMut(&T) -> bool) -> bool {
+        !self.any(|item| !pred(item))
+    }
+
+    /// Counts the elements for which `pred` returns `true`.
+    pub fn count_if(&self, mut pred: impl FnMut(&T) -> bool) -> usize {
+        let _guard = self.begin_traversal();
+        let mut count = 0;
+        let mut current = self.head;
+
+        while let Some(node) = current {
+            if pred(unsafe { &*self.item_of(node) }) {
+                count += 1;
+            }
+            current = unsafe { (*node.as_ptr()).next };
+        }
+
+        count
+    }
+
+    /// Counts the elements that compare equal to `target` with the `order_function`.
+    ///
+    /// Returns 0 if the list has no `order_function`.
+    pub fn count_equal(&self, target: &T) -> usize {
+        match self.order_function {
+            Some(cmp_fn) => self.count_if(|item| cmp_fn(item, target) == 0),
+            None => 0,
+        }
+    }
+
+    /// Returns the largest element according to `cmp`, independent of the `order_function`.
+    ///
+    /// `cmp` follows the `order_function` convention. If several elements are equally
+    /// large, the last one is returned (like `Iterator::max_by`).
+    pub fn max_by(&self, mut cmp: impl FnMut(*const T, *const T) -> i32) -> Option<&T> {
+        self.extreme_by(|candidate, best| cmp(candidate, best) >= 0)
+    }
+
+    /// Returns the smallest element according to `cmp`, independent of the `order_function`.
+    ///
+    /// `cmp` follows the `order_function` convention. If several elements are equally
+    /// small, the first one is returned (like `Iterator::min_by`).
+    pub fn min_by(&self, mut cmp: impl FnMut(*const T, *const T) -> i32) -> Option<&T> {
+        self.extreme_by(|candidate, best| cmp(candidate, best) < 0)
+    }
+
+    /// Walks the list once, replacing the current best whenever `replaces` says so.
+    fn extreme_by(&self, mut replaces: impl FnMut(*const T, *const T) -> bool) -> Option<&T> {
+        let _guard = self.begin_traversal();
+        let mut best = self.item_of(self.head?);
+        let mut current = unsafe { (*self.head?.as_ptr()).next };
+
+        while let Some(node) = current {
+            let item = self.item_of(node);
+            if replaces(item, best) {
+                best = item;
+            }
+            current = unsafe { (*node.as_ptr()).next };
+        }
+
+        Some(unsafe { &*best })
+    }
+
+    /// Returns the first element (closest to the head) for which `pred` returns `true`.
+    ///
+    /// Unlike `find_equal`, this needs neither a `target` element nor an
+    /// `order_function`, so it works for element types that are expensive or impossible
+    /// to construct just to search, e.g. `list.find_by(|conn| conn.id == id)`.
+    pub fn find_by(&self, mut pred: impl FnMut(&T) -> bool) -> Option<&T> {
+        let _guard = self.begin_traversal();
+        let mut current = self.head;
+
+        while let Some(node) = current {
+            let item = unsafe { &*self.item_of(node) };
+            if pred(item) {
+                return Some(item);
+            }
+            current = unsafe { (*node.as_ptr()).next };
+        }
+
+        None
+    }
+
+    /// Mutable version of `find_by`.
+    pub fn find_by_mut(&mut self, mut pred: impl FnMut(&T) -> bool) -> Option<&mut T> {
+        let _guard = self.begin_traversal();
+        let mut current = self.head;
+
+        while let Some(node) = current {
+            let item = self.item_of(node);
+            if pred(unsafe { &*item }) {
+                return Some(unsafe { &mut *item });
+            }
+            current = unsafe { (*node.as_ptr()).next };
+        }
+
+        None
+    }
+
+    /// Returns the first non-`None` result of `f`, walking head to tail.
+    ///
+    /// Projects a value out of the matching element during the same walk, e.g.
+    /// `list.find_map(|conn| (conn.peer == peer).then_some(conn.handle))`, so no borrow of
+    /// the element outlives the search.
+    pub fn find_map<R>(&self, mut f: impl FnMut(&T) -> Option<R>) -> Option<R> {
+        let _guard = self.begin_traversal();
+        let mut current = self.head;
+
+        while let Some(node) = current {
+            if let Some(found) = f(unsafe { &*self.item_of(node) }) {
+                return Some(found);
+            }
+            current = unsafe { (*node.as_ptr()).next };
+        }
+
+        None
+    }
+
+    /// Returns the last element (closest to the tail) for which `pred` returns `true`.
+    ///
+    /// The list is scanned from the tail backwards, so tail-biased queries such as "most
+    /// recent entry for connection X" in an append-ordered list stop early.
+    pub fn find_last(&self, mut pred: impl FnMut(&T) -> bool) -> Option<&T> {
+        let _guard = self.begin_traversal();
+        let mut current = self.tail;
+
+        while let Some(node) = current {
+            let item = unsafe { &*self.item_of(node) };
+            if pred(item) {
+                return Some(item);
+            }
+            current = unsafe { (*node.as_ptr()).prev };
+        }
+
+        None
+    }
+
+    /// Mutable version of `find_last`.
+    pub fn find_last_mut(&mut self, mut pred: impl FnMut(&T) -> bool) -> Option<&mut T> {
+        let _guard = self.begin_traversal();
+        let mut current = self.tail;
+
+        while let Some(node) = current {
+            let item = self.item_of(node);
+            if pred(unsafe { &*item }) {
+                return Some(unsafe { &mut *item });
+            }
+            current = unsafe { (*node.as_ptr()).prev };
+        }
+
+        None
+    }
+
+    /// Returns the index of `item`, counting from 0 at the head, or `None` if it isn't an
+    /// element of this list.
+    ///
+    /// The element is located by identity, not by comparing values, so equal elements
+    /// each get their own index. An element whose node isn't linked is rejected without
+    /// a walk; otherwise this is O(index).
+    pub fn position(&self, item: &T) -> Option<usize> {
+        let target = self.node_of(item as *const T as *mut T);
+        if !unsafe { (*target.as_ptr()).linked } {
+            return None;
+        }
+
+        let _guard = self.begin_traversal();
+        let mut current = self.head;
+        let mut index = 0;
+
+        while let Some(node) = current {
+            if node == target {
+                return Some(index);
+            }
+            index += 1;
+            current = unsafe { (*node.as_ptr()).next };
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RustyList, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub conn: u8,
+        pub seq: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn make_item(conn: u8, seq: i32) -> TestItem {
+        TestItem {
+            conn,
+            seq,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn find_last_returns_most_recent_match() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut items = [make_item(1, 0), make_item(2, 1), make_item(1, 2), make_item(2, 3)];
+        for item in items.iter_mut() {
+            list.push(item);
+        }
+
+        let mut checked = 0;
+        let found = list.find_last(|item| {
+            checked += 1;
+            item.conn == 1
+        });
+
+        assert_eq!(found.map(|f| f.seq), Some(2));
+        assert_eq!(checked, 2);
+        assert!(list.find_last(|item| item.conn == 9).is_none());
+    }
+
+    #[test]
+    fn any_and_all_short_circuit() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut items = [make_item(1, 5), make_item(2, 50), make_item(3, 7)];
+        for item in items.iter_mut() {
+            list.push(item);
+        }
+
+        let mut checked = 0;
+        assert!(list.any(|item| {
+            checked += 1;
+            item.seq > 10
+        }));
+        assert_eq!(checked, 2);
+
+        checked = 0;
+        assert!(!list.all(|item| {
+            checked += 1;
+            item.seq < 10
+        }));
+        assert_eq!(checked, 2);
+
+        assert!(list.all(|item| item.conn > 0));
+        assert!(!list.any(|item| item.conn > 3));
+    }
+
+    #[test]
+    fn any_and_all_on_empty_list() {
+        let list = RustyList::<TestItem>::new();
+        assert!(!list.any(|_| true));
+        assert!(list.all(|_| false));
+    }
+
+    #[test]
+    fn count_if_and_count_equal() {
+        fn by_conn(a: *const TestItem, b: *const TestItem) -> i32 {
+            unsafe { (*a).conn.cmp(&(*b).conn) as i32 }
+        }
+
+        let mut list = RustyList::<TestItem>::new_with_order(by_conn);
+        let mut items = [make_item(1, 5), make_item(2, 50), make_item(1, 70), make_item(3, 7)];
+        for item in items.iter_mut() {
+            list.push(item);
+        }
+
+        assert_eq!(list.count_if(|item| item.seq > 6), 3);
+        assert_eq!(list.count_if(|_| false), 0);
+        assert_eq!(list.count_equal(&make_item(1, 0)), 2);
+        assert_eq!(list.count_equal(&make_item(4, 0)), 0);
+
+        let unordered = RustyList::<TestItem>::new();
+        assert_eq!(unordered.count_equal(&make_item(1, 0)), 0);
+    }
+
+    #[test]
+    fn max_by_and_min_by_break_ties_like_std() {
+        fn by_seq(a: *const TestItem, b: *const TestItem) -> i32 {
+            unsafe { (*a).seq.cmp(&(*b).seq) as i32 }
+        }
+
+        let mut list = RustyList::<TestItem>::new();
+        let mut items = [make_item(1, 5), make_item(2, 9), make_item(3, 1), make_item(4, 9), make_item(5, 1)];
+        for item in items.iter_mut() {
+            list.push(item);
+        }
+
+        assert_eq!(list.max_by(by_seq).map(|i| i.conn), Some(4));
+        assert_eq!(list.min_by(by_seq).map(|i| i.conn), Some(3));
+        // reversing the comparator swaps the roles
+        assert_eq!(list.max_by(|a, b| by_seq(b, a)).map(|i| i.conn), Some(5));
+
+        let empty = RustyList::<TestItem>::new();
+        assert!(empty.max_by(by_seq).is_none());
+        assert!(empty.min_by(by_seq).is_none());
+    }
+
+    #[test]
+    fn find_by_returns_the_first_match() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut items = [make_item(1, 0), make_item(2, 1), make_item(2, 2)];
+        items.iter_mut().for_each(|item| list.push(item));
+
+        assert_eq!(list.find_by(|item| item.conn == 2).map(|item| item.seq), Some(1));
+        assert!(list.find_by(|item| item.conn == 9).is_none());
+
+        list.find_by_mut(|item| item.seq == 2).unwrap().conn = 7;
+        assert_eq!(items[2].conn, 7);
+        assert!(RustyList::<TestItem>::new().find_by(|_| true).is_none());
+    }
+
+    #[test]
+    fn find_map_projects_the_first_match() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut items = [make_item(1, 10), make_item(2, 20), make_item(2, 30)];
+        items.iter_mut().for_each(|item| list.push(item));
+
+        assert_eq!(list.find_map(|item| (item.conn == 2).then_some(item.seq * 2)), Some(40));
+        assert_eq!(list.find_map(|item| (item.conn == 9).then_some(item.seq)), None);
+    }
+
+    #[test]
+    fn find_last_mut_allows_updates() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut a = make_item(1, 0);
+        let mut b = make_item(1, 1);
+        list.push(&mut a);
+        list.push(&mut b);
+
+        list.find_last_mut(|item| item.conn == 1).unwrap().seq = 42;
+        assert_eq!(b.seq, 42);
+        assert_eq!(a.seq, 0);
+
+        let mut empty = RustyList::<TestItem>::new();
+        assert!(empty.find_last_mut(|_| true).is_none());
+    }
+
+    #[test]
+    fn position_finds_elements_by_identity() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut other = RustyList::<TestItem>::new();
+        let mut items = [make_item(1, 7), make_item(1, 7), make_item(2, 3)];
+        let mut stranger = make_item(1, 7);
+        let unlinked = make_item(1, 7);
+        items.iter_mut().for_each(|item| list.push(item));
+        other.push(&mut stranger);
+
+        // equal elements are told apart
+        let positions: std::vec::Vec<_> = items.iter().map(|item| list.position(item)).collect();
+        assert_eq!(positions, [Some(0), Some(1), Some(2)]);
+        assert_eq!(list.position(&stranger), None);
+        assert_eq!(list.position(&unlinked), None);
+    }
+
+    #[test]
+    fn bounded_length_probes_follow_the_links() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut items = [make_item(1, 0), make_item(1, 1), make_item(1, 2)];
+        assert!(list.is_empty() && !list.is_singleton());
+        assert!(list.len_at_least(0) && !list.len_at_least(1));
+
+        list.push(&mut items[0]);
+        assert!(!list.is_empty() && list.is_singleton());
+        assert!(list.len_at_least(1) && !list.len_at_least(2));
+
+        list.push(&mut items[1]);
+        list.push(&mut items[2]);
+        assert!(!list.is_singleton());
+        assert!(list.len_at_least(3) && !list.len_at_least(4));
+
+        // a corrupted count doesn't fool them
+        list.len = 1;
+        assert!(list.len_at_least(3) && !list.is_singleton());
+    }
+}
@@ -0,0 +1,265 @@
+use core::mem::MaybeUninit;
+use crate::RustyList;
+use crate::list_ops::links::Link;
+
+impl<T> RustyList<T> {
+    /// Shuffles the list in place, driven by a caller-provided random number source.
+    ///
+    /// `rng(bound)` must return a random number in `0..bound`; values outside are
+    /// reduced modulo `bound`. This keeps the crate free of any RNG dependency.
+    ///
+    /// The nodes are relinked with a merge shuffle: each half is shuffled and the halves
+    /// are merged by picking the next node from either side with probability proportional
+    /// to its remaining length, which makes every order equally likely. It costs O(n log n)
+    /// and allocates nothing. Nodes are moved within the list one at a time, so if `rng`
+    /// panics the list still holds every element.
+    pub fn shuffle(&mut self, mut rng: impl FnMut(usize) -> usize) {
+        self.shuffle_with(&mut rng);
+    }
+
+    /// Picks up to `k` elements uniformly at random into `out` without modifying the list.
+    ///
+    /// This is single-pass reservoir sampling (Algorithm R): the first `k` elements fill
+    /// the reservoir and element `i` afterwards replaces a random slot with probability
+    /// `k / (i + 1)`. `rng` follows the same convention as in `shuffle`. The order of the
+    /// sampled pointers in `out` is unspecified.
+    ///
+    /// # Returns
+    /// The number of pointers written, `min(k, len, out.len())`; that many leading slots
+    /// of `out` are initialized.
+    pub fn sample(
+        &self,
+        k: usize,
+        mut rng: impl FnMut(usize) -> usize,
+        out: &mut [MaybeUninit<*mut T>],
+    ) -> usize {
+        let k = core::cmp::min(k, out.len());
+        if k == 0 {
+            return 0;
+        }
+
+        let _guard = self.begin_traversal();
+        let mut seen = 0;
+        let mut current = self.head;
+        while let Some(node) = current {
+            let item = self.item_of(node);
+            let j = if seen < k { seen } else { rng(seen + 1) % (seen + 1) };
+            if j < k
+                && let Some(slot) = out.get_mut(j)
+            {
+                slot.write(item);
+            }
+            seen += 1;
+            current = unsafe { (*node.as_ptr()).next };
+        }
+
+        core::cmp::min(seen, k)
+    }
+
+    fn shuffle_with(&mut self, rng: &mut impl FnMut(usize) -> usize) {
+        let len = self.len;
+        self.shuffle_segment(None, len, rng);
+    }
+
+    /// Shuffles the `len` elements after `before`, or from the head when `before` is `None`.
+    ///
+    /// The halves are merged in place, each node taken from the right half being moved in
+    /// front of the left half's next node, so the list stays well formed while `rng` runs.
+    fn shuffle_segment(&mut self, before: Option<Link<T>>, len: usize, rng: &mut impl FnMut(usize) -> usize) {
+        if len < 2 {
+            return;
+        }
+
+        let left_len = len / 2;
+        self.shuffle_segment(before, left_len, rng);
+        let mut mid = before;
+        for _ in 0..left_len {
+            mid = self.next_link(mid);
+        }
+        self.shuffle_segment(mid, len - left_len, rng);
+
+        let (Some(mut left), Some(mut right)) = (self.next_link(before), self.next_link(mid)) else {
+            return;
+        };
+        let (mut left_len, mut right_len) = (left_len, len - left_len);
+        while left_len > 0 && right_len > 0 {
+            let total = left_len + right_len;
+            if rng(total) % total < left_len {
+                left_len -= 1;
+                match unsafe { (*left.as_ptr()).next } {
+                    Some(next) => left = next,
+                    None => return,
+                }
+            } else {
+                right_len -= 1;
+                let next = unsafe { (*right.as_ptr()).next };
+                unsafe { self.move_after((*left.as_ptr()).prev, right) };
+                match next {
+                    Some(next) => right = next,
+                    None => return,
+                }
+            }
+        }
+    }
+
+    /// The node after `node`, or the head when `node` is `None`.
+    fn next_link(&self, node: Option<Link<T>>) -> Option<Link<T>> {
+        match node {
+            Some(node) => unsafe { (*node.as_ptr()).next },
+            None => self.head,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec;
+    use std::vec::Vec;
+    use crate::{RustyList, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    fn xorshift(seed: &mut u32) -> impl FnMut(usize) -> usize + '_ {
+        move |bound| {
+            *seed ^= *seed << 13;
+            *seed ^= *seed >> 17;
+            *seed ^= *seed << 5;
+            *seed as usize % bound
+        }
+    }
+
+    #[test]
+    fn shuffle_keeps_every_element() {
+        let mut items: Vec<TestItem> = (0..33).map(make_item).collect();
+        let mut list = RustyList::<TestItem>::new();
+        for item in items.iter_mut() {
+            list.push(item);
+        }
+
+        let mut seed = 0xdead_beefu32;
+        list.shuffle(xorshift(&mut seed));
+
+        let mut vals = list.iter().map(|item| item.value).collect::<Vec<_>>();
+        assert!(list.scrub(usize::MAX, &mut crate::ScrubCursor::new()).completed_pass);
+        assert_ne!(vals, (0..33).collect::<Vec<_>>());
+        vals.sort();
+        assert_eq!(vals, (0..33).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn shuffle_reaches_every_permutation_of_three() {
+        let mut seen = [0u32; 6];
+        let mut seed = 0x1357_9bdfu32;
+
+        for _ in 0..600 {
+            let mut items = [make_item(0), make_item(1), make_item(2)];
+            let mut list = RustyList::<TestItem>::new();
+            for item in items.iter_mut() {
+                list.push(item);
+            }
+
+            list.shuffle(xorshift(&mut seed));
+            let index = match list.iter().map(|item| item.value).collect::<Vec<_>>()[..] {
+                [0, 1, 2] => 0,
+                [0, 2, 1] => 1,
+                [1, 0, 2] => 2,
+                [1, 2, 0] => 3,
+                [2, 0, 1] => 4,
+                [2, 1, 0] => 5,
+                _ => unreachable!(),
+            };
+            seen[index] += 1;
+        }
+
+        // roughly uniform: every permutation shows up a fair number of times
+        assert!(seen.iter().all(|&count| count > 60), "{seen:?}");
+    }
+
+    #[test]
+    fn sample_picks_distinct_elements_without_touching_list() {
+        use core::mem::MaybeUninit;
+
+        let mut items: Vec<TestItem> = (0..20).map(make_item).collect();
+        let mut list = RustyList::<TestItem>::new();
+        for item in items.iter_mut() {
+            list.push(item);
+        }
+
+        let mut seed = 0x0bad_cafeu32;
+        let mut out = [MaybeUninit::<*mut TestItem>::uninit(); 5];
+        assert_eq!(list.sample(5, xorshift(&mut seed), &mut out), 5);
+
+        let mut picked: Vec<i32> = out.iter().map(|p| unsafe { (*p.assume_init()).value }).collect();
+        picked.sort();
+        picked.dedup();
+        assert_eq!(picked.len(), 5);
+        assert_eq!(list.iter().map(|item| item.value).collect::<Vec<_>>(), (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn sample_is_bounded_by_len_and_buffer() {
+        use core::mem::MaybeUninit;
+
+        let mut items: Vec<TestItem> = (0..3).map(make_item).collect();
+        let mut list = RustyList::<TestItem>::new();
+        for item in items.iter_mut() {
+            list.push(item);
+        }
+
+        let mut out = [MaybeUninit::<*mut TestItem>::uninit(); 8];
+        assert_eq!(list.sample(8, |_| unreachable!(), &mut out), 3);
+        assert_eq!(list.sample(2, |bound| bound - 1, &mut out[..1]), 1);
+        assert_eq!(list.sample(0, |_| 0, &mut out), 0);
+    }
+
+    #[test]
+    fn sample_is_roughly_uniform() {
+        use core::mem::MaybeUninit;
+
+        let mut items: Vec<TestItem> = (0..4).map(make_item).collect();
+        let mut list = RustyList::<TestItem>::new();
+        for item in items.iter_mut() {
+            list.push(item);
+        }
+
+        let mut hits = [0u32; 4];
+        let mut seed = 0x2468_ace0u32;
+        let mut out = [MaybeUninit::<*mut TestItem>::uninit(); 1];
+        for _ in 0..800 {
+            list.sample(1, xorshift(&mut seed), &mut out);
+            hits[unsafe { (*out[0].assume_init()).value } as usize] += 1;
+        }
+
+        assert!(hits.iter().all(|&count| count > 120), "{hits:?}");
+    }
+
+    #[test]
+    fn shuffle_small_lists() {
+        let mut list = RustyList::<TestItem>::new();
+        list.shuffle(|_| unreachable!());
+        assert_eq!(list.len, 0);
+
+        let mut a = make_item(1);
+        list.push(&mut a);
+        list.shuffle(|_| unreachable!());
+        assert_eq!(list.iter().map(|item| item.value).collect::<Vec<_>>(), vec![1]);
+    }
+}
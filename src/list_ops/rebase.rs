@@ -0,0 +1,150 @@
+use core::ptr::NonNull;
+use crate::{RustyList, RustyListNode};
+
+/// Translates a link that points into `[old_base, old_base + len)` to the same offset
+/// from `new_base`. Links outside the range are returned unchanged.
+#[inline(always)]
+fn rebase_link<T>(
+    link: Option<NonNull<RustyListNode<T>>>,
+    old_base: *const u8,
+    new_base: *const u8,
+    len: usize,
+) -> Option<NonNull<RustyListNode<T>>> {
+    let ptr = link?.as_ptr();
+    let delta = ptr.addr().wrapping_sub(old_base.addr());
+
+    if delta < len {
+        // derive the new pointer from `new_base` so it carries the new region's provenance
+        let moved = new_base.wrapping_add(delta) as *mut RustyListNode<T>;
+        NonNull::new(moved)
+    } else {
+        link
+    }
+}
+
+impl<T> RustyListNode<T> {
+    /// Fixes up this node's links after a block of memory was relocated.
+    ///
+    /// Any `prev`/`next` link that pointed into `[old_base, old_base + len)` is moved to the
+    /// same offset from `new_base`. The node itself is not dereferenced through its links,
+    /// so this is safe to call on any node.
+    pub fn rebase_links(&mut self, old_base: *const u8, new_base: *const u8, len: usize) {
+        self.prev = rebase_link(self.prev, old_base, new_base, len);
+        self.next = rebase_link(self.next, old_base, new_base, len);
+    }
+}
+
+impl<T> RustyList<T> {
+    /// Fixes up every link after the `len` bytes at `old_base` were moved to `new_base`.
+    ///
+    /// Use this after a contiguous block of elements (e.g. a slab) was `memcpy`'d or grown
+    /// into new storage. The list's `head`/`tail` and every node's links that pointed into
+    /// the old block are moved to the same offset in the new block. Elements outside the
+    /// block keep their address but their links into the block are fixed up too, and so do
+    /// attached `PersistentCursor`s on elements in the block.
+    ///
+    /// # Safety
+    /// - The block must already have been copied to `new_base`, and the old block must not
+    ///   be used by the list anymore.
+    /// - Every element of the list outside the block must still be valid.
+    /// - Attached `PersistentCursor`s must not themselves live in the block.
+    pub unsafe fn rebase(&mut self, old_base: *const u8, new_base: *const u8, len: usize) {
+        self.assert_not_traversing("rebase");
+        if len == 0 || old_base == new_base {
+            return;
+        }
+
+        self.head = rebase_link(self.head, old_base, new_base, len);
+        self.tail = rebase_link(self.tail, old_base, new_base, len);
+        self.map_cursors(|position| rebase_link(position, old_base, new_base, len));
+
+        // walk the list, fixing each node before following its `next` link
+        let mut current = self.head;
+        while let Some(node_ptr) = current {
+            let node = unsafe { &mut *node_ptr.as_ptr() };
+            node.rebase_links(old_base, new_base, len);
+            current = node.next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem::{size_of_val, MaybeUninit};
+    use std::vec;
+    use std::vec::Vec;
+    use crate::{RustyList, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn rebase_follows_relocated_block() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut old = [make_item(1), make_item(2), make_item(3)];
+        let mut outside = make_item(99);
+
+        list.push(&mut old[0]);
+        list.push(&mut old[1]);
+        list.push(&mut outside);
+        list.push(&mut old[2]);
+
+        let mut new: [MaybeUninit<TestItem>; 3] = [const { MaybeUninit::uninit() }; 3];
+        let old_base = old.as_ptr() as *const u8;
+        let new_base = new.as_mut_ptr() as *mut u8;
+        let len = size_of_val(&old);
+
+        unsafe {
+            core::ptr::copy_nonoverlapping(old_base, new_base, len);
+            list.rebase(old_base, new_base, len);
+        }
+
+        // scribble on the old block, the list must not see it anymore
+        for item in old.iter_mut() {
+            item.value = -1;
+        }
+
+        assert_eq!(list.iter().map(|item| item.value).collect::<Vec<_>>(), vec![1, 2, 99, 3]);
+        assert_eq!(list.head.unwrap().as_ptr() as *const u8, unsafe { new_base.add(list.offset) });
+
+        let tail = unsafe { &*list.tail.unwrap().as_ptr() };
+        assert!(tail.next_node().is_none());
+        assert_eq!(
+            tail.prev_node().unwrap().as_ptr() as *const u8,
+            &outside.node as *const _ as *const u8,
+        );
+    }
+
+    #[test]
+    fn rebase_links_leaves_outside_links_alone() {
+        let mut node = RustyListNode::<TestItem>::new();
+        let block = [0u8; 64];
+        let moved = [0u8; 64];
+        let inside = unsafe { block.as_ptr().add(16) } as *mut RustyListNode<TestItem>;
+        let elsewhere = core::ptr::NonNull::<RustyListNode<TestItem>>::dangling();
+
+        node.prev = core::ptr::NonNull::new(inside);
+        node.next = Some(elsewhere);
+        node.rebase_links(block.as_ptr(), moved.as_ptr(), block.len());
+
+        assert_eq!(node.prev_node().unwrap().as_ptr() as *const u8, unsafe { moved.as_ptr().add(16) });
+        assert_eq!(node.next_node(), Some(elsewhere));
+    }
+}
@@ -0,0 +1,161 @@
+// registry.rs
+// Process-wide registry of named lists, for dumping every queue at once.
+use core::fmt;
+use std::boxed::Box;
+use std::sync::{Mutex, PoisonError};
+use std::vec::Vec;
+use crate::RustyList;
+
+/// Identifies a list registered with `register_debug`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ListId(u64);
+
+impl ListId {
+    /// The numeric ID, as printed by `dump_all`.
+    pub fn get(self) -> u64 {
+        self.0
+    }
+}
+
+/// Writes one registered list's report line.
+type ReportFn = Box<dyn Fn(&mut dyn fmt::Write) -> fmt::Result + Send>;
+
+struct Entry {
+    id: ListId,
+    name: &'static str,
+    report: ReportFn,
+}
+
+struct Registry {
+    next_id: u64,
+    entries: Vec<Entry>,
+}
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry { next_id: 0, entries: Vec::new() });
+
+fn registry() -> std::sync::MutexGuard<'static, Registry> {
+    REGISTRY.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl<T: Sync + 'static> RustyList<T> {
+    /// Registers this list under `name` for `dump_all`.
+    ///
+    /// `summary` writes a short description of the head element, e.g. its ID and state.
+    /// The returned `ListId` is unique for the life of the process and is passed to
+    /// `unregister_debug` to remove the entry again. `dump_all` may run on any thread and
+    /// hands `summary` a `&T` from there, hence the `T: Sync` bound.
+    ///
+    /// # Safety
+    /// The list must stay at this address until it is unregistered, and must not be
+    /// modified while `dump_all` runs: the dump reads `len` and the head element without
+    /// any synchronization of its own.
+    pub unsafe fn register_debug(
+        &self,
+        name: &'static str,
+        summary: fn(&T, &mut dyn fmt::Write) -> fmt::Result,
+    ) -> ListId {
+        let list = self as *const Self as usize;
+        let report = move |w: &mut dyn fmt::Write| {
+            let list = unsafe { &*(list as *const Self) };
+            write!(w, "len={} head=", list.len)?;
+            match list.head_item() {
+                Some(item) => summary(unsafe { &*item }, w),
+                None => w.write_str("-"),
+            }
+        };
+
+        let mut registry = registry();
+        let id = ListId(registry.next_id);
+        registry.next_id += 1;
+        registry.entries.push(Entry { id, name, report: Box::new(report) });
+        id
+    }
+}
+
+/// Removes a list registered with `register_debug`.
+///
+/// # Returns
+/// `true` if `id` was registered.
+pub fn unregister_debug(id: ListId) -> bool {
+    let mut registry = registry();
+    let before = registry.entries.len();
+    registry.entries.retain(|entry| entry.id != id);
+    registry.entries.len() != before
+}
+
+/// Writes one line per registered list, in registration order: its name, ID, length and
+/// a summary of its head element (`-` when empty).
+///
+/// Meant for a "print all queues" command on a device that has stopped making progress,
+/// so the lines carry just enough to see which queue is stuck and on what.
+pub fn dump_all<W: fmt::Write>(w: &mut W) -> fmt::Result {
+    let registry = registry();
+    for entry in registry.entries.iter() {
+        write!(w, "{} #{} ", entry.name, entry.id.0)?;
+        (entry.report)(w)?;
+        writeln!(w)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt;
+    use std::string::String;
+    use super::*;
+    use crate::{RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    // SAFETY: the test only reads elements while no thread modifies them
+    unsafe impl Sync for TestItem {}
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    fn summary(item: &TestItem, w: &mut dyn fmt::Write) -> fmt::Result {
+        write!(w, "value {}", item.value)
+    }
+
+    #[test]
+    fn dump_all_reports_registered_lists() {
+        let mut ready = RustyList::<TestItem>::new();
+        let blocked = RustyList::<TestItem>::new();
+        let mut a = make_item(4);
+        ready.push(&mut a);
+
+        let ready_id = unsafe { ready.register_debug("test-ready", summary) };
+        let blocked_id = unsafe { blocked.register_debug("test-blocked", summary) };
+        assert_ne!(ready_id, blocked_id);
+
+        // other tests may register lists of their own concurrently
+        let mut out = String::new();
+        dump_all(&mut out).unwrap();
+        let ready_line = std::format!("test-ready #{} len=1 head=value 4", ready_id.get());
+        let blocked_line = std::format!("test-blocked #{} len=0 head=-", blocked_id.get());
+        assert!(out.lines().any(|line| line == ready_line));
+        assert!(out.lines().any(|line| line == blocked_line));
+
+        assert!(unregister_debug(ready_id));
+        assert!(!unregister_debug(ready_id));
+        assert!(unregister_debug(blocked_id));
+        out.clear();
+        dump_all(&mut out).unwrap();
+        assert!(!out.contains("test-ready"));
+    }
+}
@@ -1,15 +1,50 @@
+use core::ptr::NonNull;
 use crate::{RustyList, RustyListNode};
 
 impl<T> RustyList<T> {
     /// Removes a node from the list.
     ///
+    /// An element whose node isn't linked is left alone and the list is unchanged.
+    ///
     /// # Safety
     /// - `item` must be a valid, non-null pointer to a `T` that contains a `RustyListNode<T>`.
     /// - The `offset` field of the list must be correct.
     pub fn remove(&mut self, item: &mut T) {
+        self.assert_not_traversing("remove");
+        let before = self.len;
         unsafe {
             self.remove_raw(item as *mut T);
         }
+        self.notify_watermarks(before);
+    }
+
+    /// Removes the element that embeds `node` and returns it.
+    ///
+    /// For callers whose external indexes store node pointers (e.g. a map from key to
+    /// node) rather than element pointers. Returns `None`, and leaves the list unchanged,
+    /// if the node isn't linked. Debug builds also check that the node is in this list
+    /// rather than another one, which costs a walk of the list.
+    ///
+    /// # Safety
+    /// `node` must point to a live `RustyListNode<T>` embedded in a `T` at this list's
+    /// offset.
+    pub unsafe fn remove_by_node(&mut self, node: NonNull<RustyListNode<T>>) -> Option<*mut T> {
+        self.assert_not_traversing("remove_by_node");
+        if !unsafe { (*node.as_ptr()).linked } {
+            rusty_warn!("RustyList::remove_by_node: node is not linked, ignoring");
+            return None;
+        }
+
+        let item = self.item_of(node);
+        debug_assert!(
+            self.find_item(item).is_some(),
+            "RustyList::remove_by_node: node belongs to a different list"
+        );
+
+        let before = self.len;
+        unsafe { self.remove_raw(item) };
+        self.notify_watermarks(before);
+        Some(item)
     }
 
     /// Unsafe internal function to remove a raw pointer from the list.
@@ -17,11 +52,21 @@ impl<T> RustyList<T> {
         if item.is_null() || self.len == 0 {
             return;
         }
+        self.paranoid_check_item(item);
 
         // Get pointer to RustyListNode<T> inside item
         let node_ptr = unsafe{(item as *mut u8).add(self.offset)} as *mut RustyListNode<T>;
         let node =unsafe{ &mut *node_ptr};
 
+        if !node.linked {
+            rusty_warn!("RustyList::remove: node is not linked, ignoring");
+            return;
+        }
+        self.paranoid_check_links(unsafe { NonNull::new_unchecked(node_ptr) });
+        self.move_cursors(unsafe { NonNull::new_unchecked(node_ptr) }, node.next);
+        #[cfg(feature = "replay")]
+        self.record_unlink(unsafe { NonNull::new_unchecked(node_ptr) });
+
         let node_next = node.next.map(|nn| nn.as_ptr());
         let node_prev = node.prev.map(|nn| nn.as_ptr());
 
@@ -57,11 +102,10 @@ impl<T> RustyList<T> {
         }
 
         // Clear the removed node's links
-        node.prev = None;
-        node.next = None;
+        node.clear_links();
 
         // Decrement list length
-        self.len -= 1;
+        self.len = self.len.saturating_sub(1);
 
         // Final cleanup if list is empty
         if self.len == 0 {
@@ -75,6 +119,7 @@ impl<T> RustyList<T> {
 
 #[cfg(test)]
 mod tests {
+    use core::ptr::NonNull;
     use std::vec;
     use crate::{RustyList, RustyListNode, HasRustyNode, rusty_offset};
 
@@ -183,5 +228,51 @@ mod tests {
 
         assert_eq!(vals, vec![1, 3]);
     }
-}
 
+    #[test]
+    fn remove_by_node_returns_the_element() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut a = make_item(1);
+        let mut b = make_item(2);
+        list.push(&mut a);
+        list.push(&mut b);
+
+        let node = NonNull::from(&mut b.node);
+        let removed = unsafe { list.remove_by_node(node) };
+        assert_eq!(removed, Some(&mut b as *mut TestItem));
+        assert_eq!(list.len, 1);
+        assert!(!b.node.is_linked());
+
+        // a second removal finds the node unlinked
+        assert_eq!(unsafe { list.remove_by_node(NonNull::from(&mut b.node)) }, None);
+        assert_eq!(list.len, 1);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "belongs to a different list")]
+    fn remove_by_node_rejects_foreign_node() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut other = RustyList::<TestItem>::new();
+        let mut a = make_item(1);
+        let mut b = make_item(2);
+        list.push(&mut a);
+        other.push(&mut b);
+
+        unsafe { list.remove_by_node(NonNull::from(&mut b.node)) };
+    }
+
+    #[test]
+    fn remove_unlinked_node_is_a_no_op() {
+        let mut list = RustyList::<TestItem>::new_with_order(cmp);
+        let mut a = make_item(1);
+        let mut stray = make_item(2);
+
+        list.insert(&mut a);
+        list.remove(&mut stray);
+
+        assert_eq!(list.len, 1);
+        assert_eq!(list.head, list.tail);
+        assert!(a.node.is_linked());
+    }
+}
@@ -0,0 +1,403 @@
+// replay.rs
+// Mutation log for replaying how a list reached its state, compiled in by the `replay` feature.
+use core::ptr::NonNull;
+use crate::RustyList;
+use crate::list_ops::links::Link;
+
+/// A structural change, naming elements by the tags the list's tag function gave them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayOp {
+    /// `tag` was linked right after `after`, or at the head when `after` is `None`.
+    Link { tag: u32, after: Option<u32> },
+    /// `tag` was unlinked.
+    Unlink { tag: u32 },
+    /// Every element was dropped from the list at once.
+    Clear,
+}
+
+/// One recorded change and the tick it happened at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplayEntry {
+    /// The log's clock when the change was made, or its sequence number if it has none.
+    pub tick: u32,
+    pub op: ReplayOp,
+}
+
+impl ReplayEntry {
+    /// A placeholder for initializing log buffers.
+    pub const EMPTY: Self = Self { tick: 0, op: ReplayOp::Clear };
+}
+
+/// A ring buffer of `ReplayEntry`s over caller-provided storage.
+///
+/// Once the buffer is full each new entry overwrites the oldest one, so the log always
+/// holds the most recent changes.
+#[derive(Debug)]
+pub struct ReplayLog<'a> {
+    entries: &'a mut [ReplayEntry],
+    recorded: usize,
+    clock: Option<fn() -> u32>,
+}
+
+impl<'a> ReplayLog<'a> {
+    /// Creates an empty log over `entries`, ticking once per recorded change.
+    pub fn new(entries: &'a mut [ReplayEntry]) -> Self {
+        Self { entries, recorded: 0, clock: None }
+    }
+
+    /// Creates an empty log over `entries` that stamps each change with `clock()`, such
+    /// as a cycle counter or the scheduler tick.
+    pub fn with_clock(entries: &'a mut [ReplayEntry], clock: fn() -> u32) -> Self {
+        Self { entries, recorded: 0, clock: Some(clock) }
+    }
+
+    /// Number of entries held.
+    pub fn len(&self) -> usize {
+        self.recorded.min(self.entries.len())
+    }
+
+    /// Returns `true` if nothing has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of changes recorded since the log was created, including overwritten ones.
+    pub fn recorded(&self) -> usize {
+        self.recorded
+    }
+
+    /// Number of entries overwritten because the buffer was full.
+    pub fn lost(&self) -> usize {
+        self.recorded - self.len()
+    }
+
+    /// The entries held, oldest first.
+    pub fn entries(&self) -> impl Iterator<Item = &ReplayEntry> + '_ {
+        let split = self.recorded.checked_rem(self.entries.len()).unwrap_or(0);
+        let (newer, older) = self.entries.split_at(split);
+        let older = if self.recorded > self.entries.len() { older } else { &older[..0] };
+        older.iter().chain(newer)
+    }
+
+    /// Drops every entry, keeping the storage and clock.
+    pub fn clear(&mut self) {
+        self.recorded = 0;
+    }
+
+    fn record(&mut self, op: ReplayOp) {
+        let tick = match self.clock {
+            Some(clock) => clock(),
+            None => self.recorded as u32,
+        };
+        let slot = self.recorded.checked_rem(self.entries.len());
+        if let Some(entry) = slot.and_then(|slot| self.entries.get_mut(slot)) {
+            *entry = ReplayEntry { tick, op };
+        }
+        self.recorded = self.recorded.wrapping_add(1);
+    }
+}
+
+/// The log a list records into, installed by `record_into`.
+pub(crate) struct Recorder<T> {
+    log: NonNull<ReplayLog<'static>>,
+    tag: fn(&T) -> u32,
+}
+
+impl<T> Clone for Recorder<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Recorder<T> {}
+
+impl<T> core::fmt::Debug for Recorder<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Recorder").field("log", &self.log).finish_non_exhaustive()
+    }
+}
+
+/// What `replay_into` did with the entries of a log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Replayed {
+    /// Entries applied to the list.
+    pub applied: usize,
+    /// Entries that didn't fit the list's state and were left out.
+    pub skipped: usize,
+}
+
+impl<T> RustyList<T> {
+    /// Records every structural change to this list into `log`, naming elements by `tag`.
+    ///
+    /// Recording starts with a `Clear` followed by a `Link` for each current element, so a
+    /// log that hasn't wrapped replays to the exact state of the list. From then on every
+    /// push, pop, insert, remove and move adds entries; element payloads are never
+    /// recorded, only the tags. Operations that only rewrite pointers, like `rebase`, add
+    /// nothing. `tag` should give each element a distinct value that survives the element
+    /// moving, such as a task ID.
+    ///
+    /// # Safety
+    /// `log` must stay valid and must not move until `stop_recording` is called or the list
+    /// is dropped, and must not be read while the list may still write to it.
+    pub unsafe fn record_into(&mut self, log: &mut ReplayLog<'_>, tag: fn(&T) -> u32) {
+        self.replay = Some(Recorder { log: NonNull::from(log).cast(), tag });
+        self.record_rebuild();
+    }
+
+    /// Stops recording into the log set by `record_into`.
+    pub fn stop_recording(&mut self) {
+        self.replay = None;
+    }
+
+    /// Returns `true` while the list is recording into a log.
+    pub fn is_recording(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    #[inline(always)]
+    fn record(&self, op: impl FnOnce(fn(&T) -> u32) -> ReplayOp) {
+        if let Some(recorder) = self.replay {
+            let op = op(recorder.tag);
+            unsafe { (*recorder.log.as_ptr()).record(op) };
+        }
+    }
+
+    fn tag_of(&self, tag: fn(&T) -> u32, node: Link<T>) -> u32 {
+        tag(unsafe { &*self.item_of(node) })
+    }
+
+    /// Records that the linked `node` was linked after its current `prev`.
+    #[inline(always)]
+    pub(crate) fn record_link(&self, node: Link<T>) {
+        self.record(|tag| ReplayOp::Link {
+            tag: self.tag_of(tag, node),
+            after: unsafe { (*node.as_ptr()).prev }.map(|prev| self.tag_of(tag, prev)),
+        });
+    }
+
+    /// Records that `node` is about to be unlinked.
+    #[inline(always)]
+    pub(crate) fn record_unlink(&self, node: Link<T>) {
+        self.record(|tag| ReplayOp::Unlink { tag: self.tag_of(tag, node) });
+    }
+
+    /// Records that every element was dropped at once.
+    #[inline(always)]
+    pub(crate) fn record_clear(&self) {
+        self.record(|_| ReplayOp::Clear);
+    }
+
+    /// Records the `Link`s for `first` and every node after it, once they are linked.
+    pub(crate) fn record_links_from(&self, first: Option<Link<T>>) {
+        if self.replay.is_none() {
+            return;
+        }
+        let mut current = first;
+        while let Some(node) = current {
+            self.record_link(node);
+            current = unsafe { (*node.as_ptr()).next };
+        }
+    }
+
+    /// Records the `Unlink`s for `first` through `last`, before they are detached.
+    pub(crate) fn record_unlinks(&self, first: Link<T>, last: Link<T>) {
+        if self.replay.is_none() {
+            return;
+        }
+        let mut current = Some(first);
+        while let Some(node) = current {
+            self.record_unlink(node);
+            current = if node == last { None } else { unsafe { (*node.as_ptr()).next } };
+        }
+    }
+
+    /// Records the whole list as rebuilt from scratch, after its links were rewritten in
+    /// bulk.
+    pub(crate) fn record_rebuild(&self) {
+        self.record_clear();
+        self.record_links_from(self.head);
+    }
+}
+
+/// Applies the entries held in `log`, oldest first, to `list`.
+///
+/// `factory` maps a tag to the element standing for it in `list`, for example by
+/// indexing an array of spare elements with the tag, and is called again every time the
+/// tag comes up. Replaying into an empty list a log that recorded from the start
+/// rebuilds the recorded list's order exactly.
+///
+/// A log that has wrapped (`lost() > 0`) starts part way through the history, so its first
+/// entries may unlink elements that were never linked or link after ones that are
+/// missing. Those entries are skipped and counted, and the rest still apply.
+///
+/// # Safety
+/// - `factory` must return a valid element every time, the same one for the same tag and
+///   different ones for different tags.
+/// - The elements must not be linked into any other list, and must stay valid for as long
+///   as they are in `list`.
+pub unsafe fn replay_into<T>(
+    list: &mut RustyList<T>,
+    log: &ReplayLog<'_>,
+    mut factory: impl FnMut(u32) -> *mut T,
+) -> Replayed {
+    list.assert_not_traversing("replay_into");
+    let mut replayed = Replayed::default();
+    let is_linked = |list: &RustyList<T>, item: *mut T| unsafe { (*list.node_of(item).as_ptr()).linked };
+
+    for entry in log.entries() {
+        let applied = match entry.op {
+            ReplayOp::Link { tag, after } => {
+                let item = factory(tag);
+                let prev = after.map(&mut factory);
+                if is_linked(list, item) || prev.is_some_and(|prev| !is_linked(list, prev)) {
+                    false
+                } else {
+                    let before = list.len;
+                    let node = list.claim_node(item, "replay_into");
+                    unsafe { list.link_after(prev.map(|prev| list.node_of(prev)), node) };
+                    list.notify_watermarks(before);
+                    true
+                }
+            }
+            ReplayOp::Unlink { tag } => {
+                let item = factory(tag);
+                if is_linked(list, item) {
+                    let before = list.len;
+                    unsafe { list.unlink_node(list.node_of(item)) };
+                    list.notify_watermarks(before);
+                    true
+                } else {
+                    false
+                }
+            }
+            ReplayOp::Clear => {
+                list.drain().for_each(drop);
+                true
+            }
+        };
+
+        if applied {
+            replayed.applied += 1;
+        } else {
+            replayed.skipped += 1;
+        }
+    }
+    replayed
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+    use super::*;
+    use crate::{RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn make_items(count: i32) -> Vec<TestItem> {
+        (0..count).map(|value| TestItem { value, node: RustyListNode::new() }).collect()
+    }
+
+    fn tag(item: &TestItem) -> u32 {
+        item.value as u32
+    }
+
+    fn cmp(a: *const TestItem, b: *const TestItem) -> i32 {
+        unsafe { (*b).value.cmp(&(*a).value) as i32 }
+    }
+
+    fn replay(log: &ReplayLog<'_>, copies: &mut [TestItem]) -> (Vec<i32>, Replayed) {
+        let mut copy = RustyList::<TestItem>::new();
+        let base = copies.as_mut_ptr();
+        let replayed = unsafe { replay_into(&mut copy, log, |tag| base.add(tag as usize)) };
+        (copy.iter().map(|item| item.value).collect::<Vec<_>>(), replayed)
+    }
+
+    #[test]
+    fn replay_rebuilds_the_recorded_list() {
+        let mut items = make_items(6);
+        let mut buffer = [ReplayEntry::EMPTY; 64];
+        let mut log = ReplayLog::new(&mut buffer);
+        let mut list = RustyList::<TestItem>::new();
+        let [a, b, c, d, e, f] = &mut items[..] else { unreachable!() };
+        list.push(a);
+        list.push(b);
+
+        unsafe { list.record_into(&mut log, tag) };
+        list.push(c);
+        list.push(d);
+        list.push(e);
+        list.remove(b);
+        list.pop();
+        list.insert_sorted_by(cmp, f);
+        list.sort_by(cmp);
+        list.stop_recording();
+        list.push(b);
+
+        let mut copies = make_items(6);
+        let (replayed, counts) = replay(&log, &mut copies);
+        assert_eq!(replayed, [5, 4, 3, 2]);
+        assert_eq!(counts.skipped, 0);
+        assert_eq!(log.entries().next().map(|entry| entry.op), Some(ReplayOp::Clear));
+        assert!(log.entries().zip(log.entries().skip(1)).all(|(x, y)| x.tick < y.tick));
+    }
+
+    #[test]
+    fn bulk_changes_are_recorded() {
+        let mut items = make_items(8);
+        let mut buffer = [ReplayEntry::EMPTY; 64];
+        let mut log = ReplayLog::new(&mut buffer);
+        let mut list = RustyList::<TestItem>::new();
+        let mut other = RustyList::<TestItem>::new();
+        unsafe { list.record_into(&mut log, tag) };
+
+        let (mine, theirs) = items.split_at_mut(4);
+        mine.iter_mut().for_each(|item| list.push(item));
+        theirs.iter_mut().for_each(|item| other.push(item));
+        list.push_many(&mut other);
+        list.retain(|item| item.value % 3 != 0);
+        let mut front = list.pop_many(2);
+        list.push_many(&mut front);
+        list.shuffle(|bound| 3 % bound);
+        let recorded = list.iter().map(|item| item.value).collect::<Vec<_>>();
+
+        let mut copies = make_items(8);
+        assert_eq!(replay(&log, &mut copies).0, recorded);
+    }
+
+    #[test]
+    fn wrapped_logs_keep_the_newest_entries() {
+        let mut items = make_items(4);
+        let mut buffer = [ReplayEntry::EMPTY; 3];
+        let mut log = ReplayLog::new(&mut buffer);
+        let mut list = RustyList::<TestItem>::new();
+        unsafe { list.record_into(&mut log, tag) };
+        items.iter_mut().for_each(|item| list.push(item));
+        list.pop();
+        list.pop();
+
+        assert_eq!((log.len(), log.recorded(), log.lost()), (3, 7, 4));
+        let ops: Vec<ReplayOp> = log.entries().map(|entry| entry.op).collect();
+        assert_eq!(ops, [
+            ReplayOp::Link { tag: 3, after: Some(2) },
+            ReplayOp::Unlink { tag: 0 },
+            ReplayOp::Unlink { tag: 1 },
+        ]);
+
+        // 3 links after 2, which the truncated log never linked; 0 and 1 were never linked
+        let mut copies = make_items(4);
+        let (replayed, counts) = replay(&log, &mut copies);
+        assert!(replayed.is_empty());
+        assert_eq!(counts, Replayed { applied: 0, skipped: 3 });
+    }
+}
@@ -0,0 +1,152 @@
+use crate::RustyList;
+
+impl<T> RustyList<T> {
+    /// Moves `item` to its sorted position after its key was changed in place.
+    ///
+    /// The decrease-key (or increase-key) step of a priority scheduler: the element is
+    /// checked against its old neighbours first and left alone if it still fits there;
+    /// otherwise it is unlinked and the list is searched outward from where it was, so
+    /// small key changes cost O(1) comparisons instead of a full re-insert from the head.
+    /// Like `insert`, the element ends up after any elements equal to it.
+    ///
+    /// Does nothing if the list has no `order_function` or `item` isn't linked.
+    pub fn reposition(&mut self, item: &mut T) {
+        self.assert_not_traversing("reposition");
+        let Some(cmp_fn) = self.order_function else {
+            return;
+        };
+
+        let item = item as *mut T;
+        let node = self.node_of(item);
+        let (prev, next) = unsafe {
+            let n = &*node.as_ptr();
+            if !n.linked {
+                rusty_warn!("RustyList::reposition: node is not linked, ignoring");
+                return;
+            }
+            (n.prev, n.next)
+        };
+
+        let before_prev = prev.is_some_and(|p| cmp_fn(self.item_of(p), item) > 0);
+        let after_next = next.is_some_and(|n| cmp_fn(item, self.item_of(n)) > 0);
+        if !before_prev && !after_next {
+            return;
+        }
+
+        #[cfg(feature = "reposition-hook")]
+        let old = self.neighbors_of(node);
+        unsafe { self.unlink_for_move(node) };
+
+        let new_prev = if before_prev {
+            // walk back to the last element that is not greater than the item
+            let mut p = prev;
+            while let Some(candidate) = p {
+                if cmp_fn(self.item_of(candidate), item) <= 0 {
+                    break;
+                }
+                p = unsafe { (*candidate.as_ptr()).prev };
+            }
+            p
+        } else {
+            // walk forward past every element that is not greater than the item
+            let mut last = next;
+            let mut n = next.and_then(|n| unsafe { (*n.as_ptr()).next });
+            while let Some(candidate) = n {
+                if cmp_fn(self.item_of(candidate), item) > 0 {
+                    break;
+                }
+                last = Some(candidate);
+                n = unsafe { (*candidate.as_ptr()).next };
+            }
+            last
+        };
+
+        unsafe { self.link_after(new_prev, node) };
+        #[cfg(feature = "reposition-hook")]
+        self.notify_reposition(node, old);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+    use std::vec;
+    use std::vec::Vec;
+    use crate::{RustyList, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    std::thread_local! {
+        static CALLS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    fn cmp(a: *const TestItem, b: *const TestItem) -> i32 {
+        CALLS.with(|calls| calls.set(calls.get() + 1));
+        unsafe { (*a).value.cmp(&(*b).value) as i32 }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn reposition_moves_both_ways() {
+        let mut list = RustyList::<TestItem>::new_with_order(cmp);
+        let mut items: Vec<TestItem> = (0..6).map(|v| make_item(v * 10)).collect();
+        for item in items.iter_mut() {
+            list.insert(item);
+        }
+
+        // decrease-key to the front
+        items[4].value = -5;
+        list.reposition(&mut items[4]);
+        assert_eq!(list.iter().map(|item| item.value).collect::<Vec<_>>(), vec![-5, 0, 10, 20, 30, 50]);
+
+        // increase-key to the back
+        items[1].value = 99;
+        list.reposition(&mut items[1]);
+        assert_eq!(list.iter().map(|item| item.value).collect::<Vec<_>>(), vec![-5, 0, 20, 30, 50, 99]);
+
+        // equal keys go after existing ones
+        items[0].value = 30;
+        list.reposition(&mut items[0]);
+        assert_eq!(list.iter().map(|item| item.value).collect::<Vec<_>>(), vec![-5, 20, 30, 30, 50, 99]);
+        assert_eq!(items[0].node.prev_node(), Some(core::ptr::NonNull::from(&items[3].node)));
+        assert_eq!(list.len, 6);
+        assert!(list.scrub(usize::MAX, &mut crate::ScrubCursor::new()).completed_pass);
+    }
+
+    #[test]
+    fn small_key_change_costs_few_comparisons() {
+        let mut list = RustyList::<TestItem>::new_with_order(cmp);
+        let mut items: Vec<TestItem> = (0..100).map(|v| make_item(v * 10)).collect();
+        for item in items.iter_mut() {
+            list.insert(item);
+        }
+
+        CALLS.with(|calls| calls.set(0));
+        items[50].value = 505;
+        list.reposition(&mut items[50]);
+        assert_eq!(CALLS.with(Cell::get), 2);
+
+        CALLS.with(|calls| calls.set(0));
+        items[50].value = 515;
+        list.reposition(&mut items[50]);
+        assert!(CALLS.with(Cell::get) <= 4);
+        assert_eq!(list.iter().map(|item| item.value).collect::<Vec<_>>()[50..53], [510, 515, 520]);
+    }
+}
@@ -0,0 +1,141 @@
+// reposition_hook.rs
+// Callback run when `reposition` or `move_to_front_by_handle` moves an element
+// (feature `reposition-hook`).
+use crate::RustyList;
+use crate::list_ops::links::Link;
+
+/// The elements on either side of an element, passed to the reposition hook.
+#[derive(Debug)]
+pub struct Neighbors<T> {
+    /// The element before, or `None` at the head.
+    pub prev: Option<*mut T>,
+    /// The element after, or `None` at the tail.
+    pub next: Option<*mut T>,
+}
+
+impl<T> Clone for Neighbors<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Neighbors<T> {}
+
+impl<T> PartialEq for Neighbors<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.prev == other.prev && self.next == other.next
+    }
+}
+
+impl<T> Eq for Neighbors<T> {}
+
+/// Callback installed by `set_reposition_hook`: the moved element, then its old and new
+/// neighbours.
+pub type RepositionHook<T> = fn(*mut T, Neighbors<T>, Neighbors<T>);
+
+impl<T> RustyList<T> {
+    /// Calls `hook` whenever `reposition` or `move_to_front_by_handle` moves an element.
+    ///
+    /// The hook receives the element with its neighbours before and after the move, so
+    /// structures kept alongside the list (priority bitmaps, indexes, cached head or tail
+    /// pointers) can be patched for the one element that moved instead of rebuilt. It runs
+    /// after the element is relinked and only if it actually changed place.
+    pub fn set_reposition_hook(&mut self, hook: RepositionHook<T>) {
+        self.reposition_hook = Some(hook);
+    }
+
+    /// Removes the hook set by `set_reposition_hook`.
+    pub fn clear_reposition_hook(&mut self) {
+        self.reposition_hook = None;
+    }
+
+    /// The neighbours of the linked `node`.
+    pub(crate) fn neighbors_of(&self, node: Link<T>) -> Neighbors<T> {
+        let n = unsafe { &*node.as_ptr() };
+        Neighbors {
+            prev: n.prev.map(|p| self.item_of(p)),
+            next: n.next.map(|nx| self.item_of(nx)),
+        }
+    }
+
+    /// Calls the reposition hook, if any, for `node` that was at `old`.
+    #[inline(always)]
+    pub(crate) fn notify_reposition(&self, node: Link<T>, old: Neighbors<T>) {
+        if let Some(hook) = self.reposition_hook {
+            let new = self.neighbors_of(node);
+            self.run_callback(|| hook(self.item_of(node), old, new));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::RefCell;
+    use std::vec::Vec;
+    use crate::{Neighbors, RustyList, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    std::thread_local! {
+        static MOVES: RefCell<Vec<Move>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// The moved value and the values before it, as seen by the reposition hook.
+    type Move = (i32, Option<i32>, Option<i32>);
+
+    fn cmp(a: *const TestItem, b: *const TestItem) -> i32 {
+        unsafe { (*a).value.cmp(&(*b).value) as i32 }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    fn record_move(item: *mut TestItem, old: Neighbors<TestItem>, new: Neighbors<TestItem>) {
+        let value = |p: Option<*mut TestItem>| p.map(|p| unsafe { (*p).value });
+        assert_ne!(old, new);
+        MOVES.with(|moves| moves.borrow_mut().push((unsafe { (*item).value }, value(old.prev), value(new.prev))));
+    }
+
+    #[test]
+    fn hook_reports_old_and_new_neighbours() {
+        let mut list = RustyList::<TestItem>::new_with_order(cmp);
+        let mut items: Vec<TestItem> = (0..4).map(|v| make_item(v * 10)).collect();
+        for item in items.iter_mut() {
+            list.insert(item);
+        }
+        list.set_reposition_hook(record_move);
+
+        // still fits between its neighbours: no move, no call
+        items[1].value = 15;
+        list.reposition(&mut items[1]);
+        items[1].value = 25;
+        list.reposition(&mut items[1]);
+        assert_eq!(MOVES.with(|moves| moves.take()), [(25, Some(0), Some(20))]);
+
+        list.remove(&mut items[3]);
+        let handle = list.push_handle(&mut items[3]);
+        unsafe { list.move_to_front_by_handle(handle) };
+        assert_eq!(MOVES.with(|moves| moves.take()), [(30, Some(25), None)]);
+
+        list.clear_reposition_hook();
+        items[0].value = 99;
+        list.reposition(&mut items[0]);
+        assert!(MOVES.with(|moves| moves.take()).is_empty());
+        assert_eq!(list.iter().map(|item| item.value).collect::<Vec<_>>(), [30, 20, 25, 99]);
+    }
+}
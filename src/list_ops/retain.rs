@@ -0,0 +1,138 @@
+// retain.rs
+// Filtering operations that keep some elements and detach the rest.
+use crate::RustyList;
+
+impl<T> RustyList<T> {
+    /// Keeps the elements `pred` accepts and unlinks the others, in one pass.
+    ///
+    /// `pred` may update the elements it looks at, e.g. to age a cache entry before
+    /// deciding whether it expires, but must not write their nodes. Unlinked elements are
+    /// simply dropped from the list; use `retain_into` or `extract_if` to keep hold of
+    /// them.
+    ///
+    /// # Returns
+    /// The number of elements unlinked.
+    pub fn retain(&mut self, mut pred: impl FnMut(&mut T) -> bool) -> usize {
+        self.assert_not_traversing("retain");
+        let before = self.len;
+        let mut current = self.head;
+
+        while let Some(node) = current {
+            current = unsafe { (*node.as_ptr()).next };
+
+            if !pred(unsafe { &mut *self.item_of(node) }) {
+                unsafe { self.unlink_node(node) };
+            }
+        }
+
+        self.notify_watermarks(before);
+        before - self.len
+    }
+
+    /// Keeps the elements `pred` accepts and moves the others to the tail of `out`.
+    ///
+    /// One pass, no allocation: both lists keep the relative order of their elements, and
+    /// rejected elements are appended to `out` behind whatever it already holds, without
+    /// consulting its `order_function`. Useful for quarantining bad entries without losing
+    /// them. `out` must hold the same element type with the node at the same offset.
+    ///
+    /// # Returns
+    /// The number of elements moved to `out`.
+    pub fn retain_into(&mut self, mut pred: impl FnMut(&T) -> bool, out: &mut RustyList<T>) -> usize {
+        self.assert_not_traversing("retain_into");
+        debug_assert_eq!(self.offset, out.offset, "RustyList::retain_into: offsets differ");
+        let (before, out_before) = (self.len, out.len);
+        let mut current = self.head;
+
+        while let Some(node) = current {
+            current = unsafe { (*node.as_ptr()).next };
+
+            if !pred(unsafe { &*self.item_of(node) }) {
+                unsafe {
+                    self.unlink_node(node);
+                    out.link_after(out.tail, node);
+                }
+            }
+        }
+
+        self.notify_watermarks(before);
+        out.notify_watermarks(out_before);
+        out.len - out_before
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec;
+    use std::vec::Vec;
+    use crate::{RustyList, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn retain_into_preserves_order_on_both_sides() {
+        let mut items: Vec<TestItem> = (1..=7).map(make_item).collect();
+        let mut quarantine_item = make_item(100);
+        let mut list = RustyList::<TestItem>::new();
+        let mut quarantine = RustyList::<TestItem>::new();
+        items.iter_mut().for_each(|item| list.push(item));
+        quarantine.push(&mut quarantine_item);
+
+        let moved = list.retain_into(|item| item.value % 3 != 0, &mut quarantine);
+
+        assert_eq!(moved, 2);
+        assert_eq!(list.iter().map(|item| item.value).collect::<Vec<_>>(), vec![1, 2, 4, 5, 7]);
+        assert_eq!(quarantine.iter().map(|item| item.value).collect::<Vec<_>>(), vec![100, 3, 6]);
+        assert_eq!((list.len, quarantine.len), (5, 3));
+        assert_eq!(unsafe { (*quarantine.tail.unwrap().as_ptr()).next_node() }, None);
+    }
+
+    #[test]
+    fn retain_into_can_empty_the_list() {
+        let mut items: Vec<TestItem> = (1..=3).map(make_item).collect();
+        let mut list = RustyList::<TestItem>::new();
+        let mut out = RustyList::<TestItem>::new();
+        items.iter_mut().for_each(|item| list.push(item));
+
+        assert_eq!(list.retain_into(|_| false, &mut out), 3);
+        assert!(list.head.is_none() && list.tail.is_none());
+        assert_eq!(out.iter().map(|item| item.value).collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn retain_updates_and_unlinks_in_one_pass() {
+        let mut items: Vec<TestItem> = (1..=6).map(make_item).collect();
+        let mut list = RustyList::<TestItem>::new();
+        items.iter_mut().for_each(|item| list.push(item));
+
+        let removed = list.retain(|item| {
+            item.value *= 10;
+            item.value % 20 != 0
+        });
+
+        assert_eq!(removed, 3);
+        assert_eq!(list.iter().map(|item| item.value).collect::<Vec<_>>(), vec![10, 30, 50]);
+        assert_eq!(list.len, 3);
+        assert!(items.iter().filter(|item| item.value % 20 == 0).all(|item| !item.node.is_linked()));
+        assert_eq!(list.retain(|_| false), 3);
+        assert!(list.head.is_none() && list.tail.is_none());
+    }
+}
@@ -0,0 +1,103 @@
+// round_robin.rs
+// Fair draining of several lists, one element from each per cycle.
+use crate::RustyList;
+
+/// Pops elements from several lists in turn: one from each non-empty list per cycle.
+///
+/// Draining per-client queues this way keeps a client with a long queue from starving
+/// the others, without first collecting everything into one list. Lists that run empty
+/// are skipped, and the iterator ends once every list is empty.
+pub struct RoundRobinIter<'a, 'b, T> {
+    lists: &'a mut [&'b mut RustyList<T>],
+    next: usize,
+}
+
+impl<'a, 'b, T> RoundRobinIter<'a, 'b, T> {
+    /// Starts the rotation at the first list.
+    pub fn new(lists: &'a mut [&'b mut RustyList<T>]) -> Self {
+        Self { lists, next: 0 }
+    }
+
+    /// Index of the list that gets the next turn.
+    pub fn next_index(&self) -> usize {
+        self.next
+    }
+}
+
+impl<T> Iterator for RoundRobinIter<'_, '_, T> {
+    type Item = *mut T;
+
+    fn next(&mut self) -> Option<*mut T> {
+        let count = self.lists.len();
+
+        for step in 0..count {
+            let index = (self.next + step) % count;
+            if let Some(item) = self.lists.get_mut(index).and_then(|list| list.pop()) {
+                self.next = (index + 1) % count;
+                return Some(item);
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.lists.iter().map(|list| list.len).sum();
+        (remaining, Some(remaining))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+    use crate::{RustyList, RustyListNode, HasRustyNode, RoundRobinIter, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn round_robin_takes_one_from_each_list_per_cycle() {
+        let mut a_items = [make_item(1), make_item(2), make_item(3)];
+        let mut b_items = [make_item(10)];
+        let mut c_items = [make_item(20), make_item(21)];
+
+        let mut a = RustyList::<TestItem>::new();
+        let mut b = RustyList::<TestItem>::new();
+        let mut c = RustyList::<TestItem>::new();
+        let mut empty = RustyList::<TestItem>::new();
+        a_items.iter_mut().for_each(|item| a.push(item));
+        b_items.iter_mut().for_each(|item| b.push(item));
+        c_items.iter_mut().for_each(|item| c.push(item));
+
+        let mut lists = [&mut a, &mut empty, &mut b, &mut c];
+        let iter = RoundRobinIter::new(&mut lists);
+        assert_eq!(iter.size_hint(), (6, Some(6)));
+
+        let values: Vec<i32> = iter.map(|item| unsafe { (*item).value }).collect();
+        assert_eq!(values, [1, 10, 20, 2, 21, 3]);
+        assert!(lists.iter().all(|list| list.len == 0));
+    }
+
+    #[test]
+    fn round_robin_over_no_lists_is_empty() {
+        let mut lists: [&mut RustyList<TestItem>; 0] = [];
+        assert!(RoundRobinIter::new(&mut lists).next().is_none());
+    }
+}
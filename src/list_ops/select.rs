@@ -0,0 +1,342 @@
+use crate::RustyList;
+use crate::list_ops::links::Link;
+
+impl<T> RustyList<T> {
+    /// Keeps only the `k` smallest elements according to the `order_function`.
+    ///
+    /// Does nothing if the list has no `order_function`. See `keep_smallest_k_by`.
+    pub fn keep_smallest_k(&mut self, k: usize, on_removed: impl FnMut(*mut T)) -> usize {
+        match self.order_function {
+            Some(cmp_fn) => self.keep_smallest_k_by(k, cmp_fn, on_removed),
+            None => 0,
+        }
+    }
+
+    /// Keeps only the `k` smallest elements according to `cmp`, unlinking the rest.
+    ///
+    /// Every unlinked element is handed to `on_removed` once it is fully detached. The
+    /// kept elements are maintained as a sorted window at the front of the list while
+    /// walking it once, so this costs O(n * k) comparisons in the worst case instead of a
+    /// full sort. If `cmp` or `on_removed` panics, the list holds the window followed by
+    /// the elements not yet visited. Among
+    /// equal elements the ones nearer the head win.
+    ///
+    /// # Returns
+    /// The number of elements removed. If any were removed, the kept elements end up in
+    /// `cmp` order; otherwise the list is untouched.
+    pub fn keep_smallest_k_by(
+        &mut self,
+        k: usize,
+        mut cmp: impl FnMut(*const T, *const T) -> i32,
+        mut on_removed: impl FnMut(*mut T),
+    ) -> usize {
+        if self.len <= k {
+            return 0;
+        }
+
+        let before = self.len;
+        let mut removed = 0;
+        // the kept elements are the first `kept` of the list, ending at `window_tail`
+        let mut kept = 0;
+        let mut window_tail: Option<Link<T>> = None;
+        let mut rest = self.head;
+
+        while let Some(node) = rest {
+            rest = unsafe { (*node.as_ptr()).next };
+            let item = self.item_of(node);
+
+            if kept == k {
+                // the window is full: the new element must beat the current largest
+                match window_tail {
+                    Some(tail) if cmp(item, self.item_of(tail)) < 0 => {
+                        window_tail = unsafe { (*tail.as_ptr()).prev };
+                        unsafe { self.unlink_node(tail) };
+                        kept -= 1;
+                        removed += 1;
+                        let evicted = self.item_of(tail);
+                        self.run_callback(|| on_removed(evicted));
+                    }
+                    _ => {
+                        unsafe { self.unlink_node(node) };
+                        removed += 1;
+                        self.run_callback(|| on_removed(item));
+                        continue;
+                    }
+                }
+            }
+
+            // `node` is right behind the window; move it to its place inside
+            let prev = unsafe { self.sorted_prev_from(window_tail, item, &mut cmp) };
+            if prev == window_tail {
+                window_tail = Some(node);
+            } else {
+                unsafe { self.move_after(prev, node) };
+            }
+            kept += 1;
+        }
+
+        self.notify_watermarks(before);
+        removed
+    }
+
+    /// Finds the `n`-th smallest element (0-based) according to the `order_function`.
+    ///
+    /// Returns `None` if the list has no `order_function`. See `select_nth_by`.
+    pub fn select_nth(&mut self, n: usize) -> Option<*mut T> {
+        let cmp_fn = self.order_function?;
+        self.select_nth_by(n, cmp_fn)
+    }
+
+    /// Finds the `n`-th smallest element (0-based) according to `cmp` without fully sorting.
+    ///
+    /// This is quickselect over the links: the list is partitioned in place around a pivot
+    /// into blocks of smaller, equal and greater elements, and only the block holding
+    /// position `n` is partitioned further. On return every element before the selected
+    /// one compares `<=` to it and every element after compares `>=`, like
+    /// `slice::select_nth_unstable`. The pivot is taken from the middle of each block, so
+    /// already sorted lists don't hit the quadratic worst case; expected cost is O(n).
+    /// Elements are only ever moved within the list, so if `cmp` panics the list still
+    /// holds all of them.
+    ///
+    /// # Returns
+    /// A pointer to the selected element, or `None` if `n >= len`.
+    pub fn select_nth_by(
+        &mut self,
+        mut n: usize,
+        mut cmp: impl FnMut(*const T, *const T) -> i32,
+    ) -> Option<*mut T> {
+        if n >= self.len {
+            return None;
+        }
+
+        // the segment still to partition: the `len` elements after `lo`, or from the head
+        let mut lo: Option<Link<T>> = None;
+        let mut len = self.len;
+
+        loop {
+            let first = match lo {
+                Some(l) => unsafe { (*l.as_ptr()).next },
+                None => self.head,
+            }?;
+
+            // pick the middle element of the segment as the pivot
+            let mut pivot = first;
+            for _ in 0..len / 2 {
+                pivot = unsafe { (*pivot.as_ptr()).next }?;
+            }
+            let pivot_item = self.item_of(pivot);
+
+            // partition in place into [less][equal][greater]: smaller elements move to the
+            // end of the less block, equal ones to the end of the equal block, greater ones
+            // stay where they are
+            let (mut less, mut equal) = (0, 0);
+            let mut less_tail = lo;
+            let mut equal_tail = lo;
+            let mut current = Some(first);
+            for _ in 0..len {
+                let Some(node) = current else {
+                    break;
+                };
+                current = unsafe { (*node.as_ptr()).next };
+
+                let c = cmp(self.item_of(node), pivot_item);
+                if c < 0 {
+                    unsafe { self.move_after(less_tail, node) };
+                    if equal_tail == less_tail {
+                        equal_tail = Some(node);
+                    }
+                    less_tail = Some(node);
+                    less += 1;
+                } else if c == 0 {
+                    unsafe { self.move_after(equal_tail, node) };
+                    equal_tail = Some(node);
+                    equal += 1;
+                }
+            }
+
+            if n < less {
+                len = less;
+            } else if n < less + equal {
+                let mut found = match less_tail {
+                    Some(l) => unsafe { (*l.as_ptr()).next },
+                    None => self.head,
+                }?;
+                for _ in 0..n - less {
+                    found = unsafe { (*found.as_ptr()).next }?;
+                }
+                break Some(self.item_of(found));
+            } else {
+                n -= less + equal;
+                len -= less + equal;
+                lo = equal_tail;
+            }
+        }
+    }
+
+    /// Returns the last element up to `from`, searching backwards, that is not greater
+    /// than `item`, or `None` if there is none.
+    unsafe fn sorted_prev_from(
+        &self,
+        from: Option<Link<T>>,
+        item: *const T,
+        cmp: &mut impl FnMut(*const T, *const T) -> i32,
+    ) -> Option<Link<T>> {
+        let mut prev = from;
+        while let Some(p) = prev {
+            if cmp(self.item_of(p), item) <= 0 {
+                break;
+            }
+            prev = unsafe { (*p.as_ptr()).prev };
+        }
+        prev
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec;
+    use std::vec::Vec;
+    use crate::{RustyList, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn cmp(a: *const TestItem, b: *const TestItem) -> i32 {
+        unsafe { (*a).value.cmp(&(*b).value) as i32 }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn keep_smallest_k_reports_the_rest() {
+        let mut list = RustyList::<TestItem>::new_with_order(cmp);
+        let mut items = [make_item(7), make_item(3), make_item(9), make_item(1), make_item(5), make_item(2)];
+        for item in items.iter_mut() {
+            list.push(item);
+        }
+
+        let mut dropped = vec![];
+        let removed = list.keep_smallest_k(3, |item| {
+            let item = unsafe { &*item };
+            assert!(!item.node.is_linked());
+            dropped.push(item.value);
+        });
+
+        assert_eq!(removed, 3);
+        assert_eq!(list.len, 3);
+        assert_eq!(list.iter().map(|item| item.value).collect::<Vec<_>>(), vec![1, 2, 3]);
+
+        dropped.sort();
+        assert_eq!(dropped, vec![5, 7, 9]);
+    }
+
+    #[test]
+    fn keep_smallest_k_edge_cases() {
+        let mut list = RustyList::<TestItem>::new_with_order(cmp);
+        let mut items = [make_item(2), make_item(1)];
+        for item in items.iter_mut() {
+            list.push(item);
+        }
+
+        // k >= len leaves the list untouched
+        assert_eq!(list.keep_smallest_k(2, |_| panic!("nothing to remove")), 0);
+        assert_eq!(list.iter().map(|item| item.value).collect::<Vec<_>>(), vec![2, 1]);
+
+        // k == 0 removes everything
+        let mut count = 0;
+        assert_eq!(list.keep_smallest_k(0, |_| count += 1), 2);
+        assert_eq!(count, 2);
+        assert_eq!(list.len, 0);
+        assert!(list.head.is_none());
+        assert!(list.tail.is_none());
+    }
+
+    fn check_links(list: &RustyList<TestItem>) {
+        let mut count = 0;
+        let mut prev = None;
+        let mut cursor = list.head;
+        while let Some(ptr) = cursor {
+            let node = unsafe { &*ptr.as_ptr() };
+            assert_eq!(node.prev_node(), prev);
+            prev = Some(ptr);
+            cursor = node.next_node();
+            count += 1;
+        }
+        assert_eq!(list.tail, prev);
+        assert_eq!(list.len, count);
+    }
+
+    #[test]
+    fn select_nth_partitions_around_result() {
+        let mut seed = 0x1234_5678u32;
+        for len in 1..40 {
+            let mut items: Vec<TestItem> = (0..len)
+                .map(|_| {
+                    seed ^= seed << 13;
+                    seed ^= seed >> 17;
+                    seed ^= seed << 5;
+                    make_item((seed % 16) as i32)
+                })
+                .collect();
+            let mut sorted: Vec<i32> = items.iter().map(|i| i.value).collect();
+            sorted.sort();
+
+            for n in 0..len {
+                let mut list = RustyList::<TestItem>::new_with_order(cmp);
+                for item in items.iter_mut() {
+                    list.push(item);
+                }
+
+                let selected = unsafe { &*list.select_nth(n).unwrap() };
+                assert_eq!(selected.value, sorted[n]);
+                check_links(&list);
+
+                let vals = list.iter().map(|item| item.value).collect::<Vec<_>>();
+                assert_eq!(vals[n], selected.value);
+                assert!(vals[..n].iter().all(|v| *v <= selected.value));
+                assert!(vals[n + 1..].iter().all(|v| *v >= selected.value));
+            }
+        }
+    }
+
+    #[test]
+    fn select_nth_out_of_range_or_no_comparator() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut a = make_item(1);
+        list.push(&mut a);
+
+        assert!(list.select_nth(0).is_none());
+        assert!(list.select_nth_by(1, cmp).is_none());
+        assert_eq!(unsafe { (*list.select_nth_by(0, cmp).unwrap()).value }, 1);
+        assert_eq!(list.len, 1);
+    }
+
+    #[test]
+    fn keep_smallest_k_by_ad_hoc_comparator() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut items = [make_item(4), make_item(8), make_item(6)];
+        for item in items.iter_mut() {
+            list.push(item);
+        }
+
+        // keep the largest instead
+        let removed = list.keep_smallest_k_by(1, |a, b| cmp(b, a), |_| {});
+        assert_eq!(removed, 2);
+        assert_eq!(list.iter().map(|item| item.value).collect::<Vec<_>>(), vec![8]);
+    }
+}
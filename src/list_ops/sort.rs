@@ -0,0 +1,566 @@
+use core::mem::MaybeUninit;
+use core::ptr::NonNull;
+use crate::{RustyList, RustyListNode, rusty_container_of, rusty_container_of_mut};
+use crate::list_ops::links::Link;
+
+/// Returns the first index in `v` whose element is not less than `x`.
+fn lower_bound<E>(v: &[E], x: &E, less: &mut impl FnMut(&E, &E) -> bool) -> usize {
+    let (mut lo, mut hi) = (0, v.len());
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if less(&v[mid], x) { lo = mid + 1 } else { hi = mid }
+    }
+    lo
+}
+
+/// Returns the first index in `v` whose element is greater than `x`.
+fn upper_bound<E>(v: &[E], x: &E, less: &mut impl FnMut(&E, &E) -> bool) -> usize {
+    let (mut lo, mut hi) = (0, v.len());
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if less(x, &v[mid]) { hi = mid } else { lo = mid + 1 }
+    }
+    lo
+}
+
+/// Stable merge of the sorted runs `v[..mid]` and `v[mid..]` without a buffer.
+///
+/// Splits around a pivot, rotates the middle block into place and recurses on both
+/// halves (the classic buffer-less `inplace_merge`).
+fn merge_in_place<E>(v: &mut [E], mid: usize, less: &mut impl FnMut(&E, &E) -> bool) {
+    let len = v.len();
+    if mid == 0 || mid == len {
+        return;
+    }
+    if len == 2 {
+        if less(&v[1], &v[0]) {
+            v.swap(0, 1);
+        }
+        return;
+    }
+
+    let (cut1, cut2) = if mid > len - mid {
+        let cut1 = mid / 2;
+        (cut1, mid + lower_bound(&v[mid..], &v[cut1], less))
+    } else {
+        let cut2 = mid + (len - mid) / 2;
+        (upper_bound(&v[..mid], &v[cut2], less), cut2)
+    };
+
+    v[cut1..cut2].rotate_left(mid - cut1);
+    let new_mid = cut1 + (cut2 - mid);
+
+    merge_in_place(&mut v[..new_mid], cut1, less);
+    merge_in_place(&mut v[new_mid..], cut2 - new_mid, less);
+}
+
+/// Stable bottom-up merge sort of a slice that needs no allocation.
+fn stable_sort_slice<E>(v: &mut [E], less: &mut impl FnMut(&E, &E) -> bool) {
+    let len = v.len();
+    let mut width = 1;
+    while width < len {
+        let mut lo = 0;
+        while lo + width < len {
+            let hi = core::cmp::min(lo + 2 * width, len);
+            merge_in_place(&mut v[lo..hi], width, less);
+            lo = hi;
+        }
+        width *= 2;
+    }
+}
+
+impl<T> RustyList<T> {
+    /// Sorts the list using its `order_function`. Does nothing if the list has none.
+    ///
+    /// See `sort_by` for how the sort works.
+    pub fn sort(&mut self) {
+        if let Some(cmp_fn) = self.order_function {
+            self.sort_by(cmp_fn);
+        }
+    }
+
+    /// Re-establishes `order_function` order after the keys of many elements were changed
+    /// in place.
+    ///
+    /// The batch counterpart to `reposition`: first checks whether the list is still in
+    /// order (`len - 1` comparisons, no relinking) and only then runs the natural merge
+    /// sort, which stays close to O(n) when just a few elements moved. Equal elements keep
+    /// their relative order.
+    ///
+    /// # Returns
+    /// `true` if the list had to be reordered, `false` if it was already sorted or has no
+    /// `order_function`.
+    pub fn resort(&mut self) -> bool {
+        let Some(cmp_fn) = self.order_function else {
+            return false;
+        };
+        if self.is_sorted_by(cmp_fn) {
+            return false;
+        }
+
+        self.sort_by(cmp_fn);
+        true
+    }
+
+    /// Returns `true` if every element compares `<=` to the one after it.
+    fn is_sorted_by(&self, mut cmp: impl FnMut(*const T, *const T) -> i32) -> bool {
+        let _guard = self.begin_traversal();
+        #[cfg(feature = "metrics")]
+        let mut scan = self.begin_scan(crate::list_ops::metrics::ScanKind::Sort);
+        let mut current = self.head;
+
+        while let Some(node) = current {
+            let next = unsafe { (*node.as_ptr()).next };
+            if let Some(n) = next {
+                #[cfg(feature = "metrics")]
+                scan.tick();
+                if cmp(self.item_of(node), self.item_of(n)) > 0 {
+                    return false;
+                }
+            }
+            current = next;
+        }
+
+        true
+    }
+
+    /// Sorts the list with `cmp`, which follows the same convention as `order_function`.
+    ///
+    /// This is a natural merge sort: it detects the runs already present in the list
+    /// (ascending ones, and strictly descending ones which are reversed in place) and only
+    /// merges those. Nearly sorted lists, the common case for timer and event lists, sort
+    /// in close to O(n); the worst case is O(n log n). The sort is stable, relinks the
+    /// nodes in place and allocates nothing.
+    ///
+    /// Every node is moved with a complete unlink and relink, so the list is well formed
+    /// whenever `cmp` runs: if `cmp` panics, the list keeps all its elements, partially
+    /// sorted.
+    pub fn sort_by(&mut self, mut cmp: impl FnMut(*const T, *const T) -> i32) {
+        self.assert_not_traversing("sort");
+        if self.len < 2 {
+            return;
+        }
+
+        // counts the comparisons for the `metrics` stats
+        let mut _comparisons = 0usize;
+        let mut cmp = |a: *const T, b: *const T| {
+            _comparisons = _comparisons.saturating_add(1);
+            cmp(a, b)
+        };
+
+        loop {
+            let mut runs = 0;
+            let mut rest = self.head;
+
+            while let Some(start) = rest {
+                let (a_head, after_a) = unsafe { self.take_run(start, &mut cmp) };
+                runs += 1;
+                let Some(b_start) = after_a else {
+                    break;
+                };
+
+                let (b_head, after_b) = unsafe { self.take_run(b_start, &mut cmp) };
+                runs += 1;
+                unsafe { self.merge_runs(a_head, b_head, after_b, &mut cmp) };
+                rest = after_b;
+            }
+
+            if runs <= 2 {
+                break;
+            }
+        }
+
+        #[cfg(feature = "metrics")]
+        self.record_scan(crate::list_ops::metrics::ScanKind::Sort, _comparisons);
+    }
+
+    /// Finds the run starting at `start` and makes it ascending.
+    ///
+    /// Returns the run's head and the node right after the run. A strictly descending run
+    /// is reversed by moving each of its nodes in front of the run; equal elements never
+    /// form a descending run, which keeps the sort stable.
+    unsafe fn take_run(
+        &mut self,
+        start: Link<T>,
+        cmp: &mut impl FnMut(*const T, *const T) -> i32,
+    ) -> (Link<T>, Option<Link<T>>) {
+        let offset = self.offset;
+        let item = |node: Link<T>| unsafe { rusty_container_of(node.as_ptr(), offset) };
+        let first_next = unsafe { (*start.as_ptr()).next };
+
+        match first_next {
+            Some(second) if cmp(item(second), item(start)) < 0 => {
+                // strictly descending: reverse while walking
+                let mut head = start;
+                let mut current = second;
+
+                loop {
+                    let after = unsafe { (*current.as_ptr()).next };
+                    unsafe { self.move_after((*head.as_ptr()).prev, current) };
+                    head = current;
+
+                    match after {
+                        Some(next) if cmp(item(next), item(current)) < 0 => current = next,
+                        _ => return (head, after),
+                    }
+                }
+            }
+            // a single element is a run of its own
+            None => (start, None),
+            Some(second) => {
+                let mut last = second;
+                loop {
+                    match unsafe { (*last.as_ptr()).next } {
+                        Some(next) if cmp(item(next), item(last)) >= 0 => last = next,
+                        after => return (start, after),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Stable in-place merge of the adjacent ascending runs starting at `a` and `b`, the
+    /// second one ending right before `end`.
+    ///
+    /// Each element of the second run that belongs earlier is moved in front of the first
+    /// element of the first run that is greater than it.
+    unsafe fn merge_runs(
+        &mut self,
+        a: Link<T>,
+        b: Link<T>,
+        end: Option<Link<T>>,
+        cmp: &mut impl FnMut(*const T, *const T) -> i32,
+    ) {
+        let offset = self.offset;
+        let item = |node: Link<T>| unsafe { rusty_container_of(node.as_ptr(), offset) };
+        let mut x = a;
+        let mut y = Some(b);
+
+        // the first run is used up when `x` reaches the second run's next element
+        while let Some(next_b) = y.filter(|&node| Some(node) != end && node != x) {
+            // ties keep `x` first, as it came first in the list
+            if cmp(item(next_b), item(x)) < 0 {
+                y = unsafe { (*next_b.as_ptr()).next };
+                unsafe { self.move_after((*x.as_ptr()).prev, next_b) };
+            } else {
+                match unsafe { (*x.as_ptr()).next } {
+                    Some(next) => x = next,
+                    None => return,
+                }
+            }
+        }
+    }
+
+    /// Sorts the list by a key that is computed only once per element.
+    ///
+    /// `key` is called exactly `len` times and the results are cached in `scratch`
+    /// next to the element pointers, so expensive keys (checksums, string compares into
+    /// flash) are not re-derived on every comparison. The sort is stable and allocates
+    /// nothing; `scratch` must hold at least `len` entries.
+    ///
+    /// # Returns
+    /// `true` if the list was sorted, `false` (list untouched) if `scratch` is too small.
+    pub fn sort_by_cached_key<K: Ord>(
+        &mut self,
+        scratch: &mut [MaybeUninit<(K, *mut T)>],
+        mut key: impl FnMut(&T) -> K,
+    ) -> bool {
+        if scratch.len() < self.len {
+            return false;
+        }
+        if self.len < 2 {
+            return true;
+        }
+
+        let mut filled = 0;
+        let mut current = self.head;
+        for slot in scratch.iter_mut() {
+            let Some(node_ptr) = current else {
+                break;
+            };
+            let item = unsafe { rusty_container_of_mut(node_ptr.as_ptr(), self.offset) };
+            slot.write((key(unsafe { &*item }), item));
+            filled += 1;
+            current = unsafe { (*node_ptr.as_ptr()).next };
+        }
+        let capacity = scratch.len();
+        let written = &mut scratch[..filled.min(capacity)];
+
+        // SAFETY: the first `filled` entries were just written
+        let entries = unsafe { &mut *(written as *mut [MaybeUninit<(K, *mut T)>] as *mut [(K, *mut T)]) };
+        stable_sort_slice(entries, &mut |a, b| a.0 < b.0);
+
+        unsafe { self.relink_in_order(entries.iter().map(|entry| entry.1)) };
+
+        for entry in written {
+            unsafe { entry.assume_init_drop() };
+        }
+        true
+    }
+
+    /// Rebuilds the links so the list holds exactly the elements yielded by `items`, in that order.
+    ///
+    /// # Safety
+    /// `items` must yield every element of the list exactly once.
+    pub(crate) unsafe fn relink_in_order(&mut self, items: impl Iterator<Item = *mut T>) {
+        self.assert_not_traversing("sort");
+        let mut prev: Option<NonNull<RustyListNode<T>>> = None;
+        self.head = None;
+
+        for item in items {
+            let node_ptr = unsafe { (item as *mut u8).add(self.offset) } as *mut RustyListNode<T>;
+            let node = unsafe { NonNull::new_unchecked(node_ptr) };
+
+            unsafe { (*node_ptr).prev = prev };
+            match prev {
+                Some(prev_node) => unsafe { (*prev_node.as_ptr()).next = Some(node) },
+                None => self.head = Some(node),
+            }
+            prev = Some(node);
+        }
+
+        if let Some(tail) = prev {
+            unsafe { (*tail.as_ptr()).next = None };
+        }
+        self.tail = prev;
+        #[cfg(feature = "replay")]
+        self.record_rebuild();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem::MaybeUninit;
+    use std::vec;
+    use std::vec::Vec;
+    use crate::{RustyList, RustyListNode, HasRustyNode, rusty_offset, rusty_container_of};
+    use super::stable_sort_slice;
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub tag: u8,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn make_item(val: i32, tag: u8) -> TestItem {
+        TestItem {
+            value: val,
+            tag,
+            node: RustyListNode::new(),
+        }
+    }
+
+    fn walk(list: &RustyList<TestItem>) -> Vec<(i32, u8)> {
+        let mut vals = vec![];
+        let mut cursor = list.head;
+        while let Some(ptr) = cursor {
+            let item = unsafe { &*rusty_container_of(ptr.as_ptr(), list.offset) };
+            vals.push((item.value, item.tag));
+            cursor = item.node.next_node();
+        }
+
+        // the backward links must mirror the forward walk
+        let mut back = vec![];
+        let mut cursor = list.tail;
+        while let Some(ptr) = cursor {
+            let item = unsafe { &*rusty_container_of(ptr.as_ptr(), list.offset) };
+            back.push((item.value, item.tag));
+            cursor = item.node.prev_node();
+        }
+        back.reverse();
+        assert_eq!(vals, back);
+
+        vals
+    }
+
+    #[test]
+    fn resort_after_bulk_key_changes() {
+        let mut list = RustyList::<TestItem>::new_with_order(cmp);
+        let mut items: Vec<TestItem> = (0..6).map(|v| make_item(v, 0)).collect();
+        for item in items.iter_mut() {
+            list.insert(item);
+        }
+        assert!(!list.resort());
+
+        items[0].value = 4;
+        items[0].tag = 1;
+        items[5].value = -1;
+        items[2].value = 10;
+        assert!(list.resort());
+        // the changed 4 was ahead of the original 4, and the sort is stable
+        assert_eq!(walk(&list), vec![(-1, 0), (1, 0), (3, 0), (4, 1), (4, 0), (10, 0)]);
+        assert!(!list.resort());
+
+        let mut unordered = RustyList::<TestItem>::new();
+        let mut a = make_item(2, 0);
+        let mut b = make_item(1, 0);
+        unordered.push(&mut a);
+        unordered.push(&mut b);
+        assert!(!unordered.resort());
+        assert_eq!(walk(&unordered), vec![(2, 0), (1, 0)]);
+    }
+
+    #[test]
+    fn sort_by_cached_key_calls_key_once_per_item() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut items = [make_item(5, 0), make_item(1, 0), make_item(4, 0), make_item(2, 0), make_item(3, 0)];
+        for item in items.iter_mut() {
+            list.push(item);
+        }
+
+        let mut calls = 0;
+        let mut scratch = [const { MaybeUninit::<(i32, *mut TestItem)>::uninit() }; 5];
+        let sorted = list.sort_by_cached_key(&mut scratch, |item| {
+            calls += 1;
+            item.value
+        });
+
+        assert!(sorted);
+        assert_eq!(calls, 5);
+        assert_eq!(list.len, 5);
+        assert_eq!(walk(&list), vec![(1, 0), (2, 0), (3, 0), (4, 0), (5, 0)]);
+    }
+
+    #[test]
+    fn sort_by_cached_key_is_stable() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut items = [make_item(2, 0), make_item(1, 0), make_item(2, 1), make_item(1, 1), make_item(2, 2)];
+        for item in items.iter_mut() {
+            list.push(item);
+        }
+
+        let mut scratch = [const { MaybeUninit::<(i32, *mut TestItem)>::uninit() }; 8];
+        assert!(list.sort_by_cached_key(&mut scratch, |item| item.value));
+        assert_eq!(walk(&list), vec![(1, 0), (1, 1), (2, 0), (2, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn sort_by_cached_key_rejects_small_scratch() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut items = [make_item(2, 0), make_item(1, 0)];
+        for item in items.iter_mut() {
+            list.push(item);
+        }
+
+        let mut scratch = [const { MaybeUninit::<(i32, *mut TestItem)>::uninit() }; 1];
+        assert!(!list.sort_by_cached_key(&mut scratch, |item| item.value));
+        assert_eq!(walk(&list), vec![(2, 0), (1, 0)]);
+    }
+
+    fn cmp(a: *const TestItem, b: *const TestItem) -> i32 {
+        unsafe { (*a).value.cmp(&(*b).value) as i32 }
+    }
+
+    fn list_of(items: &mut [TestItem]) -> RustyList<TestItem> {
+        let mut list = RustyList::<TestItem>::new_with_order(cmp);
+        for item in items.iter_mut() {
+            list.push(item);
+        }
+        list
+    }
+
+    #[test]
+    fn sort_orders_unsorted_list() {
+        let mut items = [make_item(3, 0), make_item(1, 0), make_item(4, 0), make_item(1, 1), make_item(5, 0), make_item(9, 0), make_item(2, 0), make_item(6, 0)];
+        let mut list = list_of(&mut items);
+
+        list.sort();
+
+        assert_eq!(list.len, 8);
+        assert_eq!(walk(&list), vec![(1, 0), (1, 1), (2, 0), (3, 0), (4, 0), (5, 0), (6, 0), (9, 0)]);
+    }
+
+    #[test]
+    fn sort_of_sorted_list_is_linear() {
+        let mut items: Vec<TestItem> = (0..64).map(|v| make_item(v, 0)).collect();
+        let mut list = list_of(&mut items);
+
+        let mut calls = 0;
+        list.sort_by(|a, b| {
+            calls += 1;
+            cmp(a, b)
+        });
+
+        assert_eq!(calls, 63);
+        assert_eq!(walk(&list), (0..64).map(|v| (v, 0)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn sort_reverses_descending_run() {
+        let mut items: Vec<TestItem> = (0..16).rev().map(|v| make_item(v, 0)).collect();
+        let mut list = list_of(&mut items);
+
+        let mut calls = 0;
+        list.sort_by(|a, b| {
+            calls += 1;
+            cmp(a, b)
+        });
+
+        // one reversed run: no merging needed
+        assert_eq!(calls, 15);
+        assert_eq!(walk(&list), (0..16).map(|v| (v, 0)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn sort_is_stable_and_matches_std() {
+        let mut seed = 0x9e37_79b9u32;
+        for len in 0..50 {
+            let mut items: Vec<TestItem> = (0..len)
+                .map(|i| {
+                    seed ^= seed << 13;
+                    seed ^= seed >> 17;
+                    seed ^= seed << 5;
+                    make_item((seed % 10) as i32, i as u8)
+                })
+                .collect();
+            let mut expected: Vec<(i32, u8)> = items.iter().map(|i| (i.value, i.tag)).collect();
+            expected.sort_by_key(|e| e.0);
+
+            let mut list = list_of(&mut items);
+            list.sort();
+
+            assert_eq!(list.len, len);
+            assert_eq!(walk(&list), expected);
+        }
+    }
+
+    #[test]
+    fn sort_without_order_function_is_noop() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut items = [make_item(2, 0), make_item(1, 0)];
+        for item in items.iter_mut() {
+            list.push(item);
+        }
+
+        list.sort();
+        assert_eq!(walk(&list), vec![(2, 0), (1, 0)]);
+    }
+
+    #[test]
+    fn stable_sort_slice_matches_std() {
+        let mut seed = 0x2545_f491u32;
+        for len in 0..40 {
+            let mut v: Vec<(u32, usize)> = (0..len)
+                .map(|i| {
+                    seed ^= seed << 13;
+                    seed ^= seed >> 17;
+                    seed ^= seed << 5;
+                    (seed % 7, i)
+                })
+                .collect();
+            let mut expected = v.clone();
+            expected.sort_by_key(|e| e.0);
+
+            stable_sort_slice(&mut v, &mut |a, b| a.0 < b.0);
+            assert_eq!(v, expected);
+        }
+    }
+}
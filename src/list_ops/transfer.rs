@@ -0,0 +1,100 @@
+// transfer.rs
+// Moving a single element between lists.
+use crate::RustyList;
+
+impl<T> RustyList<T> {
+    /// Moves `item` from this list into `to` in one call.
+    ///
+    /// The element is unlinked here and inserted into `to` as `insert` would, so it lands
+    /// at its sorted position if `to` has an `order_function` and at the tail otherwise.
+    /// Both lengths are updated together and nothing happens unless the element is linked:
+    /// the common remove-then-insert mistake of inserting an element that was never on the
+    /// source list can't happen. Debug builds also check that it is on this list rather
+    /// than another one, which costs a walk of the list.
+    ///
+    /// # Returns
+    /// `true` if the element was moved, `false` if it wasn't linked.
+    pub fn transfer(&mut self, item: &mut T, to: &mut RustyList<T>) -> bool {
+        self.assert_not_traversing("transfer");
+        debug_assert_eq!(self.offset, to.offset, "RustyList::transfer: offsets differ");
+        let item_ptr = item as *mut T;
+
+        if !unsafe { (*self.node_of(item_ptr).as_ptr()).linked } {
+            rusty_warn!("RustyList::transfer: node is not linked, ignoring");
+            return false;
+        }
+        debug_assert!(
+            self.find_item(item_ptr).is_some(),
+            "RustyList::transfer: item is not on the source list"
+        );
+
+        self.remove(item);
+        to.insert(item);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+    use crate::{RustyList, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn cmp(a: *const TestItem, b: *const TestItem) -> i32 {
+        unsafe { (*a).value.cmp(&(*b).value) as i32 }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn transfer_moves_into_sorted_position() {
+        let mut from = RustyList::<TestItem>::new();
+        let mut to = RustyList::<TestItem>::new_with_order(cmp);
+        let mut a = make_item(5);
+        let mut b = make_item(1);
+        let mut c = make_item(9);
+        from.push(&mut a);
+        to.insert(&mut b);
+        to.insert(&mut c);
+
+        assert!(from.transfer(&mut a, &mut to));
+        assert_eq!((from.len, to.len), (0, 3));
+        assert!(from.head.is_none());
+        assert_eq!(to.iter().map(|item| item.value).collect::<Vec<_>>(), [1, 5, 9]);
+
+        // no longer on `from`, and a stray element is refused too
+        let mut stray = make_item(7);
+        assert!(!from.transfer(&mut stray, &mut to));
+        assert_eq!(to.len, 3);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "not on the source list")]
+    fn transfer_rejects_item_from_another_list() {
+        let mut from = RustyList::<TestItem>::new();
+        let mut other = RustyList::<TestItem>::new();
+        let mut to = RustyList::<TestItem>::new();
+        let mut a = make_item(1);
+        other.push(&mut a);
+
+        from.transfer(&mut a, &mut to);
+    }
+}
@@ -0,0 +1,156 @@
+// watermarks.rs
+// Length thresholds with a callback for flow control (feature `watermarks`).
+use crate::RustyList;
+
+/// Which threshold `len` crossed, passed to the watermark callback.
+#[cfg(feature = "watermarks")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatermarkEvent {
+    /// `len` rose from below `high` to `high` or more.
+    High,
+    /// `len` fell from above `low` to `low` or less.
+    Low,
+}
+
+/// Thresholds and callback installed by `set_watermarks`.
+#[cfg(feature = "watermarks")]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Watermarks {
+    low: usize,
+    high: usize,
+    callback: fn(WatermarkEvent, usize),
+}
+
+impl<T> RustyList<T> {
+    /// Calls `callback` whenever an operation makes `len` cross a threshold.
+    ///
+    /// `callback` receives `High` when `len` rises from below `high` to at least `high`,
+    /// and `Low` when it falls from above `low` to at most `low`, together with the new
+    /// `len`. With `low < high` this gives hysteresis for flow control: assert
+    /// backpressure on `High`, release it on `Low`. The callback runs synchronously at the
+    /// end of the operation that crossed the threshold (`push`, `pop`, `insert`, `remove`,
+    /// ...), so it can't race with producers the way polling `len` does. Operations that
+    /// only reorder the list never call it.
+    #[cfg(feature = "watermarks")]
+    pub fn set_watermarks(&mut self, low: usize, high: usize, callback: fn(WatermarkEvent, usize)) {
+        self.watermarks = Some(Watermarks { low, high, callback });
+    }
+
+    /// Removes the watermarks set by `set_watermarks`.
+    #[cfg(feature = "watermarks")]
+    pub fn clear_watermarks(&mut self) {
+        self.watermarks = None;
+    }
+
+    /// Calls the watermark callback if `len` crossed a threshold since it was `before`.
+    ///
+    /// Without the `watermarks` feature this is a no-op.
+    #[inline(always)]
+    pub(crate) fn notify_watermarks(&self, before: usize) {
+        #[cfg(feature = "watermarks")]
+        {
+            let Some(marks) = self.watermarks else {
+                return;
+            };
+
+            if before < marks.high && self.len >= marks.high {
+                self.run_callback(|| (marks.callback)(WatermarkEvent::High, self.len));
+            } else if before > marks.low && self.len <= marks.low {
+                self.run_callback(|| (marks.callback)(WatermarkEvent::Low, self.len));
+            }
+        }
+        #[cfg(not(feature = "watermarks"))]
+        let _ = before;
+    }
+}
+
+#[cfg(all(test, feature = "watermarks"))]
+mod tests {
+    use core::cell::RefCell;
+    use std::vec;
+    use std::vec::Vec;
+    use super::WatermarkEvent;
+    use crate::{RustyList, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    std::thread_local! {
+        static EVENTS: RefCell<Vec<(WatermarkEvent, usize)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn record(event: WatermarkEvent, len: usize) {
+        EVENTS.with(|events| events.borrow_mut().push((event, len)));
+    }
+
+    #[test]
+    fn callbacks_fire_on_crossings_only() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut items = [make_item(1), make_item(2), make_item(3), make_item(4)];
+        list.set_watermarks(1, 3, record);
+
+        for item in items.iter_mut() {
+            list.push(item);
+        }
+        list.pop();
+        list.remove(&mut items[2]);
+        list.pop();
+        list.insert(&mut items[0]);
+
+        let events = EVENTS.with(|events| events.take());
+        assert_eq!(events, vec![(WatermarkEvent::High, 3), (WatermarkEvent::Low, 1)]);
+    }
+
+    #[test]
+    fn bulk_removal_reports_once() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut items = [make_item(1), make_item(2), make_item(3), make_item(4)];
+        for item in items.iter_mut() {
+            list.push(item);
+        }
+
+        list.set_watermarks(1, 4, record);
+        list.keep_smallest_k_by(0, |a, b| unsafe { (*a).value.cmp(&(*b).value) as i32 }, |_| {});
+
+        let events = EVENTS.with(|events| events.take());
+        assert_eq!(events, vec![(WatermarkEvent::Low, 0)]);
+
+        list.clear_watermarks();
+        list.push(&mut items[0]);
+        assert!(EVENTS.with(|events| events.borrow().is_empty()));
+    }
+
+    #[test]
+    fn restore_structure_reports_the_net_change_only() {
+        let mut list = RustyList::<TestItem>::new();
+        let mut items = [make_item(1), make_item(2), make_item(3)];
+        for item in items.iter_mut() {
+            list.push(item);
+        }
+
+        // same length before and after: no Low on the reset, no High on the relink
+        list.set_watermarks(1, 3, record);
+        unsafe { list.restore_structure(items.as_mut_ptr(), &[2, 0, 1]) };
+        assert!(EVENTS.with(|events| events.borrow().is_empty()));
+
+        unsafe { list.restore_structure(items.as_mut_ptr(), &[1]) };
+        assert_eq!(EVENTS.with(|events| events.take()), vec![(WatermarkEvent::Low, 1)]);
+    }
+}
@@ -0,0 +1,477 @@
+use core::ptr::NonNull;
+use crate::{HasRustyNode, RustyList, RustyListNode, rusty_offset};
+
+/// An index entry of an `AdaptiveSortedList`, and the storage the list keeps entries in.
+///
+/// Each entry points at one element of the list and at the entry for the same element
+/// on the level below, if any.
+#[repr(C)]
+#[derive(Debug)]
+pub struct SkipNode<T> {
+    target: Option<NonNull<T>>,
+    down: Option<NonNull<SkipNode<T>>>,
+    node: RustyListNode<SkipNode<T>>,
+}
+
+impl<T> SkipNode<T> {
+    /// Creates an unused entry, to hand to `AdaptiveSortedList::add_storage`.
+    pub const fn new() -> Self {
+        Self { target: None, down: None, node: RustyListNode::new_const() }
+    }
+}
+
+impl<T> Default for SkipNode<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> HasRustyNode for SkipNode<T> {
+    fn rusty_offset() -> usize {
+        rusty_offset(|x: &Self| &x.node)
+    }
+}
+
+/// A sorted `RustyList` that grows a skip-list index once it gets long.
+///
+/// Below `promote_at` elements it is a plain sorted list: `insert` and `find_equal` walk
+/// it, and it uses no memory besides the list header. When an insert brings `len` up to
+/// `promote_at`, the list is indexed with up to `LEVELS` levels of `SkipNode` entries
+/// taken from a caller-provided pool (`add_storage`), after which lookups and inserts
+/// descend the levels and cost O(log n) comparisons. Once removals bring `len` down to
+/// half of `promote_at`, every entry goes back to the pool; the gap between the two
+/// thresholds keeps a list hovering around one of them from rebuilding over and over.
+///
+/// The index needs about one entry per element. If the pool runs short, elements are
+/// left out of the index or indexed on fewer levels: lookups stay correct, just slower.
+/// `LEVELS` levels serve lists up to about `2^LEVELS` elements at full speed.
+///
+/// ```ignore
+/// static mut POOL: [SkipNode<Timer>; 1024] = [const { SkipNode::new() }; 1024];
+///
+/// let mut timers = AdaptiveSortedList::<Timer, 10>::new(by_deadline, 64);
+/// for entry in unsafe { &mut *addr_of_mut!(POOL) } {
+///     timers.add_storage(entry);
+/// }
+/// timers.insert(&mut timer);
+/// ```
+pub struct AdaptiveSortedList<T, const LEVELS: usize> {
+    list: RustyList<T>,
+    cmp: fn(*const T, *const T) -> i32,
+    levels: [RustyList<SkipNode<T>>; LEVELS],
+    spare: RustyList<SkipNode<T>>,
+    promote_at: usize,
+    promoted: bool,
+    seed: u32,
+}
+
+impl<T: HasRustyNode, const LEVELS: usize> AdaptiveSortedList<T, LEVELS> {
+    /// Creates an empty list sorted with `cmp` that builds its index at `promote_at`
+    /// elements.
+    pub fn new(cmp: fn(*const T, *const T) -> i32, promote_at: usize) -> Self {
+        Self {
+            list: RustyList::new_with_order(cmp),
+            cmp,
+            levels: core::array::from_fn(|_| RustyList::new()),
+            spare: RustyList::new(),
+            promote_at,
+            promoted: false,
+            seed: 0x9E37_79B9,
+        }
+    }
+
+    /// The underlying list, for read-only operations.
+    pub fn list(&self) -> &RustyList<T> {
+        &self.list
+    }
+
+    /// Number of elements in the list.
+    pub fn len(&self) -> usize {
+        self.list.len
+    }
+
+    /// Returns `true` if the list holds no elements.
+    pub fn is_empty(&self) -> bool {
+        self.list.len == 0
+    }
+
+    /// Returns `true` while the list has (or is meant to have) a skip-list index.
+    pub fn is_promoted(&self) -> bool {
+        self.promoted
+    }
+
+    /// Gives the list a `SkipNode` to build its index with.
+    ///
+    /// The entry must stay valid for as long as the list is used.
+    pub fn add_storage(&mut self, entry: &mut SkipNode<T>) {
+        self.spare.push(entry);
+    }
+
+    /// Number of `SkipNode`s in the pool, not used by the index.
+    pub fn spare_storage(&self) -> usize {
+        self.spare.len
+    }
+
+    /// Number of index entries on each level, bottom level first.
+    pub fn level_lens(&self) -> [usize; LEVELS] {
+        core::array::from_fn(|level| self.levels[level].len)
+    }
+
+    /// Inserts `item` at its sorted position, after any elements equal to it, and builds
+    /// the index if the list just reached `promote_at` elements.
+    pub fn insert(&mut self, item: &mut T) {
+        if !self.promoted {
+            self.list.insert(item);
+            if self.list.len >= self.promote_at {
+                self.promote();
+            }
+            return;
+        }
+
+        let mut preds = [None; LEVELS];
+        let before = self.descend(item, |c| c <= 0, &mut preds);
+        let node = self.list.claim_node(item, "insert");
+        unsafe { self.list.link_after(before.map(|prev| self.list.node_of(prev)), node) };
+        self.index(item, &preds);
+    }
+
+    /// Returns the first element that compares equal to `target`.
+    pub fn find_equal(&mut self, target: &T) -> Option<&mut T> {
+        let mut preds = [None; LEVELS];
+        let before = self.descend(target, |c| c < 0, &mut preds);
+        let candidate = self.next_item(before)?;
+        if (self.cmp)(candidate, target) == 0 {
+            Some(unsafe { &mut *candidate })
+        } else {
+            None
+        }
+    }
+
+    /// Removes `item` from the list and the index, dropping the index if the list shrank
+    /// to half of `promote_at`.
+    pub fn remove(&mut self, item: &mut T) {
+        if self.promoted {
+            self.unindex(item);
+        }
+        self.list.remove(item);
+        self.demote_if_short();
+    }
+
+    /// Removes and returns the smallest element.
+    pub fn pop(&mut self) -> Option<*mut T> {
+        let head = self.list.peek()?;
+        if self.promoted {
+            self.unindex(head);
+        }
+        let item = self.list.pop();
+        self.demote_if_short();
+        item
+    }
+
+    /// The element after `item`, or the head when `item` is `None`.
+    fn next_item(&self, item: Option<*mut T>) -> Option<*mut T> {
+        let next = match item {
+            Some(item) => unsafe { (*self.list.node_of(item).as_ptr()).next },
+            None => self.list.head,
+        };
+        next.map(|node| self.list.item_of(node))
+    }
+
+    /// The entry after `entry` on `level`, or its first entry when `entry` is `None`.
+    fn next_entry(&self, level: usize, entry: Option<*mut SkipNode<T>>) -> Option<*mut SkipNode<T>> {
+        let level = &self.levels[level];
+        let next = match entry {
+            Some(entry) => unsafe { (*entry).node.next },
+            None => level.head,
+        };
+        next.map(|node| level.item_of(node))
+    }
+
+    /// The element an entry points at.
+    fn target_of(entry: *mut SkipNode<T>) -> *mut T {
+        unsafe { (*entry).target.map_or(core::ptr::null_mut(), NonNull::as_ptr) }
+    }
+
+    /// Walks down the index and then the list, moving past every element for which
+    /// `advance(cmp(element, key))` holds, and returns the last element moved past.
+    /// `preds` receives the last entry moved past on each level.
+    fn descend(
+        &self,
+        key: *const T,
+        advance: impl Fn(i32) -> bool,
+        preds: &mut [Option<*mut SkipNode<T>>; LEVELS],
+    ) -> Option<*mut T> {
+        let mut from: Option<*mut SkipNode<T>> = None;
+        let mut start = None;
+
+        for level in (0..LEVELS).rev() {
+            let mut current = from;
+            while let Some(next) = self.next_entry(level, current) {
+                if !advance((self.cmp)(Self::target_of(next), key)) {
+                    break;
+                }
+                current = Some(next);
+            }
+            preds[level] = current;
+            // an entry on every level but the bottom one has an entry below it
+            from = current.and_then(|entry| unsafe { (*entry).down }).map(NonNull::as_ptr);
+            start = current.map(Self::target_of);
+        }
+
+        let mut current = start;
+        while let Some(next) = self.next_item(current) {
+            if !advance((self.cmp)(next, key)) {
+                break;
+            }
+            current = Some(next);
+        }
+        current
+    }
+
+    /// A random index height for a new element: 0 half the time, 1 a quarter of the time,
+    /// and so on, capped at `LEVELS`.
+    fn random_height(&mut self) -> usize {
+        // xorshift32
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        (self.seed.trailing_ones() as usize).min(LEVELS)
+    }
+
+    /// Adds entries for the newly linked `item` right after `preds`, as many levels high
+    /// as `random_height` and the pool allow.
+    fn index(&mut self, item: &mut T, preds: &[Option<*mut SkipNode<T>>; LEVELS]) {
+        let height = self.random_height();
+        let mut down = None;
+
+        for (level, &pred) in preds.iter().enumerate().take(height) {
+            let Some(entry) = self.spare.pop() else {
+                return;
+            };
+            let entry = unsafe { &mut *entry };
+            entry.target = Some(NonNull::from(&mut *item));
+            entry.down = down;
+            down = Some(NonNull::from(&mut *entry));
+
+            let list = &mut self.levels[level];
+            let node = list.claim_node(entry, "insert");
+            unsafe { list.link_after(pred.map(|pred| list.node_of(pred)), node) };
+        }
+    }
+
+    /// Removes the entries pointing at `item`, if it has any, and returns them to the pool.
+    fn unindex(&mut self, item: *mut T) {
+        let mut from: Option<*mut SkipNode<T>> = None;
+
+        for level in (0..LEVELS).rev() {
+            let mut current = from;
+            while let Some(next) = self.next_entry(level, current) {
+                if (self.cmp)(Self::target_of(next), item) >= 0 {
+                    break;
+                }
+                current = Some(next);
+            }
+
+            // `item` is among the entries equal to it, if it is on this level at all
+            let mut probe = self.next_entry(level, current);
+            while let Some(entry) = probe {
+                if Self::target_of(entry) == item {
+                    self.release_from(level, entry);
+                    return;
+                }
+                if (self.cmp)(Self::target_of(entry), item) != 0 {
+                    break;
+                }
+                probe = self.next_entry(level, Some(entry));
+            }
+            from = current.and_then(|entry| unsafe { (*entry).down }).map(NonNull::as_ptr);
+        }
+    }
+
+    /// Returns `entry`, on `level`, and the entries below it to the pool.
+    fn release_from(&mut self, level: usize, entry: *mut SkipNode<T>) {
+        let mut entry = Some(entry);
+        for level in (0..=level).rev() {
+            let Some(current) = entry else {
+                return;
+            };
+            let current = unsafe { &mut *current };
+            entry = current.down.map(NonNull::as_ptr);
+            self.levels[level].remove(current);
+            self.spare.push(current);
+        }
+    }
+
+    /// Builds the index over the whole list: every second element on the bottom level,
+    /// every fourth on the next, and so on.
+    fn promote(&mut self) {
+        self.promoted = true;
+        let mut position = 0usize;
+        let mut current = self.list.head;
+
+        while let Some(node) = current {
+            position += 1;
+            current = unsafe { (*node.as_ptr()).next };
+            let height = (position.trailing_zeros() as usize).min(LEVELS);
+            let mut down = None;
+
+            for level in 0..height {
+                let Some(entry) = self.spare.pop() else {
+                    return;
+                };
+                let entry = unsafe { &mut *entry };
+                entry.target = NonNull::new(self.list.item_of(node));
+                entry.down = down;
+                down = Some(NonNull::from(&mut *entry));
+                self.levels[level].push(entry);
+            }
+        }
+    }
+
+    /// Drops the index once the list is down to half of `promote_at` elements.
+    fn demote_if_short(&mut self) {
+        if !self.promoted || self.list.len > self.promote_at / 2 {
+            return;
+        }
+        self.promoted = false;
+        for level in 0..LEVELS {
+            while let Some(entry) = self.levels[level].pop() {
+                self.spare.push(unsafe { &mut *entry });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+    use std::vec::Vec;
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    std::thread_local! {
+        static CALLS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    fn cmp(a: *const TestItem, b: *const TestItem) -> i32 {
+        CALLS.with(|calls| calls.set(calls.get() + 1));
+        unsafe { (*a).value.cmp(&(*b).value) as i32 }
+    }
+
+    fn calls() -> usize {
+        CALLS.with(|calls| calls.replace(0))
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    fn values<const L: usize>(list: &AdaptiveSortedList<TestItem, L>) -> Vec<i32> {
+        list.list().iter().map(|item| item.value).collect()
+    }
+
+    /// Scrambles `0..n` so inserts land all over the list.
+    fn scrambled(n: i32) -> Vec<TestItem> {
+        (0..n).map(|i| make_item((i * 37) % n)).collect()
+    }
+
+    #[test]
+    fn promotes_at_threshold_and_stays_sorted() {
+        let mut pool: Vec<SkipNode<TestItem>> = (0..256).map(|_| SkipNode::new()).collect();
+        let mut items = scrambled(256);
+        let mut list = AdaptiveSortedList::<TestItem, 8>::new(cmp, 32);
+        pool.iter_mut().for_each(|entry| list.add_storage(entry));
+
+        for (count, item) in items.iter_mut().enumerate() {
+            list.insert(item);
+            assert_eq!(list.is_promoted(), count + 1 >= 32);
+        }
+        assert_eq!(values(&list), (0..256).collect::<Vec<_>>());
+        let lens = list.level_lens();
+        assert!(lens[0] > 64 && lens[0] > lens[1] && lens[1] > lens[2]);
+
+        for value in [0, 100, 200, 255] {
+            assert_eq!(list.find_equal(&make_item(value)).map(|item| item.value), Some(value));
+        }
+        assert!(list.find_equal(&make_item(256)).is_none());
+    }
+
+    #[test]
+    fn lookups_get_cheaper_once_promoted() {
+        let mut pool: Vec<SkipNode<TestItem>> = (0..512).map(|_| SkipNode::new()).collect();
+        let mut items = scrambled(512);
+        let mut linear = AdaptiveSortedList::<TestItem, 10>::new(cmp, usize::MAX);
+        items.iter_mut().for_each(|item| linear.insert(item));
+        calls();
+        linear.find_equal(&make_item(400));
+        let linear_calls = calls();
+
+        let mut items = scrambled(512);
+        let mut indexed = AdaptiveSortedList::<TestItem, 10>::new(cmp, 16);
+        pool.iter_mut().for_each(|entry| indexed.add_storage(entry));
+        items.iter_mut().for_each(|item| indexed.insert(item));
+        calls();
+        indexed.find_equal(&make_item(400));
+        let indexed_calls = calls();
+
+        assert_eq!(linear_calls, 402);
+        assert!(indexed_calls < 64, "{indexed_calls} comparisons");
+    }
+
+    #[test]
+    fn removals_unindex_and_demote() {
+        let mut pool: Vec<SkipNode<TestItem>> = (0..64).map(|_| SkipNode::new()).collect();
+        let mut items: Vec<TestItem> = (0..64).map(|i| make_item(i / 2)).collect();
+        let mut list = AdaptiveSortedList::<TestItem, 6>::new(cmp, 16);
+        pool.iter_mut().for_each(|entry| list.add_storage(entry));
+        items.iter_mut().for_each(|item| list.insert(item));
+        assert!(list.is_promoted());
+
+        // one of each pair of duplicates
+        for item in items.iter_mut().step_by(2) {
+            list.remove(item);
+        }
+        assert_eq!(values(&list), (0..32).collect::<Vec<_>>());
+        assert_eq!(list.spare_storage() + list.level_lens().iter().sum::<usize>(), 64);
+        for value in 0..32 {
+            assert_eq!(list.find_equal(&make_item(value)).map(|item| item.value), Some(value));
+        }
+
+        while list.len() > 8 {
+            list.pop();
+        }
+        assert!(!list.is_promoted());
+        assert_eq!(list.spare_storage(), 64);
+        assert_eq!(values(&list), (24..32).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn short_pool_degrades_gracefully() {
+        let mut pool: Vec<SkipNode<TestItem>> = (0..4).map(|_| SkipNode::new()).collect();
+        let mut items = scrambled(100);
+        let mut list = AdaptiveSortedList::<TestItem, 8>::new(cmp, 8);
+        pool.iter_mut().for_each(|entry| list.add_storage(entry));
+        items.iter_mut().for_each(|item| list.insert(item));
+
+        assert_eq!(list.spare_storage(), 0);
+        assert_eq!(values(&list), (0..100).collect::<Vec<_>>());
+        for value in 0..100 {
+            assert!(list.find_equal(&make_item(value)).is_some());
+        }
+    }
+}
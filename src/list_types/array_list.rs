@@ -0,0 +1,234 @@
+use core::mem::MaybeUninit;
+use core::ptr;
+
+/// A fixed-capacity, array-backed sorted container that mirrors `RustyList`'s API.
+///
+/// For elements too small to justify two link pointers each, this stores up to `N`
+/// values inline and keeps them in `order_function` order. It uses the same comparator
+/// convention as `RustyList` (`< 0`, `0`, `> 0` on two `*const T`) and the same
+/// operation names (`insert`, `find_equal`, `remove`, `pop`), so switching
+/// representation mostly touches the declaration. Unlike `RustyList` it owns its
+/// elements, so `insert` takes values and `remove`/`pop` give them back.
+pub struct RustyArrayList<T, const N: usize> {
+    len: usize,
+    items: [MaybeUninit<T>; N],
+    order_function: Option<fn(*const T, *const T) -> i32>,
+}
+
+impl<T, const N: usize> RustyArrayList<T, N> {
+    /// Creates an empty list that appends on `insert`.
+    pub const fn new() -> Self {
+        Self {
+            len: 0,
+            items: [const { MaybeUninit::uninit() }; N],
+            order_function: None,
+        }
+    }
+
+    /// Creates an empty list that keeps items sorted with `order`.
+    pub const fn new_with_order(order: fn(*const T, *const T) -> i32) -> Self {
+        Self {
+            len: 0,
+            items: [const { MaybeUninit::uninit() }; N],
+            order_function: Some(order),
+        }
+    }
+
+    /// Number of items in the list.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the list holds no items.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if no more items fit.
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// The maximum number of items, `N`.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// The items in list order.
+    pub fn as_slice(&self) -> &[T] {
+        // SAFETY: the first `len` items are initialized
+        unsafe { &*(&self.items[..self.len] as *const [MaybeUninit<T>] as *const [T]) }
+    }
+
+    /// Inserts `item` at its sorted position, after any equal items.
+    ///
+    /// Without an `order_function` the item is appended, like `RustyList::insert`.
+    ///
+    /// # Returns
+    /// `Err(item)` if the list is full.
+    pub fn insert(&mut self, item: T) -> Result<(), T> {
+        if self.len == N {
+            return Err(item);
+        }
+
+        let index = match self.order_function {
+            Some(cmp_fn) => {
+                let items = self.as_slice();
+                items.partition_point(|existing| cmp_fn(existing, &item) <= 0)
+            }
+            None => self.len,
+        };
+
+        // SAFETY: index <= len < N, so shifting `len - index` items up by one stays in bounds
+        unsafe {
+            let base = self.items.as_mut_ptr();
+            ptr::copy(base.add(index), base.add(index + 1), self.len - index);
+        }
+        self.items[index].write(item);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Finds the first item that compares equal to `target` with the `order_function`.
+    ///
+    /// Returns `None` if there is no match or no `order_function`. Because the items are
+    /// sorted this is a binary search.
+    pub fn find_equal(&mut self, target: &T) -> Option<&mut T> {
+        let index = self.index_of_equal(target)?;
+        // SAFETY: index < len
+        Some(unsafe { self.items[index].assume_init_mut() })
+    }
+
+    /// Removes and returns the first item that compares equal to `target`.
+    pub fn remove(&mut self, target: &T) -> Option<T> {
+        let index = self.index_of_equal(target)?;
+        Some(self.remove_at(index))
+    }
+
+    /// Removes and returns the first (smallest) item.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        Some(self.remove_at(0))
+    }
+
+    fn index_of_equal(&self, target: &T) -> Option<usize> {
+        let cmp_fn = self.order_function?;
+        let items = self.as_slice();
+        let index = items.partition_point(|existing| cmp_fn(existing, target) < 0);
+
+        match items.get(index) {
+            Some(found) if cmp_fn(found, target) == 0 => Some(index),
+            _ => None,
+        }
+    }
+
+    fn remove_at(&mut self, index: usize) -> T {
+        // SAFETY: index < len; the item is moved out and the tail shifted down over it
+        unsafe {
+            let item = self.items[index].assume_init_read();
+            let base = self.items.as_mut_ptr();
+            ptr::copy(base.add(index + 1), base.add(index), self.len - index - 1);
+            self.len -= 1;
+            item
+        }
+    }
+}
+
+impl<T, const N: usize> Default for RustyArrayList<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for RustyArrayList<T, N> {
+    fn drop(&mut self) {
+        // SAFETY: the first `len` items are initialized and dropped exactly once here
+        unsafe {
+            ptr::drop_in_place(&mut self.items[..self.len] as *mut [MaybeUninit<T>] as *mut [T]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[derive(Debug, PartialEq)]
+    struct Small {
+        key: u8,
+        tag: u8,
+    }
+
+    fn cmp(a: *const Small, b: *const Small) -> i32 {
+        unsafe { (*a).key.cmp(&(*b).key) as i32 }
+    }
+
+    fn small(key: u8, tag: u8) -> Small {
+        Small { key, tag }
+    }
+
+    #[test]
+    fn insert_keeps_sorted_order_and_stability() {
+        let mut list = RustyArrayList::<Small, 8>::new_with_order(cmp);
+        for (key, tag) in [(3, 0), (1, 0), (2, 0), (1, 1), (3, 1)] {
+            list.insert(small(key, tag)).unwrap();
+        }
+
+        let order: std::vec::Vec<(u8, u8)> = list.as_slice().iter().map(|s| (s.key, s.tag)).collect();
+        assert_eq!(order, std::vec![(1, 0), (1, 1), (2, 0), (3, 0), (3, 1)]);
+    }
+
+    #[test]
+    fn insert_rejects_when_full() {
+        let mut list = RustyArrayList::<Small, 2>::new_with_order(cmp);
+        list.insert(small(1, 0)).unwrap();
+        list.insert(small(2, 0)).unwrap();
+
+        assert!(list.is_full());
+        assert_eq!(list.insert(small(0, 7)), Err(small(0, 7)));
+        assert_eq!(list.len(), 2);
+    }
+
+    #[test]
+    fn find_equal_remove_and_pop() {
+        let mut list = RustyArrayList::<Small, 4>::new_with_order(cmp);
+        for key in [4, 2, 3] {
+            list.insert(small(key, 0)).unwrap();
+        }
+
+        list.find_equal(&small(3, 0)).unwrap().tag = 9;
+        assert!(list.find_equal(&small(5, 0)).is_none());
+
+        assert_eq!(list.remove(&small(3, 0)), Some(small(3, 9)));
+        assert_eq!(list.remove(&small(3, 0)), None);
+        assert_eq!(list.pop(), Some(small(2, 0)));
+        assert_eq!(list.as_slice(), &[small(4, 0)]);
+    }
+
+    #[test]
+    fn unordered_list_appends_and_has_no_lookup() {
+        let mut list = RustyArrayList::<Small, 4>::new();
+        list.insert(small(2, 0)).unwrap();
+        list.insert(small(1, 0)).unwrap();
+
+        assert_eq!(list.as_slice(), &[small(2, 0), small(1, 0)]);
+        assert!(list.find_equal(&small(2, 0)).is_none());
+        assert_eq!(list.pop(), Some(small(2, 0)));
+    }
+
+    #[test]
+    fn drop_releases_remaining_items() {
+        let tracker = Rc::new(());
+        {
+            let mut list = RustyArrayList::<Rc<()>, 4>::new();
+            list.insert(tracker.clone()).unwrap();
+            list.insert(tracker.clone()).unwrap();
+            drop(list.pop());
+            assert_eq!(Rc::strong_count(&tracker), 2);
+        }
+        assert_eq!(Rc::strong_count(&tracker), 1);
+    }
+}
@@ -0,0 +1,307 @@
+use core::fmt;
+use crate::{RustyList, HasRustyNode};
+
+/// What a `BoundedRustyList` does when an element arrives while it is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdmissionPolicy {
+    /// Refuse the new element; the list is unchanged.
+    Reject,
+    /// Admit the new element, then evict the head (the oldest for appended lists, the
+    /// smallest for sorted ones).
+    EvictHead,
+    /// Admit the new element, then evict the tail (the largest for sorted lists).
+    EvictTail,
+}
+
+/// Decides which element a full `BoundedRustyList` gives up for a new one.
+///
+/// Lets LRU, FIFO, priority-based or size-weighted eviction plug into the same container.
+/// `AdmissionPolicy` implements it for the common end-of-list choices.
+pub trait EvictionPolicy<T> {
+    /// Whether `incoming` may enter the full `list` at all. Returning `false` rejects it
+    /// with `ListFull` before it is linked. Admits everything by default.
+    fn admit(&mut self, list: &RustyList<T>, incoming: &T) -> bool {
+        let _ = (list, incoming);
+        true
+    }
+
+    /// Picks the element to evict from `list`, which already holds `incoming`.
+    ///
+    /// Must return an element of `list`; `incoming` itself is allowed. If it returns
+    /// anything else, `incoming` is unlinked again and rejected with `ListFull`.
+    fn victim<'a>(&mut self, list: &'a RustyList<T>, incoming: &'a T) -> &'a T;
+}
+
+impl<T> EvictionPolicy<T> for AdmissionPolicy {
+    fn admit(&mut self, _list: &RustyList<T>, _incoming: &T) -> bool {
+        *self != AdmissionPolicy::Reject
+    }
+
+    fn victim<'a>(&mut self, list: &'a RustyList<T>, incoming: &'a T) -> &'a T {
+        let end = match self {
+            AdmissionPolicy::EvictTail => list.tail_item(),
+            _ => list.head_item(),
+        };
+        end.map_or(incoming, |item| unsafe { &*item })
+    }
+}
+
+/// Returned by `BoundedRustyList::insert`/`push` when the list is full and its policy
+/// refuses the new element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListFull;
+
+impl fmt::Display for ListFull {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BoundedRustyList is full")
+    }
+}
+
+/// A `RustyList` with a maximum length and an eviction policy applied on insert.
+///
+/// Keeps backpressure handling in one place instead of at every call site that pushes.
+/// When the list is full, `insert`/`push` ask the policy `P` whether to reject the new
+/// element or admit it and evict another, handing the evicted element back to the
+/// caller. Because the element is placed before the eviction, a sorted list with
+/// `EvictTail` may evict the element that was just inserted if it is the largest; that
+/// keeps the `max_len` smallest elements.
+pub struct BoundedRustyList<T, P = AdmissionPolicy> {
+    list: RustyList<T>,
+    max_len: usize,
+    policy: P,
+}
+
+impl<T: HasRustyNode, P: EvictionPolicy<T>> BoundedRustyList<T, P> {
+    /// Creates an empty bounded list that appends on `insert`.
+    pub fn new(max_len: usize, policy: P) -> Self {
+        Self {
+            list: RustyList::new(),
+            max_len,
+            policy,
+        }
+    }
+
+    /// Creates an empty bounded list that keeps items sorted with `order`.
+    pub fn new_with_order(order: fn(*const T, *const T) -> i32, max_len: usize, policy: P) -> Self {
+        Self {
+            list: RustyList::new_with_order(order),
+            max_len,
+            policy,
+        }
+    }
+
+    /// The underlying list, for read-only operations.
+    pub fn list(&self) -> &RustyList<T> {
+        &self.list
+    }
+
+    /// Number of elements in the list.
+    pub fn len(&self) -> usize {
+        self.list.len
+    }
+
+    /// Returns `true` if the list holds no elements.
+    pub fn is_empty(&self) -> bool {
+        self.list.len == 0
+    }
+
+    /// Returns `true` if the list holds `max_len` elements or more.
+    pub fn is_full(&self) -> bool {
+        self.list.len >= self.max_len
+    }
+
+    /// The maximum number of elements.
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// Inserts `item` at its sorted position, applying the admission policy if full.
+    ///
+    /// # Returns
+    /// - `Ok(None)` if `item` was inserted without evicting anything.
+    /// - `Ok(Some(evicted))` if the list was full and `evicted` (possibly `item` itself) was
+    ///   unlinked to make room.
+    /// - `Err(ListFull)` if the list was full and the policy didn't admit `item`.
+    pub fn insert(&mut self, item: &mut T) -> Result<Option<*mut T>, ListFull> {
+        self.admit(item, RustyList::insert)
+    }
+
+    /// Appends `item` at the tail, applying the admission policy if full.
+    ///
+    /// Returns the same as `insert`.
+    pub fn push(&mut self, item: &mut T) -> Result<Option<*mut T>, ListFull> {
+        self.admit(item, RustyList::push)
+    }
+
+    /// The eviction policy, e.g. to update LRU state.
+    pub fn policy_mut(&mut self) -> &mut P {
+        &mut self.policy
+    }
+
+    /// Removes `item` from the list.
+    pub fn remove(&mut self, item: &mut T) {
+        self.list.remove(item);
+    }
+
+    /// Removes and returns the head element.
+    pub fn pop(&mut self) -> Option<*mut T> {
+        self.list.pop()
+    }
+
+    fn admit(
+        &mut self,
+        item: &mut T,
+        link: fn(&mut RustyList<T>, &mut T),
+    ) -> Result<Option<*mut T>, ListFull> {
+        if !self.is_full() {
+            link(&mut self.list, item);
+            return Ok(None);
+        }
+
+        if !self.policy.admit(&self.list, item) {
+            return Err(ListFull);
+        }
+
+        link(&mut self.list, item);
+        let victim = self.policy.victim(&self.list, item) as *const T;
+
+        // find the victim through the list's own pointers, ends first
+        let Some(evicted) = self.list.find_item(victim) else {
+            // a victim from outside the list would leave it over `max_len`: back `item` out
+            rusty_warn!("BoundedRustyList: policy victim is not in the list, rejecting");
+            self.list.remove(item);
+            return Err(ListFull);
+        };
+        self.list.remove(unsafe { &mut *evicted });
+        Ok(Some(evicted))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec;
+    use std::vec::Vec;
+    use crate::{RustyListNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn cmp(a: *const TestItem, b: *const TestItem) -> i32 {
+        unsafe { (*a).value.cmp(&(*b).value) as i32 }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    fn evicted_value(result: Result<Option<*mut TestItem>, ListFull>) -> Option<i32> {
+        result.unwrap().map(|item| unsafe { (*item).value })
+    }
+
+    /// Evicts the largest element, refusing newcomers larger than all residents.
+    struct EvictLargest {
+        evictions: usize,
+    }
+
+    impl EvictionPolicy<TestItem> for EvictLargest {
+        fn admit(&mut self, list: &RustyList<TestItem>, incoming: &TestItem) -> bool {
+            list.any(|item| item.value > incoming.value)
+        }
+
+        fn victim<'a>(&mut self, list: &'a RustyList<TestItem>, _incoming: &'a TestItem) -> &'a TestItem {
+            self.evictions += 1;
+            list.max_by(cmp).unwrap()
+        }
+    }
+
+    #[test]
+    fn custom_policy_picks_victim_anywhere() {
+        let mut list = BoundedRustyList::new(3, EvictLargest { evictions: 0 });
+        let mut items = [make_item(4), make_item(8), make_item(2), make_item(9), make_item(5)];
+        let [a, b, c, d, e] = &mut items;
+
+        list.push(a).unwrap();
+        list.push(b).unwrap();
+        list.push(c).unwrap();
+        assert_eq!(list.push(d), Err(ListFull));
+        assert_eq!(evicted_value(list.push(e)), Some(8));
+
+        assert_eq!(list.list().iter().map(|item| item.value).collect::<Vec<_>>(), vec![4, 2, 5]);
+        assert_eq!(list.policy_mut().evictions, 1);
+    }
+
+    /// Names an element that isn't in the list.
+    struct EvictStranger {
+        stranger: &'static TestItem,
+    }
+
+    impl EvictionPolicy<TestItem> for EvictStranger {
+        fn victim<'a>(&mut self, _list: &'a RustyList<TestItem>, _incoming: &'a TestItem) -> &'a TestItem {
+            self.stranger
+        }
+    }
+
+    #[test]
+    fn victim_outside_the_list_rejects_incoming() {
+        let mut list = BoundedRustyList::new(1, EvictStranger { stranger: std::boxed::Box::leak(std::boxed::Box::new(make_item(0))) });
+        let mut items = [make_item(1), make_item(2)];
+
+        assert_eq!(list.push(&mut items[0]), Ok(None));
+        assert_eq!(list.push(&mut items[1]), Err(ListFull));
+        assert!(!items[1].node.is_linked());
+        assert_eq!(list.len(), 1);
+    }
+
+    #[test]
+    fn reject_leaves_list_unchanged() {
+        let mut list = BoundedRustyList::<TestItem>::new(2, AdmissionPolicy::Reject);
+        let mut items = [make_item(1), make_item(2), make_item(3)];
+
+        assert_eq!(list.push(&mut items[0]), Ok(None));
+        assert_eq!(list.push(&mut items[1]), Ok(None));
+        assert!(list.is_full());
+        assert_eq!(list.push(&mut items[2]), Err(ListFull));
+        assert!(!items[2].node.is_linked());
+        assert_eq!(list.list().iter().map(|item| item.value).collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn evict_head_drops_oldest() {
+        let mut list = BoundedRustyList::<TestItem>::new(2, AdmissionPolicy::EvictHead);
+        let mut items = [make_item(1), make_item(2), make_item(3)];
+
+        list.push(&mut items[0]).unwrap();
+        list.push(&mut items[1]).unwrap();
+        assert_eq!(evicted_value(list.push(&mut items[2])), Some(1));
+        assert!(!items[0].node.is_linked());
+        assert_eq!(list.list().iter().map(|item| item.value).collect::<Vec<_>>(), vec![2, 3]);
+    }
+
+    #[test]
+    fn evict_tail_keeps_smallest_in_sorted_list() {
+        let mut list = BoundedRustyList::<TestItem>::new_with_order(cmp, 2, AdmissionPolicy::EvictTail);
+        let mut items = [make_item(5), make_item(3), make_item(9), make_item(1)];
+
+        list.insert(&mut items[0]).unwrap();
+        list.insert(&mut items[1]).unwrap();
+        // the newcomer is the largest, so it is the one evicted
+        assert_eq!(evicted_value(list.insert(&mut items[2])), Some(9));
+        assert_eq!(evicted_value(list.insert(&mut items[3])), Some(5));
+        assert_eq!(list.list().iter().map(|item| item.value).collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(list.len(), 2);
+    }
+}
@@ -0,0 +1,195 @@
+use core::cell::{Cell, UnsafeCell};
+use core::fmt;
+use crate::RustyList;
+
+/// Returned by `RustyListCell::try_with_mut`/`try_with` when the list is already borrowed
+/// in a conflicting way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlreadyBorrowed;
+
+impl fmt::Display for AlreadyBorrowed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RustyListCell is already borrowed")
+    }
+}
+
+/// Borrow state: `0` free, `> 0` number of shared borrows, `-1` borrowed mutably.
+const MUT_BORROWED: isize = -1;
+
+/// Interior-mutability wrapper that lets `&`-shared code mutate a `RustyList`.
+///
+/// Meant for single-threaded executors where several tasks hold a shared reference to the
+/// same list. Borrows are tracked at runtime like `RefCell`, but access is scoped to a
+/// closure so a borrow can never outlive the call: `with_mut` panics (and `try_with_mut`
+/// fails) if the list is already borrowed, e.g. from inside another `with_mut` closure.
+///
+/// The cell is `!Sync`, so it can't be shared between threads; use a lock for that.
+pub struct RustyListCell<T> {
+    borrow: Cell<isize>,
+    list: UnsafeCell<RustyList<T>>,
+}
+
+/// Restores the borrow state when a closure returns or unwinds.
+struct BorrowGuard<'a> {
+    borrow: &'a Cell<isize>,
+    restore: isize,
+}
+
+impl Drop for BorrowGuard<'_> {
+    fn drop(&mut self) {
+        self.borrow.set(self.restore);
+    }
+}
+
+impl<T> RustyListCell<T> {
+    /// Wraps `list` in a cell.
+    pub const fn new(list: RustyList<T>) -> Self {
+        Self {
+            borrow: Cell::new(0),
+            list: UnsafeCell::new(list),
+        }
+    }
+
+    /// Unwraps the cell and returns the list.
+    pub fn into_inner(self) -> RustyList<T> {
+        self.list.into_inner()
+    }
+
+    /// Returns `true` if the list is currently borrowed (shared or mutably).
+    pub fn is_borrowed(&self) -> bool {
+        self.borrow.get() != 0
+    }
+
+    /// Runs `f` with exclusive access to the list.
+    ///
+    /// # Panics
+    /// Panics if the list is already borrowed.
+    #[track_caller]
+    pub fn with_mut<R>(&self, f: impl FnOnce(&mut RustyList<T>) -> R) -> R {
+        match self.try_with_mut(f) {
+            Ok(result) => result,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Runs `f` with exclusive access to the list, or fails if it is already borrowed.
+    pub fn try_with_mut<R>(&self, f: impl FnOnce(&mut RustyList<T>) -> R) -> Result<R, AlreadyBorrowed> {
+        if self.borrow.get() != 0 {
+            return Err(AlreadyBorrowed);
+        }
+
+        self.borrow.set(MUT_BORROWED);
+        let _guard = BorrowGuard { borrow: &self.borrow, restore: 0 };
+
+        // SAFETY: the borrow flag guarantees no other reference to the list exists
+        Ok(f(unsafe { &mut *self.list.get() }))
+    }
+
+    /// Runs `f` with shared access to the list. Shared borrows may nest.
+    ///
+    /// # Panics
+    /// Panics if the list is borrowed mutably.
+    #[track_caller]
+    pub fn with<R>(&self, f: impl FnOnce(&RustyList<T>) -> R) -> R {
+        match self.try_with(f) {
+            Ok(result) => result,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Runs `f` with shared access to the list, or fails if it is borrowed mutably.
+    pub fn try_with<R>(&self, f: impl FnOnce(&RustyList<T>) -> R) -> Result<R, AlreadyBorrowed> {
+        let current = self.borrow.get();
+        if current == MUT_BORROWED {
+            return Err(AlreadyBorrowed);
+        }
+
+        self.borrow.set(current + 1);
+        let _guard = BorrowGuard { borrow: &self.borrow, restore: current };
+
+        // SAFETY: the borrow flag guarantees no mutable reference to the list exists
+        Ok(f(unsafe { &*self.list.get() }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn shared_references_can_mutate() {
+        let cell = RustyListCell::new(RustyList::<TestItem>::new());
+        let producer = &cell;
+        let consumer = &cell;
+
+        let mut a = make_item(1);
+        let mut b = make_item(2);
+        producer.with_mut(|list| list.push(&mut a));
+        producer.with_mut(|list| list.push(&mut b));
+
+        assert_eq!(consumer.with(|list| list.len), 2);
+        let first = consumer.with_mut(|list| list.pop()).unwrap();
+        assert_eq!(unsafe { (*first).value }, 1);
+        assert!(!cell.is_borrowed());
+        assert_eq!(cell.into_inner().len, 1);
+    }
+
+    #[test]
+    fn nested_mutable_borrow_is_rejected() {
+        let cell = RustyListCell::new(RustyList::<TestItem>::new());
+
+        cell.with_mut(|_| {
+            assert!(cell.is_borrowed());
+            assert_eq!(cell.try_with_mut(|_| ()), Err(AlreadyBorrowed));
+            assert_eq!(cell.try_with(|_| ()), Err(AlreadyBorrowed));
+        });
+
+        // shared borrows nest, but block mutation
+        cell.with(|_| {
+            assert_eq!(cell.try_with(|list| list.len), Ok(0));
+            assert_eq!(cell.try_with_mut(|_| ()), Err(AlreadyBorrowed));
+        });
+
+        assert!(!cell.is_borrowed());
+    }
+
+    #[test]
+    #[should_panic(expected = "RustyListCell is already borrowed")]
+    fn with_mut_panics_when_reentered() {
+        let cell = RustyListCell::new(RustyList::<TestItem>::new());
+        cell.with_mut(|_| cell.with_mut(|_| ()));
+    }
+
+    #[test]
+    fn borrow_is_released_after_panic() {
+        let cell = RustyListCell::new(RustyList::<TestItem>::new());
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            cell.with_mut(|_| panic!("boom"));
+        }));
+
+        assert!(result.is_err());
+        assert!(!cell.is_borrowed());
+        assert_eq!(cell.try_with_mut(|list| list.len), Ok(0));
+    }
+}
@@ -0,0 +1,161 @@
+use crate::{RustyList, HasRustyNode};
+
+/// A `RustyList` that keeps a running count of its elements per key class.
+///
+/// `class_of` maps each element to a class in `0..CLASSES` (a priority level, a traffic
+/// class, ...), and the counts are updated by every operation that goes through the
+/// wrapper, so `count(class)` answers "how many of these are queued" without a traversal.
+/// Elements whose class is `>= CLASSES` are stored but not counted. An element's class
+/// must not change while it is in the list.
+pub struct CountingRustyList<T, const CLASSES: usize> {
+    list: RustyList<T>,
+    class_of: fn(*const T) -> usize,
+    counts: [usize; CLASSES],
+}
+
+impl<T: HasRustyNode, const CLASSES: usize> CountingRustyList<T, CLASSES> {
+    /// Creates an empty counting list that appends on `insert`.
+    pub fn new(class_of: fn(*const T) -> usize) -> Self {
+        Self {
+            list: RustyList::new(),
+            class_of,
+            counts: [0; CLASSES],
+        }
+    }
+
+    /// Creates an empty counting list that keeps items sorted with `order`.
+    pub fn new_with_order(order: fn(*const T, *const T) -> i32, class_of: fn(*const T) -> usize) -> Self {
+        Self {
+            list: RustyList::new_with_order(order),
+            class_of,
+            counts: [0; CLASSES],
+        }
+    }
+
+    /// The underlying list, for read-only operations.
+    pub fn list(&self) -> &RustyList<T> {
+        &self.list
+    }
+
+    /// Number of elements in the list.
+    pub fn len(&self) -> usize {
+        self.list.len
+    }
+
+    /// Returns `true` if the list holds no elements.
+    pub fn is_empty(&self) -> bool {
+        self.list.len == 0
+    }
+
+    /// Number of elements of `class` in the list; 0 for classes `>= CLASSES`.
+    pub fn count(&self, class: usize) -> usize {
+        self.counts.get(class).copied().unwrap_or(0)
+    }
+
+    /// The per-class counts.
+    pub fn counts(&self) -> &[usize; CLASSES] {
+        &self.counts
+    }
+
+    /// Inserts `item` at its sorted position and counts it.
+    pub fn insert(&mut self, item: &mut T) {
+        let before = self.list.len;
+        self.list.insert(item);
+        if self.list.len > before {
+            self.adjust(item, true);
+        }
+    }
+
+    /// Appends `item` at the tail and counts it.
+    pub fn push(&mut self, item: &mut T) {
+        let before = self.list.len;
+        self.list.push(item);
+        if self.list.len > before {
+            self.adjust(item, true);
+        }
+    }
+
+    /// Removes `item` from the list and uncounts it.
+    pub fn remove(&mut self, item: &mut T) {
+        let before = self.list.len;
+        self.list.remove(item);
+        if self.list.len < before {
+            self.adjust(item, false);
+        }
+    }
+
+    /// Removes and returns the head element.
+    pub fn pop(&mut self) -> Option<*mut T> {
+        let item = self.list.pop()?;
+        self.adjust(item, false);
+        Some(item)
+    }
+
+    fn adjust(&mut self, item: *const T, added: bool) {
+        if let Some(count) = self.counts.get_mut((self.class_of)(item)) {
+            if added {
+                *count += 1;
+            } else {
+                *count -= 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RustyListNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub priority: usize,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn priority_of(item: *const TestItem) -> usize {
+        unsafe { (*item).priority }
+    }
+
+    fn make_item(val: i32, priority: usize) -> TestItem {
+        TestItem {
+            value: val,
+            priority,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn counts_follow_list_operations() {
+        let mut list = CountingRustyList::<TestItem, 4>::new(priority_of);
+        let mut a = make_item(1, 3);
+        let mut b = make_item(2, 3);
+        let mut c = make_item(3, 0);
+        let mut d = make_item(4, 9);
+
+        list.push(&mut a);
+        list.insert(&mut b);
+        list.push(&mut c);
+        list.push(&mut d);
+
+        assert_eq!(list.counts(), &[1, 0, 0, 2]);
+        assert_eq!(list.count(9), 0);
+        assert_eq!(list.len(), 4);
+
+        list.remove(&mut b);
+        // removing an element that is no longer linked doesn't uncount twice
+        list.remove(&mut b);
+        assert_eq!(list.count(3), 1);
+
+        assert_eq!(unsafe { (*list.pop().unwrap()).value }, 1);
+        assert_eq!(list.counts(), &[1, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,233 @@
+use crate::{RustyList, HasRustyNode};
+
+/// Gives the tick at which an element in a `DeadlineList` expires.
+pub trait HasDeadline {
+    /// Returns the element's deadline. Must not change while the element is queued;
+    /// remove it, change it and insert it again.
+    fn deadline(&self) -> u64;
+}
+
+fn by_deadline<T: HasDeadline>(a: *const T, b: *const T) -> i32 {
+    unsafe { (*a).deadline().cmp(&(*b).deadline()) as i32 }
+}
+
+/// A timer list with `N` one-tick buckets for the near future and a sorted overflow list
+/// for everything later.
+///
+/// An element due within the next `N` ticks is appended to the bucket for its tick in
+/// O(1); only far-future elements pay for a sorted insert, and they land in a list that
+/// bursts of near timers never touch. As time advances, the overflow list is cascaded
+/// into the buckets from its head, so each element is moved at most once. Elements whose
+/// deadline has already passed when inserted go into the bucket that expires next.
+pub struct DeadlineList<T, const N: usize> {
+    buckets: [RustyList<T>; N],
+    overflow: RustyList<T>,
+    /// The first tick not expired yet; the buckets cover `next_tick..next_tick + N`.
+    next_tick: u64,
+    /// Number of elements in the buckets.
+    near: usize,
+}
+
+impl<T: HasRustyNode + HasDeadline, const N: usize> DeadlineList<T, N> {
+    /// Creates an empty list whose first tick is `now`. `N` must be at least 1.
+    pub fn new(now: u64) -> Self {
+        const { assert!(N > 0, "DeadlineList needs at least one bucket") };
+        Self {
+            buckets: core::array::from_fn(|_| RustyList::new()),
+            overflow: RustyList::new_with_order(by_deadline::<T>),
+            next_tick: now,
+            near: 0,
+        }
+    }
+}
+
+impl<T: HasDeadline, const N: usize> DeadlineList<T, N> {
+    /// Queues `item` to expire at its deadline.
+    pub fn insert(&mut self, item: &mut T) {
+        match self.bucket_of(item.deadline()) {
+            Some(index) => {
+                self.buckets[index].push(item);
+                self.near += 1;
+            }
+            None => self.overflow.insert(item),
+        }
+    }
+
+    /// Removes a queued `item` before it expires.
+    ///
+    /// # Returns
+    /// `false` if `item` wasn't queued.
+    pub fn remove(&mut self, item: &mut T) -> bool {
+        if !unsafe { (*self.overflow.node_of(item).as_ptr()).linked } {
+            return false;
+        }
+
+        match self.bucket_of(item.deadline()) {
+            Some(index) => {
+                self.buckets[index].remove(item);
+                self.near -= 1;
+            }
+            None => self.overflow.remove(item),
+        }
+        true
+    }
+
+    /// Expires every element with a deadline at or before `now`, handing each to
+    /// `on_expired` once it is unlinked, tick by tick.
+    ///
+    /// Ticks with nothing in the buckets are skipped straight to the next overflow
+    /// deadline, so a long idle gap costs no more than a short one.
+    ///
+    /// # Returns
+    /// The number of elements expired.
+    pub fn advance(&mut self, now: u64, mut on_expired: impl FnMut(*mut T)) -> usize {
+        let mut expired = 0;
+
+        while self.next_tick <= now {
+            if self.near == 0 {
+                // nothing near: jump to the next overflow deadline, or past `now`
+                let next = self.overflow.peek_ref().map_or(u64::MAX, |item| item.deadline());
+                self.next_tick = self.next_tick.max(next.min(now.saturating_add(1)));
+                self.cascade();
+                if self.near == 0 {
+                    break;
+                }
+                continue;
+            }
+
+            let bucket = &mut self.buckets[(self.next_tick % N as u64) as usize];
+            while let Some(item) = bucket.pop() {
+                self.near -= 1;
+                expired += 1;
+                on_expired(item);
+            }
+
+            self.next_tick += 1;
+            self.cascade();
+        }
+
+        expired
+    }
+
+    /// Moves overflow elements that have come within the bucket window into their buckets.
+    fn cascade(&mut self) {
+        while let Some(item) = self.overflow.peek() {
+            let Some(index) = self.bucket_of(unsafe { (*item).deadline() }) else {
+                break;
+            };
+            self.overflow.pop();
+            self.buckets[index].push(unsafe { &mut *item });
+            self.near += 1;
+        }
+    }
+
+    /// The bucket for `deadline`, or `None` if it lies beyond the bucket window.
+    fn bucket_of(&self, deadline: u64) -> Option<usize> {
+        let tick = deadline.max(self.next_tick);
+        (tick - self.next_tick < N as u64).then_some((tick % N as u64) as usize)
+    }
+
+    /// The first tick that hasn't expired yet.
+    pub fn next_tick(&self) -> u64 {
+        self.next_tick
+    }
+
+    /// The earliest deadline queued, or `None` if the list is empty.
+    ///
+    /// Elements whose deadline had already passed when inserted report the tick they will
+    /// expire on instead.
+    pub fn next_deadline(&self) -> Option<u64> {
+        if self.near > 0 {
+            let start = self.next_tick;
+            return (start..start + N as u64)
+                .find(|&tick| self.buckets[(tick % N as u64) as usize].len > 0);
+        }
+        self.overflow.peek_ref().map(|item| item.deadline())
+    }
+
+    /// Number of queued elements.
+    pub fn len(&self) -> usize {
+        self.near + self.overflow.len
+    }
+
+    /// Returns `true` if nothing is queued.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec;
+    use std::vec::Vec;
+    use crate::{RustyListNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct Timer {
+        pub deadline: u64,
+        pub node: RustyListNode<Timer>,
+    }
+
+    impl HasRustyNode for Timer {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    impl HasDeadline for Timer {
+        fn deadline(&self) -> u64 {
+            self.deadline
+        }
+    }
+
+    fn make_timer(deadline: u64) -> Timer {
+        Timer {
+            deadline,
+            node: RustyListNode::new(),
+        }
+    }
+
+    fn expire(list: &mut DeadlineList<Timer, 4>, now: u64) -> Vec<u64> {
+        let mut out = vec![];
+        list.advance(now, |timer| out.push(unsafe { (*timer).deadline }));
+        out
+    }
+
+    #[test]
+    fn near_and_far_timers_expire_in_order() {
+        let mut list = DeadlineList::<Timer, 4>::new(10);
+        let mut timers: Vec<Timer> = [12, 40, 11, 13, 25, 14, 5, 26].into_iter().map(make_timer).collect();
+        for timer in timers.iter_mut() {
+            list.insert(timer);
+        }
+        assert_eq!(list.len(), 8);
+        assert_eq!(list.next_deadline(), Some(10));
+
+        assert_eq!(expire(&mut list, 12), [5, 11, 12]);
+        assert_eq!(list.next_tick(), 13);
+        assert_eq!(expire(&mut list, 25), [13, 14, 25]);
+
+        // 26 has cascaded into a bucket and can still be cancelled there
+        assert!(list.remove(&mut timers[7]));
+        assert!(!list.remove(&mut timers[7]));
+        assert_eq!(list.next_deadline(), Some(40));
+        assert_eq!(expire(&mut list, 1000), [40]);
+        assert!(list.is_empty());
+        assert_eq!(list.next_tick(), 1001);
+    }
+
+    #[test]
+    fn far_timer_removed_from_overflow() {
+        let mut list = DeadlineList::<Timer, 4>::new(0);
+        let mut far = make_timer(100);
+        let mut near = make_timer(2);
+        list.insert(&mut far);
+        list.insert(&mut near);
+
+        assert!(list.remove(&mut far));
+        assert_eq!(expire(&mut list, 200), [2]);
+        assert!(!far.node.is_linked());
+    }
+}
@@ -0,0 +1,199 @@
+use crate::{IndexLinks, LinkIndex, RustyIndexList, SlotOutOfRange};
+
+/// A ring of DMA descriptors, split into a driver-owned and a hardware-owned list.
+///
+/// The descriptors live in a caller-provided, physically contiguous array whose layout
+/// the hardware dictates, so nothing can be embedded in them. Their links are kept in a
+/// separate `IndexLinks` table instead, and the two `RustyIndexList`s over it track who
+/// owns each slot: the driver fills a free descriptor, hands it over with `give_to_hw`,
+/// and gets it back with `take_from_hw` once the hardware has completed it. Both lists
+/// are FIFOs, matching the order a ring is processed in.
+///
+/// ```ignore
+/// #[repr(C)]
+/// struct TxDesc { addr: u32, len: u16, flags: u16 }
+///
+/// static mut DESCS: [TxDesc; 16] = [const { TxDesc::EMPTY }; 16];
+/// let mut links = [IndexLinks::<u8>::UNLINKED; 16];
+/// let mut ring = DmaRing::new(unsafe { &mut *addr_of_mut!(DESCS) }, &mut links)?;
+///
+/// let slot = ring.next_free().unwrap();
+/// *ring.descriptor_mut(slot).unwrap() = TxDesc { addr, len, flags: OWN };
+/// ring.give_to_hw();
+/// // later, in the completion interrupt
+/// while let Some(slot) = ring.take_from_hw_if(|desc| desc.flags & OWN == 0) { ... }
+/// ```
+///
+/// The ring never reads or writes a descriptor on its own; only the caller does, through
+/// `descriptor`/`descriptor_mut`. Writing a descriptor the hardware owns is allowed but
+/// is the caller's responsibility to synchronize.
+pub struct DmaRing<'a, D, I: LinkIndex> {
+    descriptors: &'a mut [D],
+    links: &'a mut [IndexLinks<I>],
+    driver: RustyIndexList<I>,
+    hw: RustyIndexList<I>,
+}
+
+impl<'a, D, I: LinkIndex> DmaRing<'a, D, I> {
+    /// Creates a ring over `descriptors`, with every slot owned by the driver in index
+    /// order.
+    ///
+    /// `links` is reset and must have an entry for every descriptor. Fails with the
+    /// first slot that doesn't fit the link type or the table.
+    pub fn new(descriptors: &'a mut [D], links: &'a mut [IndexLinks<I>]) -> Result<Self, SlotOutOfRange> {
+        links.fill(IndexLinks::UNLINKED);
+        let mut driver = RustyIndexList::new();
+        for slot in 0..descriptors.len() {
+            driver.push(links, slot)?;
+        }
+
+        Ok(Self {
+            descriptors,
+            links,
+            driver,
+            hw: RustyIndexList::new(),
+        })
+    }
+
+    /// Number of descriptors in the ring.
+    pub fn capacity(&self) -> usize {
+        self.descriptors.len()
+    }
+
+    /// Number of descriptors the driver owns.
+    pub fn driver_len(&self) -> usize {
+        self.driver.len()
+    }
+
+    /// Number of descriptors the hardware owns.
+    pub fn hw_len(&self) -> usize {
+        self.hw.len()
+    }
+
+    /// The descriptor `give_to_hw` would hand over next, for filling it in first.
+    pub fn next_free(&self) -> Option<usize> {
+        self.driver.head()
+    }
+
+    /// The descriptor `take_from_hw` would take back next, i.e. the oldest one the
+    /// hardware owns.
+    pub fn next_pending(&self) -> Option<usize> {
+        self.hw.head()
+    }
+
+    /// Moves the oldest driver-owned descriptor to the tail of the hardware list and
+    /// returns its slot, or `None` if the driver owns none.
+    pub fn give_to_hw(&mut self) -> Option<usize> {
+        let slot = self.driver.pop(self.links)?;
+        self.hw.push(self.links, slot).ok()?;
+        Some(slot)
+    }
+
+    /// Moves the oldest hardware-owned descriptor back to the tail of the driver list and
+    /// returns its slot, or `None` if the hardware owns none.
+    pub fn take_from_hw(&mut self) -> Option<usize> {
+        let slot = self.hw.pop(self.links)?;
+        self.driver.push(self.links, slot).ok()?;
+        Some(slot)
+    }
+
+    /// Like `take_from_hw`, but only if `done` says the hardware has finished with the
+    /// oldest descriptor, e.g. by checking that it cleared the ownership bit.
+    pub fn take_from_hw_if(&mut self, done: impl FnOnce(&D) -> bool) -> Option<usize> {
+        let slot = self.hw.head()?;
+        if !done(self.descriptors.get(slot)?) {
+            return None;
+        }
+        self.take_from_hw()
+    }
+
+    /// The descriptor in `slot`, or `None` if it is out of range.
+    pub fn descriptor(&self, slot: usize) -> Option<&D> {
+        self.descriptors.get(slot)
+    }
+
+    /// Mutable access to the descriptor in `slot`, or `None` if it is out of range.
+    pub fn descriptor_mut(&mut self, slot: usize) -> Option<&mut D> {
+        self.descriptors.get_mut(slot)
+    }
+
+    /// The start of the descriptor array, for programming the hardware's base address
+    /// register. Slot `i` is at `as_ptr().add(i)`, a fixed `size_of::<D>()` stride apart.
+    pub fn as_ptr(&self) -> *const D {
+        self.descriptors.as_ptr()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec;
+    use std::vec::Vec;
+
+    const OWN: u16 = 0x8000;
+
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Desc {
+        addr: u32,
+        len: u16,
+        flags: u16,
+    }
+
+    const EMPTY: Desc = Desc { addr: 0, len: 0, flags: 0 };
+
+    #[test]
+    fn descriptors_move_between_driver_and_hardware() {
+        let mut descs = [EMPTY; 4];
+        let mut links = [IndexLinks::<u8>::UNLINKED; 4];
+        let mut ring = DmaRing::new(&mut descs, &mut links).unwrap();
+        assert_eq!((ring.capacity(), ring.driver_len(), ring.hw_len()), (4, 4, 0));
+
+        let mut given = vec![];
+        for addr in [0x100, 0x200, 0x300] {
+            let slot = ring.next_free().unwrap();
+            *ring.descriptor_mut(slot).unwrap() = Desc { addr, len: 64, flags: OWN };
+            given.push(ring.give_to_hw().unwrap());
+        }
+        assert_eq!(given, [0, 1, 2]);
+        assert_eq!((ring.driver_len(), ring.hw_len()), (1, 3));
+
+        // the hardware completes the first descriptor only
+        ring.descriptor_mut(0).unwrap().flags = 0;
+        let mut done = vec![];
+        while let Some(slot) = ring.take_from_hw_if(|desc| desc.flags & OWN == 0) {
+            done.push(slot);
+        }
+        assert_eq!(done, [0]);
+        assert_eq!(ring.next_pending(), Some(1));
+
+        // freed slots go to the back of the free list
+        assert_eq!(ring.take_from_hw(), Some(1));
+        assert_eq!(ring.give_to_hw(), Some(3));
+        assert_eq!(ring.give_to_hw(), Some(0));
+        assert_eq!((ring.driver_len(), ring.hw_len()), (1, 3));
+        assert_eq!(unsafe { (*ring.as_ptr().add(1)).addr }, 0x200);
+    }
+
+    #[test]
+    fn descriptors_are_never_touched_by_the_ring() {
+        let mut descs: Vec<Desc> = (0..3).map(|i| Desc { addr: i, ..EMPTY }).collect();
+        let mut links = [IndexLinks::<u16>::UNLINKED; 3];
+        let mut ring = DmaRing::new(&mut descs, &mut links).unwrap();
+
+        while ring.give_to_hw().is_some() {}
+        while ring.take_from_hw().is_some() {}
+        assert!(ring.give_to_hw().is_some());
+        assert_eq!(ring.descriptor(5), None);
+
+        assert_eq!(descs.iter().map(|desc| desc.addr).collect::<Vec<_>>(), [0, 1, 2]);
+        assert!(descs.iter().all(|desc| desc.flags == 0 && desc.len == 0));
+    }
+
+    #[test]
+    fn short_link_tables_are_rejected() {
+        let mut descs = [EMPTY; 4];
+        let mut links = [IndexLinks::<u8>::UNLINKED; 3];
+        assert_eq!(DmaRing::new(&mut descs, &mut links).err(), Some(SlotOutOfRange { slot: 3 }));
+    }
+}
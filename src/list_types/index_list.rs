@@ -0,0 +1,283 @@
+use core::fmt;
+
+/// An integer type usable as a link in a `RustyIndexList`.
+///
+/// The largest value is reserved as the "no link" sentinel, so a list with `u8` links
+/// addresses up to 255 slots, `u16` up to 65535, and so on. Narrow links keep the link
+/// table small: a 64-entry descriptor table with `u8` links needs 128 bytes of links
+/// instead of 1 KiB of pointers. `u32` links are not available on 16-bit targets, where
+/// slot numbers can't exceed `u16` anyway.
+pub trait LinkIndex: Copy + Eq + TryFrom<usize> {
+    /// The sentinel meaning "no link".
+    const NIL: Self;
+
+    /// Widens the index back to a slot number.
+    fn index(self) -> usize;
+}
+
+macro_rules! impl_link_index {
+    ($($ty:ty),*) => {
+        $(
+            impl LinkIndex for $ty {
+                const NIL: Self = <$ty>::MAX;
+
+                #[inline(always)]
+                fn index(self) -> usize {
+                    self as usize
+                }
+            }
+        )*
+    };
+}
+
+impl_link_index!(u8, u16, usize);
+// a `u32` link would be truncated into a 16-bit `usize` slot number, and its sentinel
+// would collide with a valid slot
+#[cfg(not(target_pointer_width = "16"))]
+impl_link_index!(u32);
+
+/// Returned when a slot number can't be linked: it doesn't fit the link type, collides
+/// with its sentinel, or lies outside the link table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotOutOfRange {
+    /// The rejected slot number.
+    pub slot: usize,
+}
+
+impl fmt::Display for SlotOutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "slot {} does not fit the index list's link type or table", self.slot)
+    }
+}
+
+/// The `prev`/`next` links of one slot, stored outside the element itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexLinks<I: LinkIndex> {
+    prev: I,
+    next: I,
+}
+
+impl<I: LinkIndex> IndexLinks<I> {
+    /// Links of a slot that is in no list, for initializing link tables.
+    pub const UNLINKED: Self = Self { prev: I::NIL, next: I::NIL };
+
+    /// Creates unlinked links.
+    pub const fn new() -> Self {
+        Self::UNLINKED
+    }
+}
+
+impl<I: LinkIndex> Default for IndexLinks<I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A doubly linked list of slot numbers whose links live in a caller-provided table.
+///
+/// Instead of embedding pointers in each element, slot `i` of the elements array is linked
+/// through `links[i]`, and the links are `I`-sized indices. Several lists can share one
+/// link table as long as every slot is in at most one of them, so moving a slot between
+/// lists touches only the table. Every operation takes the table explicitly and checks the
+/// slot against it, so a misused slot corrupts the list structure but never memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RustyIndexList<I: LinkIndex> {
+    len: usize,
+    head: I,
+    tail: I,
+}
+
+impl<I: LinkIndex> RustyIndexList<I> {
+    /// An empty list, for initializing arrays of list heads.
+    pub const EMPTY: Self = Self { len: 0, head: I::NIL, tail: I::NIL };
+
+    /// Creates an empty list.
+    pub const fn new() -> Self {
+        Self::EMPTY
+    }
+
+    /// Number of slots in the list.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the list has no slots.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The first slot, if any.
+    pub fn head(&self) -> Option<usize> {
+        link(self.head)
+    }
+
+    /// The last slot, if any.
+    pub fn tail(&self) -> Option<usize> {
+        link(self.tail)
+    }
+
+    /// The slot after `slot`, or `None` at the tail or for an out-of-range slot.
+    pub fn next(&self, links: &[IndexLinks<I>], slot: usize) -> Option<usize> {
+        link(links.get(slot)?.next)
+    }
+
+    /// The slot before `slot`, or `None` at the head or for an out-of-range slot.
+    pub fn prev(&self, links: &[IndexLinks<I>], slot: usize) -> Option<usize> {
+        link(links.get(slot)?.prev)
+    }
+
+    /// Appends `slot` at the tail.
+    ///
+    /// `slot` must not already be in a list sharing `links`.
+    pub fn push(&mut self, links: &mut [IndexLinks<I>], slot: usize) -> Result<(), SlotOutOfRange> {
+        let index = checked_index(links, slot)?;
+        let prev = self.tail;
+
+        links[slot] = IndexLinks { prev, next: I::NIL };
+        match link(prev) {
+            Some(p) => links[p].next = index,
+            None => self.head = index,
+        }
+        self.tail = index;
+        self.len = self.len.saturating_add(1);
+        Ok(())
+    }
+
+    /// Prepends `slot` at the head.
+    ///
+    /// `slot` must not already be in a list sharing `links`.
+    pub fn push_front(&mut self, links: &mut [IndexLinks<I>], slot: usize) -> Result<(), SlotOutOfRange> {
+        let index = checked_index(links, slot)?;
+        let next = self.head;
+
+        links[slot] = IndexLinks { prev: I::NIL, next };
+        match link(next) {
+            Some(n) => links[n].prev = index,
+            None => self.tail = index,
+        }
+        self.head = index;
+        self.len = self.len.saturating_add(1);
+        Ok(())
+    }
+
+    /// Removes and returns the head slot.
+    pub fn pop(&mut self, links: &mut [IndexLinks<I>]) -> Option<usize> {
+        let slot = self.head()?;
+        self.remove(links, slot).ok()?;
+        Some(slot)
+    }
+
+    /// Unlinks `slot` from the list.
+    ///
+    /// `slot` must be in this list.
+    pub fn remove(&mut self, links: &mut [IndexLinks<I>], slot: usize) -> Result<(), SlotOutOfRange> {
+        checked_index(links, slot)?;
+        let IndexLinks { prev, next } = links[slot];
+
+        match link(prev) {
+            Some(p) => links[p].next = next,
+            None => self.head = next,
+        }
+        match link(next) {
+            Some(n) => links[n].prev = prev,
+            None => self.tail = prev,
+        }
+
+        links[slot] = IndexLinks::UNLINKED;
+        self.len = self.len.saturating_sub(1);
+        Ok(())
+    }
+}
+
+impl<I: LinkIndex> Default for RustyIndexList<I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Converts a stored link to a slot number.
+#[inline(always)]
+fn link<I: LinkIndex>(index: I) -> Option<usize> {
+    if index == I::NIL { None } else { Some(index.index()) }
+}
+
+/// Narrows `slot` to a link, checking it against the link type and the table.
+fn checked_index<I: LinkIndex>(links: &[IndexLinks<I>], slot: usize) -> Result<I, SlotOutOfRange> {
+    match I::try_from(slot) {
+        Ok(index) if index != I::NIL && slot < links.len() => Ok(index),
+        _ => Err(SlotOutOfRange { slot }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec;
+    use std::vec::Vec;
+
+    fn slots<I: LinkIndex>(list: &RustyIndexList<I>, links: &[IndexLinks<I>]) -> Vec<usize> {
+        let mut out = vec![];
+        let mut cursor = list.head();
+        while let Some(slot) = cursor {
+            out.push(slot);
+            cursor = list.next(links, slot);
+        }
+        out
+    }
+
+    #[test]
+    fn push_pop_remove_with_u8_links() {
+        let mut links = [IndexLinks::<u8>::UNLINKED; 64];
+        let mut list = RustyIndexList::<u8>::new();
+
+        for slot in [3, 10, 63] {
+            list.push(&mut links, slot).unwrap();
+        }
+        list.push_front(&mut links, 0).unwrap();
+        assert_eq!(slots(&list, &links), vec![0, 3, 10, 63]);
+        assert_eq!(list.prev(&links, 10), Some(3));
+
+        list.remove(&mut links, 10).unwrap();
+        assert_eq!(links[10], IndexLinks::UNLINKED);
+        assert_eq!(list.pop(&mut links), Some(0));
+        assert_eq!(slots(&list, &links), vec![3, 63]);
+        assert_eq!(list.tail(), Some(63));
+        assert_eq!(list.len(), 2);
+    }
+
+    #[test]
+    fn slots_outside_link_type_or_table_are_rejected() {
+        let mut links = vec![IndexLinks::<u8>::UNLINKED; 300];
+        let mut list = RustyIndexList::<u8>::new();
+
+        // 255 is the sentinel and 256 doesn't fit a u8
+        assert_eq!(list.push(&mut links, 255), Err(SlotOutOfRange { slot: 255 }));
+        assert_eq!(list.push(&mut links, 256), Err(SlotOutOfRange { slot: 256 }));
+        assert!(list.push(&mut links, 254).is_ok());
+
+        let mut small = [IndexLinks::<u16>::UNLINKED; 4];
+        let mut wide = RustyIndexList::<u16>::new();
+        assert_eq!(wide.push_front(&mut small, 4), Err(SlotOutOfRange { slot: 4 }));
+        assert!(wide.is_empty());
+    }
+
+    #[test]
+    fn lists_share_one_link_table() {
+        let mut links = [IndexLinks::<u32>::UNLINKED; 8];
+        let mut free = RustyIndexList::<u32>::new();
+        let mut busy = RustyIndexList::<u32>::new();
+
+        for slot in 0..4 {
+            free.push(&mut links, slot).unwrap();
+        }
+        while let Some(slot) = free.pop(&mut links) {
+            if slot % 2 == 0 {
+                busy.push(&mut links, slot).unwrap();
+            }
+        }
+
+        assert!(free.is_empty());
+        assert_eq!(slots(&busy, &links), vec![0, 2]);
+        assert_eq!(links[1], IndexLinks::UNLINKED);
+    }
+}
@@ -0,0 +1,237 @@
+use core::fmt;
+use core::hash::Hash;
+use core::ptr::NonNull;
+use hashbrown::HashMap;
+use crate::{RustyList, HasRustyNode};
+
+/// Extracts the lookup key of an element stored in an `IndexedRustyList`.
+pub trait KeyOf<K> {
+    /// Returns the element's key. Must not change while the element is in the list;
+    /// use `IndexedRustyList::modify` to change it.
+    fn key_of(&self) -> K;
+}
+
+/// Returned by `IndexedRustyList::insert`/`push` when an element with the same key is
+/// already in the list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DuplicateKey;
+
+impl fmt::Display for DuplicateKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("IndexedRustyList already holds an element with this key")
+    }
+}
+
+/// A `RustyList` with a key → element hash index for O(1) lookups.
+///
+/// `find_equal` on a plain list walks it, which dominates once lists grow to thousands of
+/// elements. This keeps a `HashMap` from each element's `KeyOf` key to the element,
+/// updated by every operation that goes through the wrapper. Keys are unique: inserting a
+/// second element with the same key fails with `DuplicateKey`.
+///
+/// The list itself is only exposed read-only through `list()`, so the index can't go
+/// stale behind the wrapper's back.
+pub struct IndexedRustyList<T, K> {
+    list: RustyList<T>,
+    index: HashMap<K, NonNull<T>>,
+}
+
+impl<T: HasRustyNode + KeyOf<K>, K: Hash + Eq> IndexedRustyList<T, K> {
+    /// Creates an empty indexed list that appends on `insert`.
+    pub fn new() -> Self {
+        Self {
+            list: RustyList::new(),
+            index: HashMap::new(),
+        }
+    }
+
+    /// Creates an empty indexed list that keeps items sorted with `order`.
+    pub fn new_with_order(order: fn(*const T, *const T) -> i32) -> Self {
+        Self {
+            list: RustyList::new_with_order(order),
+            index: HashMap::new(),
+        }
+    }
+
+    /// The underlying list, for read-only operations.
+    pub fn list(&self) -> &RustyList<T> {
+        &self.list
+    }
+
+    /// Number of elements in the list.
+    pub fn len(&self) -> usize {
+        self.list.len
+    }
+
+    /// Returns `true` if the list holds no elements.
+    pub fn is_empty(&self) -> bool {
+        self.list.len == 0
+    }
+
+    /// Returns `true` if an element with `key` is in the list.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.index.contains_key(key)
+    }
+
+    /// Inserts `item` at its sorted position and indexes it.
+    pub fn insert(&mut self, item: &mut T) -> Result<(), DuplicateKey> {
+        self.index_item(item)?;
+        self.list.insert(item);
+        Ok(())
+    }
+
+    /// Appends `item` at the tail and indexes it.
+    pub fn push(&mut self, item: &mut T) -> Result<(), DuplicateKey> {
+        self.index_item(item)?;
+        self.list.push(item);
+        Ok(())
+    }
+
+    /// Removes `item` from the list and the index.
+    ///
+    /// Does nothing if `item` isn't the element indexed under its key.
+    pub fn remove(&mut self, item: &mut T) {
+        let key = item.key_of();
+        if self.index.get(&key) == Some(&NonNull::from(&mut *item)) {
+            self.index.remove(&key);
+            self.list.remove(item);
+        }
+    }
+
+    /// Removes and returns the head element.
+    pub fn pop(&mut self) -> Option<*mut T> {
+        let item = self.list.pop()?;
+        let key = unsafe { (*item).key_of() };
+        self.index.remove(&key);
+        Some(item)
+    }
+
+    /// Looks up the element with `key` in O(1).
+    pub fn get(&mut self, key: &K) -> Option<&mut T> {
+        self.index.get(key).map(|item| unsafe { &mut *item.as_ptr() })
+    }
+
+    /// Looks up the element with the same key as `target` in O(1).
+    ///
+    /// This is `find_equal` for lists whose `order_function` treats equal keys as equal.
+    pub fn find_equal(&mut self, target: &T) -> Option<&mut T> {
+        self.get(&target.key_of())
+    }
+
+    /// Changes `item` with `f` and moves it to its new sorted position and key.
+    ///
+    /// The element is unlinked and unindexed first, so `f` may change both its key and its
+    /// ordering. If the new key collides with another element, `item` is left out of the
+    /// list and `DuplicateKey` is returned.
+    pub fn modify(&mut self, item: &mut T, f: impl FnOnce(&mut T)) -> Result<(), DuplicateKey> {
+        self.remove(item);
+        f(item);
+        self.insert(item)
+    }
+
+    fn index_item(&mut self, item: &mut T) -> Result<(), DuplicateKey> {
+        let key = item.key_of();
+        if self.index.contains_key(&key) {
+            return Err(DuplicateKey);
+        }
+        self.index.insert(key, NonNull::from(item));
+        Ok(())
+    }
+}
+
+impl<T: HasRustyNode + KeyOf<K>, K: Hash + Eq> Default for IndexedRustyList<T, K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec;
+    use std::vec::Vec;
+    use crate::{RustyListNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    impl KeyOf<i32> for TestItem {
+        fn key_of(&self) -> i32 {
+            self.value
+        }
+    }
+
+    fn cmp(a: *const TestItem, b: *const TestItem) -> i32 {
+        unsafe { (*a).value.cmp(&(*b).value) as i32 }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn index_follows_insert_remove_and_pop() {
+        let mut indexed = IndexedRustyList::<TestItem, i32>::new_with_order(cmp);
+        let mut items: Vec<TestItem> = (0..100).rev().map(make_item).collect();
+        for item in items.iter_mut() {
+            indexed.insert(item).unwrap();
+        }
+
+        assert_eq!(indexed.len(), 100);
+        assert_eq!(indexed.get(&42).unwrap().value, 42);
+        assert!(indexed.find_equal(&make_item(7)).is_some());
+
+        indexed.remove(&mut items[0]); // value 99
+        assert!(!indexed.contains_key(&99));
+        assert_eq!(unsafe { (*indexed.pop().unwrap()).value }, 0);
+        assert!(indexed.get(&0).is_none());
+        assert_eq!(indexed.len(), 98);
+    }
+
+    #[test]
+    fn duplicate_keys_are_rejected() {
+        let mut indexed = IndexedRustyList::<TestItem, i32>::new();
+        let mut a = make_item(1);
+        let mut b = make_item(1);
+
+        indexed.push(&mut a).unwrap();
+        assert_eq!(indexed.push(&mut b), Err(DuplicateKey));
+        assert!(!b.node.is_linked());
+
+        // removing the unindexed twin leaves the original alone
+        indexed.remove(&mut b);
+        assert_eq!(indexed.len(), 1);
+    }
+
+    #[test]
+    fn modify_rekeys_and_resorts() {
+        let mut indexed = IndexedRustyList::<TestItem, i32>::new_with_order(cmp);
+        let mut a = make_item(1);
+        let mut b = make_item(2);
+        let mut c = make_item(3);
+        indexed.insert(&mut a).unwrap();
+        indexed.insert(&mut b).unwrap();
+        indexed.insert(&mut c).unwrap();
+
+        indexed.modify(&mut a, |item| item.value = 10).unwrap();
+        assert!(indexed.get(&1).is_none());
+        assert_eq!(indexed.get(&10).unwrap().value, 10);
+        assert_eq!(indexed.list().iter().map(|item| item.value).collect::<Vec<_>>(), vec![2, 3, 10]);
+
+        assert_eq!(indexed.modify(&mut b, |item| item.value = 3), Err(DuplicateKey));
+        assert_eq!(indexed.list().iter().map(|item| item.value).collect::<Vec<_>>(), vec![3, 10]);
+    }
+}
@@ -0,0 +1,188 @@
+use core::ptr::NonNull;
+use crate::{RustyList, HasRustyNode};
+
+/// An unsorted `RustyList` that caches pointers to its minimum and maximum elements.
+///
+/// Every element is appended, and `cmp` (with the `order_function` convention) is only
+/// used to keep the cached extremes current: adding an element costs at most two
+/// comparisons, and removing one only invalidates the cache if it was a cached extreme,
+/// in which case the next `min`/`max` query rescans the list once. Ties resolve like
+/// `RustyList::min_by`/`max_by`: the minimum is the first of equal elements, the maximum
+/// the last.
+pub struct MinMaxRustyList<T> {
+    list: RustyList<T>,
+    cmp: fn(*const T, *const T) -> i32,
+    min: Option<NonNull<T>>,
+    max: Option<NonNull<T>>,
+    min_stale: bool,
+    max_stale: bool,
+}
+
+impl<T: HasRustyNode> MinMaxRustyList<T> {
+    /// Creates an empty list whose extremes are tracked with `cmp`.
+    pub fn new(cmp: fn(*const T, *const T) -> i32) -> Self {
+        Self {
+            list: RustyList::new(),
+            cmp,
+            min: None,
+            max: None,
+            min_stale: false,
+            max_stale: false,
+        }
+    }
+
+    /// The underlying list, for read-only operations.
+    pub fn list(&self) -> &RustyList<T> {
+        &self.list
+    }
+
+    /// Number of elements in the list.
+    pub fn len(&self) -> usize {
+        self.list.len
+    }
+
+    /// Returns `true` if the list holds no elements.
+    pub fn is_empty(&self) -> bool {
+        self.list.len == 0
+    }
+
+    /// Appends `item` at the tail and updates the cached extremes.
+    pub fn push(&mut self, item: &mut T) {
+        self.list.push(item);
+        let ptr = NonNull::from(item);
+
+        if !self.min_stale && self.min.is_none_or(|min| (self.cmp)(ptr.as_ptr(), min.as_ptr()) < 0) {
+            self.min = Some(ptr);
+        }
+        if !self.max_stale && self.max.is_none_or(|max| (self.cmp)(ptr.as_ptr(), max.as_ptr()) >= 0) {
+            self.max = Some(ptr);
+        }
+    }
+
+    /// Removes `item` from the list, invalidating a cached extreme that points at it.
+    pub fn remove(&mut self, item: &mut T) {
+        self.list.remove(item);
+        self.forget(NonNull::from(item));
+    }
+
+    /// Removes and returns the head element.
+    pub fn pop(&mut self) -> Option<*mut T> {
+        let item = self.list.pop()?;
+        self.forget(NonNull::new(item)?);
+        Some(item)
+    }
+
+    /// The smallest element, rescanning the list only if the cached one was removed.
+    pub fn min(&mut self) -> Option<&T> {
+        if self.min_stale {
+            self.min = self.list.min_by(self.cmp).map(NonNull::from);
+            self.min_stale = false;
+        }
+        self.min.map(|min| unsafe { &*min.as_ptr() })
+    }
+
+    /// The largest element, rescanning the list only if the cached one was removed.
+    pub fn max(&mut self) -> Option<&T> {
+        if self.max_stale {
+            self.max = self.list.max_by(self.cmp).map(NonNull::from);
+            self.max_stale = false;
+        }
+        self.max.map(|max| unsafe { &*max.as_ptr() })
+    }
+
+    fn forget(&mut self, item: NonNull<T>) {
+        if self.list.len == 0 {
+            self.min = None;
+            self.max = None;
+            self.min_stale = false;
+            self.max_stale = false;
+            return;
+        }
+        if self.min == Some(item) {
+            self.min_stale = true;
+        }
+        if self.max == Some(item) {
+            self.max_stale = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+    use crate::{RustyListNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    std::thread_local! {
+        static CALLS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    fn cmp(a: *const TestItem, b: *const TestItem) -> i32 {
+        CALLS.with(|calls| calls.set(calls.get() + 1));
+        unsafe { (*a).value.cmp(&(*b).value) as i32 }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn extremes_are_cached_and_refreshed_lazily() {
+        let mut list = MinMaxRustyList::<TestItem>::new(cmp);
+        let mut items = [make_item(5), make_item(1), make_item(9), make_item(3)];
+        for item in items.iter_mut() {
+            list.push(item);
+        }
+
+        CALLS.with(|calls| calls.set(0));
+        assert_eq!(list.min().unwrap().value, 1);
+        assert_eq!(list.max().unwrap().value, 9);
+        assert_eq!(CALLS.with(Cell::get), 0);
+
+        // removing a non-extreme keeps the cache
+        list.remove(&mut items[3]);
+        assert_eq!(list.max().unwrap().value, 9);
+        assert_eq!(CALLS.with(Cell::get), 0);
+
+        // removing the max forces one rescan
+        list.remove(&mut items[2]);
+        assert_eq!(list.max().unwrap().value, 5);
+        assert!(CALLS.with(Cell::get) > 0);
+
+        assert_eq!(unsafe { (*list.pop().unwrap()).value }, 5);
+        assert_eq!(list.max().unwrap().value, 1);
+        assert_eq!(list.min().unwrap().value, 1);
+
+        list.pop();
+        assert!(list.min().is_none());
+        assert!(list.max().is_none());
+    }
+
+    #[test]
+    fn ties_match_min_by_and_max_by() {
+        let mut list = MinMaxRustyList::<TestItem>::new(cmp);
+        let mut a = make_item(2);
+        let mut b = make_item(2);
+        list.push(&mut a);
+        list.push(&mut b);
+
+        assert!(core::ptr::eq(list.min().unwrap(), &a));
+        assert!(core::ptr::eq(list.max().unwrap(), &b));
+    }
+}
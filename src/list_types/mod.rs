@@ -0,0 +1,21 @@
+pub mod cell;
+pub mod array_list;
+pub mod index_list;
+pub mod dma_ring;
+pub mod counting;
+pub mod min_max;
+pub mod bounded;
+pub mod padded;
+pub mod deadline;
+pub mod priority;
+pub mod adaptive;
+#[cfg(target_has_atomic = "ptr")]
+pub mod steal_deque;
+#[cfg(feature = "hashbrown")]
+pub mod indexed;
+#[cfg(feature = "std")]
+pub mod shared;
+#[cfg(feature = "async")]
+pub mod stream;
+#[cfg(feature = "critical-section")]
+pub mod static_list;
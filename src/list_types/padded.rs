@@ -0,0 +1,127 @@
+use core::ops::{Deref, DerefMut};
+use crate::RustyList;
+
+/// Aligns and pads a value to a cache line so neighbours in an array never share one.
+///
+/// Meant for arrays of per-core lists: unpadded list heads sit next to each other, and a
+/// core pushing to its own list invalidates the line holding its neighbour's head too.
+/// The alignment is 128 bytes on x86_64, aarch64 and powerpc64 (where adjacent lines are
+/// prefetched in pairs), 32 on 32-bit arm, mips and riscv, and 64 elsewhere. AVR and
+/// MSP430 have no data cache, so there it adds no padding at all.
+#[cfg_attr(any(target_arch = "x86_64", target_arch = "aarch64", target_arch = "powerpc64"), repr(align(128)))]
+#[cfg_attr(
+    any(target_arch = "arm", target_arch = "mips", target_arch = "riscv32", target_arch = "riscv64"),
+    repr(align(32))
+)]
+#[cfg_attr(any(target_arch = "avr", target_arch = "msp430"), repr(align(1)))]
+#[cfg_attr(
+    not(any(
+        target_arch = "avr",
+        target_arch = "msp430",
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "powerpc64",
+        target_arch = "arm",
+        target_arch = "mips",
+        target_arch = "riscv32",
+        target_arch = "riscv64",
+    )),
+    repr(align(64))
+)]
+#[derive(Debug, Default)]
+pub struct CachePadded<V> {
+    value: V,
+}
+
+/// A `RustyList` on its own cache line, for arrays of per-core lists.
+pub type PaddedList<T> = CachePadded<RustyList<T>>;
+
+impl<V> CachePadded<V> {
+    /// Pads `value` to a cache line.
+    pub const fn new(value: V) -> Self {
+        Self { value }
+    }
+
+    /// Unwraps the padded value.
+    pub fn into_inner(self) -> V {
+        self.value
+    }
+
+    /// Shared access to the padded value.
+    pub fn get(&self) -> &V {
+        &self.value
+    }
+
+    /// Exclusive access to the padded value.
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.value
+    }
+}
+
+impl<V> From<V> for CachePadded<V> {
+    fn from(value: V) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<V> Deref for CachePadded<V> {
+    type Target = V;
+
+    fn deref(&self) -> &V {
+        &self.value
+    }
+}
+
+impl<V> DerefMut for CachePadded<V> {
+    fn deref_mut(&mut self) -> &mut V {
+        &mut self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem::align_of;
+    use crate::{PaddedList, RustyList, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn per_core_lists_sit_on_separate_lines() {
+        let mut lists: [PaddedList<TestItem>; 4] = core::array::from_fn(|_| PaddedList::default());
+        let align = align_of::<PaddedList<TestItem>>();
+        assert!(align >= 32);
+
+        for pair in lists.windows(2) {
+            let a = &pair[0] as *const PaddedList<TestItem> as usize;
+            let b = &pair[1] as *const PaddedList<TestItem> as usize;
+            assert_eq!(a % align, 0);
+            assert!(b - a >= align);
+        }
+
+        let mut item = make_item(7);
+        lists[2].push(&mut item);
+        assert_eq!(lists[2].len, 1);
+        assert_eq!(lists[1].get().len, 0);
+
+        let list: RustyList<TestItem> = core::mem::take(&mut lists[2]).into_inner();
+        assert_eq!(list.len, 1);
+    }
+}
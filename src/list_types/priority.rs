@@ -0,0 +1,158 @@
+use crate::{RustyList, HasRustyNode};
+
+/// One FIFO `RustyList` per priority level, with a ready bitmap for O(1) selection.
+///
+/// The classic scheduler run queue: `priority_of` maps each element to a level in `0..P`,
+/// where a higher level is more urgent, and bit `p` of a single word is set exactly while
+/// level `p` is non-empty. `highest_ready_priority` is then one count-leading-zeros
+/// instruction and `pop_highest` never looks at an empty level, however many there are.
+/// `P` can be at most `usize::BITS`. An element's priority must not change while it is
+/// queued; remove it and push it again.
+pub struct PriorityArrayList<T, const P: usize> {
+    levels: [RustyList<T>; P],
+    priority_of: fn(*const T) -> usize,
+    ready: usize,
+}
+
+impl<T: HasRustyNode, const P: usize> PriorityArrayList<T, P> {
+    /// Creates an empty priority array.
+    pub fn new(priority_of: fn(*const T) -> usize) -> Self {
+        const { assert!(P > 0 && P <= usize::BITS as usize, "PriorityArrayList needs 1..=usize::BITS levels") };
+        Self {
+            levels: core::array::from_fn(|_| RustyList::new()),
+            priority_of,
+            ready: 0,
+        }
+    }
+}
+
+impl<T, const P: usize> PriorityArrayList<T, P> {
+    /// The highest non-empty level, or `None` if nothing is queued.
+    #[inline]
+    pub fn highest_ready_priority(&self) -> Option<usize> {
+        (self.ready != 0).then(|| (usize::BITS - 1 - self.ready.leading_zeros()) as usize)
+    }
+
+    /// The ready bitmap: bit `p` is set while level `p` holds elements.
+    pub fn ready_bitmap(&self) -> usize {
+        self.ready
+    }
+
+    /// The list of elements at level `priority`, in arrival order.
+    ///
+    /// # Panics
+    /// If `priority >= P`.
+    pub fn level(&self, priority: usize) -> &RustyList<T> {
+        &self.levels[priority]
+    }
+
+    /// Number of queued elements, over all levels.
+    pub fn len(&self) -> usize {
+        self.levels.iter().map(|level| level.len).sum()
+    }
+
+    /// Returns `true` if nothing is queued.
+    pub fn is_empty(&self) -> bool {
+        self.ready == 0
+    }
+
+    /// Appends `item` to the tail of its priority level.
+    ///
+    /// Priorities `>= P` are queued at the highest level, `P - 1`.
+    pub fn push(&mut self, item: &mut T) {
+        let priority = self.level_of(item);
+        self.levels[priority].push(item);
+        self.ready |= 1 << priority;
+    }
+
+    /// Removes and returns the oldest element of the highest non-empty level.
+    pub fn pop_highest(&mut self) -> Option<*mut T> {
+        let priority = self.highest_ready_priority()?;
+        let item = self.levels[priority].pop();
+        self.update_ready(priority);
+        item
+    }
+
+    /// Removes `item` from its level.
+    pub fn remove(&mut self, item: &mut T) {
+        let priority = self.level_of(item);
+        self.levels[priority].remove(item);
+        self.update_ready(priority);
+    }
+
+    fn level_of(&self, item: *const T) -> usize {
+        (self.priority_of)(item).min(P - 1)
+    }
+
+    fn update_ready(&mut self, priority: usize) {
+        if self.levels[priority].len == 0 {
+            self.ready &= !(1 << priority);
+        } else {
+            self.ready |= 1 << priority;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec;
+    use crate::{RustyListNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct Task {
+        pub id: i32,
+        pub priority: usize,
+        pub node: RustyListNode<Task>,
+    }
+
+    impl HasRustyNode for Task {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn priority_of(task: *const Task) -> usize {
+        unsafe { (*task).priority }
+    }
+
+    fn make_task(id: i32, priority: usize) -> Task {
+        Task {
+            id,
+            priority,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn pops_highest_level_first_in_fifo_order() {
+        let mut queue = PriorityArrayList::<Task, 16>::new(priority_of);
+        assert_eq!(queue.highest_ready_priority(), None);
+
+        let mut tasks = [
+            make_task(1, 3),
+            make_task(2, 15),
+            make_task(3, 3),
+            make_task(4, 0),
+            make_task(5, 100),
+        ];
+        for task in tasks.iter_mut() {
+            queue.push(task);
+        }
+        assert_eq!(queue.ready_bitmap(), 1 << 15 | 1 << 3 | 1);
+        assert_eq!(queue.len(), 5);
+
+        queue.remove(&mut tasks[1]);
+        assert_eq!(queue.highest_ready_priority(), Some(15));
+
+        let mut order = vec![];
+        while let Some(task) = queue.pop_highest() {
+            order.push(unsafe { (*task).id });
+        }
+        assert_eq!(order, [5, 1, 3, 4]);
+        assert!(queue.is_empty());
+        assert_eq!(queue.level(3).len, 0);
+        assert!(tasks.iter().all(|task| !task.node.is_linked()));
+    }
+}
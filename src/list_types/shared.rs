@@ -0,0 +1,248 @@
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+use std::vec::Vec;
+use crate::{HasRustyNode, RustyList};
+
+/// A `RustyList` behind a `std::sync::Mutex`, for sharing between threads.
+///
+/// Every access goes through the lock. A panic while the lock is held poisons the mutex,
+/// but the list operations never leave the links half-updated, so the lock is simply
+/// taken over again instead of propagating the poison.
+///
+/// The list is only `Send` and `Sync` when `T: Send`. Element types embed raw links, so
+/// they need an `unsafe impl Send` of their own asserting that their data is only touched
+/// under the lock.
+pub struct SharedRustyList<T> {
+    list: Mutex<RustyList<T>>,
+    /// Signalled by `notify` for threads blocked in `pop_wait`.
+    available: Condvar,
+    /// Consumer waiting in `PopStream::poll_next` for the list to become non-empty.
+    #[cfg(feature = "async")]
+    pub(crate) waker: Mutex<Option<core::task::Waker>>,
+}
+
+// SAFETY: the list, and through it every element, is only reached while holding the
+// mutex, so one thread at a time touches the links, the same contract as `Mutex<T>`.
+// Elements are handed to whichever thread takes the lock, so they must be `Send`; a `&T`
+// never outlives the lock (`with` and `snapshot_into_vec` scope it to the closure), so
+// `Sync` is not required of them.
+unsafe impl<T: Send> Sync for SharedRustyList<T> {}
+unsafe impl<T: Send> Send for SharedRustyList<T> {}
+
+impl<T: HasRustyNode> SharedRustyList<T> {
+    /// Creates an empty, unordered shared list.
+    pub fn new() -> Self {
+        Self::from_list(RustyList::new())
+    }
+
+    /// Creates an empty shared list that keeps `insert`ed items in `order` order.
+    pub fn new_with_order(order: fn(*const T, *const T) -> i32) -> Self {
+        Self::from_list(RustyList::new_with_order(order))
+    }
+}
+
+impl<T: HasRustyNode> Default for SharedRustyList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SharedRustyList<T> {
+    /// Wraps an existing list.
+    pub fn from_list(list: RustyList<T>) -> Self {
+        Self {
+            list: Mutex::new(list),
+            available: Condvar::new(),
+            #[cfg(feature = "async")]
+            waker: Mutex::new(None),
+        }
+    }
+
+    /// Locks the list for a series of operations.
+    pub fn lock(&self) -> MutexGuard<'_, RustyList<T>> {
+        self.list.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Runs `f` with the list locked.
+    pub fn with<R>(&self, f: impl FnOnce(&mut RustyList<T>) -> R) -> R {
+        f(&mut self.lock())
+    }
+
+    /// Appends `item` at the tail and wakes a waiting consumer.
+    pub fn push(&self, item: &mut T) {
+        self.lock().push(item);
+        self.notify();
+    }
+
+    /// Inserts `item` at its sorted position and wakes a waiting consumer.
+    pub fn insert(&self, item: &mut T) {
+        self.lock().insert(item);
+        self.notify();
+    }
+
+    /// Wakes a thread blocked in `pop_wait` and a consumer waiting on the stream.
+    ///
+    /// `push` and `insert` call this; call it yourself after adding elements through
+    /// `lock` or `with`.
+    pub fn notify(&self) {
+        self.available.notify_one();
+        #[cfg(feature = "async")]
+        if let Some(waker) = self.waker.lock().unwrap_or_else(PoisonError::into_inner).take() {
+            waker.wake();
+        }
+    }
+
+    /// Removes and returns the head element.
+    pub fn pop(&self) -> Option<*mut T> {
+        self.lock().pop()
+    }
+
+    /// Removes and returns the head element, blocking until one is available.
+    ///
+    /// With `Some(timeout)`, gives up and returns `None` once `timeout` has elapsed without
+    /// an element arriving; with `None`, waits indefinitely. Each `push`/`insert` wakes one
+    /// waiting thread, which makes the list usable as a simple multi-producer channel.
+    pub fn pop_wait(&self, timeout: Option<Duration>) -> Option<*mut T> {
+        let deadline = timeout.map(|t| Instant::now() + t);
+        let mut list = self.lock();
+
+        loop {
+            if let Some(item) = list.pop() {
+                return Some(item);
+            }
+
+            list = match deadline {
+                None => self.available.wait(list).unwrap_or_else(PoisonError::into_inner),
+                Some(deadline) => {
+                    let remaining = deadline.checked_duration_since(Instant::now())?;
+                    self.available
+                        .wait_timeout(list, remaining)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0
+                }
+            };
+        }
+    }
+
+    /// Number of elements in the list at the time of the call.
+    pub fn len(&self) -> usize {
+        self.lock().len
+    }
+
+    /// Returns `true` if the list was empty at the time of the call.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Copies a projection of every element into a `Vec`, head to tail, under the lock.
+    ///
+    /// Meant for observability endpoints that report queue contents: `project` clones just
+    /// the payload worth reporting (e.g. `|job| job.id` or `|job| job.name.clone()`), the
+    /// `Vec` is sized once up front, and the lock is held only for that single walk.
+    pub fn snapshot_into_vec<U>(&self, mut project: impl FnMut(&T) -> U) -> Vec<U> {
+        let list = self.lock();
+        let mut out = Vec::with_capacity(list.len);
+
+        list.any(|item| {
+            out.push(project(item));
+            false
+        });
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+    use crate::{RustyListNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    // SAFETY: the links are only touched under the list's lock
+    unsafe impl Send for TestItem {}
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn cmp(a: *const TestItem, b: *const TestItem) -> i32 {
+        unsafe { (*a).value.cmp(&(*b).value) as i32 }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn snapshot_projects_in_list_order() {
+        let shared = SharedRustyList::<TestItem>::new_with_order(cmp);
+        let mut items = [make_item(3), make_item(1), make_item(2)];
+        for item in items.iter_mut() {
+            shared.insert(item);
+        }
+
+        assert_eq!(shared.snapshot_into_vec(|item| item.value * 10), [10, 20, 30]);
+        assert_eq!(shared.len(), 3);
+    }
+
+    #[test]
+    fn threads_share_one_list() {
+        // leaked so the elements outlive every thread
+        let items: &'static mut [TestItem] = Vec::leak((0..8).map(make_item).collect());
+        let items_addr = items.as_mut_ptr() as usize;
+        let shared = Arc::new(SharedRustyList::<TestItem>::new());
+
+        let producers: Vec<_> = (0..2)
+            .map(|half| {
+                let shared = Arc::clone(&shared);
+                thread::spawn(move || {
+                    let items = items_addr as *mut TestItem;
+                    for i in (half * 4)..(half * 4 + 4) {
+                        shared.push(unsafe { &mut *items.add(i) });
+                    }
+                })
+            })
+            .collect();
+        for producer in producers {
+            producer.join().unwrap();
+        }
+
+        let mut values = shared.snapshot_into_vec(|item| item.value);
+        values.sort();
+        assert_eq!(values, (0..8).collect::<Vec<_>>());
+        assert!(shared.with(|list| list.pop()).is_some());
+        assert_eq!(shared.len(), 7);
+    }
+
+    #[test]
+    fn pop_wait_blocks_until_push_or_timeout() {
+        let shared = Arc::new(SharedRustyList::<TestItem>::new());
+        assert!(shared.pop_wait(Some(Duration::from_millis(10))).is_none());
+
+        let item: &'static mut TestItem = std::boxed::Box::leak(std::boxed::Box::new(make_item(7)));
+        let item_addr = item as *mut TestItem as usize;
+
+        let consumer = {
+            let shared = Arc::clone(&shared);
+            thread::spawn(move || shared.pop_wait(None).map(|p| p as usize))
+        };
+        thread::sleep(Duration::from_millis(20));
+        shared.push(unsafe { &mut *(item_addr as *mut TestItem) });
+
+        assert_eq!(consumer.join().unwrap(), Some(item_addr));
+        assert!(shared.is_empty());
+    }
+}
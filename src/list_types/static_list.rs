@@ -0,0 +1,189 @@
+use core::cell::{Cell, UnsafeCell};
+use crate::{HasRustyNode, RustyList};
+
+/// A `RustyList` that can live in a `static` and be shared between ISRs and thread context.
+///
+/// On single-core MCUs the only sound way to touch a list shared with an interrupt
+/// handler is inside a critical section. `with` packages that pattern: it enters a
+/// critical section (via the `critical-section` crate), hands out `&mut RustyList<T>` and
+/// guarantees the section is left again, even if the closure panics.
+///
+/// The list itself is created lazily on first use, so `new`/`new_with_order` are `const`
+/// and a table of lists can be a `static` array initialized from `EMPTY`.
+///
+/// A `static` needs `Sync`, which this type only is when `T: Send`. Element types embed raw
+/// links, so they need an `unsafe impl Send` of their own asserting that their data is only
+/// touched inside `with`.
+pub struct StaticRustyList<T> {
+    order_function: Option<fn(*const T, *const T) -> i32>,
+    /// Set while a `with` call holds the list. Kept outside `list` so it can be checked
+    /// before any reference into the list exists.
+    in_use: Cell<bool>,
+    list: UnsafeCell<Option<RustyList<T>>>,
+}
+
+// SAFETY: the list is only ever accessed inside a critical section, and `with` rejects
+// re-entry, so at most one `&mut RustyList<T>` exists at a time. Any thread or interrupt
+// context can be the one calling `with`, so the elements must be `Send`; element types
+// embed raw links and need their own `unsafe impl Send` to opt in.
+unsafe impl<T: Send> Sync for StaticRustyList<T> {}
+unsafe impl<T: Send> Send for StaticRustyList<T> {}
+
+/// Clears the in-use flag when `with` returns or unwinds.
+struct InUseGuard<'a>(&'a Cell<bool>);
+
+impl Drop for InUseGuard<'_> {
+    fn drop(&mut self) {
+        self.0.set(false);
+    }
+}
+
+impl<T> StaticRustyList<T> {
+    /// An empty, unordered static list, for initializing arrays of lists:
+    ///
+    /// ```ignore
+    /// static QUEUES: [StaticRustyList<Task>; 32] = [StaticRustyList::EMPTY; 32];
+    /// ```
+    ///
+    /// `RustyList` itself has no such constant, because its node offset comes from
+    /// `HasRustyNode::rusty_offset`, which can't be called in a const context.
+    // every use is meant to be a fresh list, which is exactly what a const gives
+    #[allow(clippy::declare_interior_mutable_const)]
+    pub const EMPTY: Self = Self {
+        order_function: None,
+        in_use: Cell::new(false),
+        list: UnsafeCell::new(None),
+    };
+
+    /// Creates an empty, unordered static list.
+    pub const fn new() -> Self {
+        Self::EMPTY
+    }
+
+    /// Creates an empty static list that keeps `insert`ed items in `order` order.
+    pub const fn new_with_order(order: fn(*const T, *const T) -> i32) -> Self {
+        Self {
+            order_function: Some(order),
+            in_use: Cell::new(false),
+            list: UnsafeCell::new(None),
+        }
+    }
+}
+
+impl<T: HasRustyNode> StaticRustyList<T> {
+    /// Runs `f` with exclusive access to the list inside a critical section.
+    ///
+    /// # Panics
+    /// Panics if called again from inside `f` for the same list (e.g. an ISR firing in the
+    /// middle of a `with` on a platform whose critical section doesn't mask it).
+    pub fn with<R>(&self, f: impl FnOnce(&mut RustyList<T>) -> R) -> R {
+        critical_section::with(|_cs| {
+            if self.in_use.replace(true) {
+                panic!("StaticRustyList::with called re-entrantly");
+            }
+            let _guard = InUseGuard(&self.in_use);
+
+            // SAFETY: we are inside a critical section and `in_use` was clear, so no other
+            // reference into `list` exists until the guard is dropped
+            let slot = unsafe { &mut *self.list.get() };
+            let order = self.order_function;
+            let list = slot.get_or_insert_with(|| match order {
+                Some(order) => RustyList::new_with_order(order),
+                None => RustyList::new(),
+            });
+
+            f(list)
+        })
+    }
+}
+
+impl<T> Default for StaticRustyList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RustyListNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    // SAFETY: the links are only touched inside `with`
+    unsafe impl Send for TestItem {}
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn cmp(a: *const TestItem, b: *const TestItem) -> i32 {
+        unsafe { (*a).value.cmp(&(*b).value) as i32 }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    static QUEUE: StaticRustyList<TestItem> = StaticRustyList::new_with_order(cmp);
+    static QUEUES: [StaticRustyList<TestItem>; 4] = [StaticRustyList::EMPTY; 4];
+
+    #[test]
+    fn with_gives_exclusive_access_to_a_static_list() {
+        let mut a = make_item(2);
+        let mut b = make_item(1);
+
+        QUEUE.with(|list| {
+            list.insert(&mut a);
+            list.insert(&mut b);
+        });
+
+        let first = QUEUE.with(|list| list.pop()).unwrap();
+        assert_eq!(unsafe { (*first).value }, 1);
+        assert_eq!(QUEUE.with(|list| list.len), 1);
+        QUEUE.with(|list| list.pop());
+    }
+
+    #[test]
+    fn array_of_empty_lists_is_independent() {
+        let mut a = make_item(1);
+        let mut b = make_item(2);
+
+        QUEUES[1].with(|list| list.push(&mut a));
+        QUEUES[3].with(|list| list.push(&mut b));
+
+        let lens: [usize; 4] = core::array::from_fn(|i| QUEUES[i].with(|list| list.len));
+        assert_eq!(lens, [0, 1, 0, 1]);
+        let popped = QUEUES[3].with(|list| list.pop()).unwrap();
+        assert_eq!(unsafe { (*popped).value }, 2);
+        QUEUES[1].with(|list| list.pop());
+    }
+
+    #[test]
+    #[should_panic(expected = "re-entrantly")]
+    fn nested_with_panics() {
+        let list = StaticRustyList::<TestItem>::new();
+        list.with(|_| list.with(|_| ()));
+    }
+
+    #[test]
+    fn in_use_is_cleared_after_panic() {
+        let list = StaticRustyList::<TestItem>::new();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            list.with(|_| panic!("boom"));
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(list.with(|l| l.len), 0);
+    }
+}
@@ -0,0 +1,328 @@
+use core::cell::UnsafeCell;
+use core::hint::spin_loop;
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+use core::sync::atomic::{AtomicBool, AtomicIsize, Ordering};
+use crate::{HasRustyNode, RustyListNode, rusty_container_of_mut};
+
+type Link<T> = NonNull<RustyListNode<T>>;
+
+/// An intrusive work-stealing deque: the owner works LIFO at the tail, thieves take FIFO
+/// from the head.
+///
+/// One worker at a time owns the deque through a `StealWorker`, whose `push` and `pop`
+/// take no lock on the fast path: a pop costs one store and one load of the shared
+/// indices. Other threads call `steal`, which takes a spinlock among thieves and claims
+/// the head with the same store-then-load handshake, so owner and thieves only meet on the
+/// lock when the deque is down to its last element. Thieves never take that last element;
+/// it stays with the owner.
+///
+/// Elements embed a `RustyListNode<T>` like in a `RustyList` and must stay valid while they
+/// are in the deque. The deque is only `Send` and `Sync` when `T: Send`, so element types
+/// need an `unsafe impl Send` of their own.
+pub struct RustyStealDeque<T> {
+    offset: usize,
+    /// One past the logical index of the tail element. Only the owner writes it.
+    bottom: AtomicIsize,
+    /// Logical index of the head element. Only written with `lock` held.
+    top: AtomicIsize,
+    /// Serializes thieves, and the owner when it takes the last element.
+    lock: AtomicBool,
+    /// Set while a `StealWorker` exists.
+    owned: AtomicBool,
+    head: UnsafeCell<Option<Link<T>>>,
+    tail: UnsafeCell<Option<Link<T>>>,
+}
+
+// SAFETY: `head` is only touched with `lock` held, except by the owner while the deque is
+// empty, when no thief reaches it. `tail` and the links of the last element are only
+// touched by the owner. The `top`/`bottom` handshake keeps a thief at least one element
+// away from the owner's end, so the two sides never write the same link. Thieves take
+// elements pushed on the owner's thread, so the elements must be `Send`.
+unsafe impl<T: Send> Sync for RustyStealDeque<T> {}
+unsafe impl<T: Send> Send for RustyStealDeque<T> {}
+
+/// The owner's handle to a `RustyStealDeque`, returned by `worker`.
+///
+/// The handle is `!Send`, so the fast-path operations stay on the thread that took it.
+/// Dropping it lets another thread take ownership.
+pub struct StealWorker<'a, T> {
+    deque: &'a RustyStealDeque<T>,
+    _not_send: PhantomData<*mut ()>,
+}
+
+/// Releases the spinlock when dropped.
+struct LockGuard<'a>(&'a AtomicBool);
+
+impl Drop for LockGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+impl<T: HasRustyNode> RustyStealDeque<T> {
+    /// Creates an empty deque.
+    pub fn new() -> Self {
+        Self {
+            offset: T::rusty_offset(),
+            bottom: AtomicIsize::new(0),
+            top: AtomicIsize::new(0),
+            lock: AtomicBool::new(false),
+            owned: AtomicBool::new(false),
+            head: UnsafeCell::new(None),
+            tail: UnsafeCell::new(None),
+        }
+    }
+}
+
+impl<T: HasRustyNode> Default for RustyStealDeque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> RustyStealDeque<T> {
+    /// Takes ownership of the deque, or returns `None` if a `StealWorker` already exists.
+    pub fn worker(&self) -> Option<StealWorker<'_, T>> {
+        self.owned
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .ok()?;
+        Some(StealWorker { deque: self, _not_send: PhantomData })
+    }
+
+    /// Takes the head element, or returns `None` if fewer than two elements are queued.
+    pub fn steal(&self) -> Option<*mut T> {
+        let _lock = self.acquire();
+        let t = self.top.load(Ordering::Relaxed);
+        self.top.store(t + 1, Ordering::SeqCst);
+        let b = self.bottom.load(Ordering::SeqCst);
+
+        // the claimed element must not be the owner's last one: the owner only checks
+        // `top` after claiming its element, so leave it at least one
+        if t + 1 >= b {
+            self.top.store(t, Ordering::SeqCst);
+            return None;
+        }
+
+        unsafe {
+            let node = (*self.head.get())?;
+            let next = (*node.as_ptr()).next?;
+            *self.head.get() = Some(next);
+            (*next.as_ptr()).prev = None;
+            (*node.as_ptr()).clear_links();
+            Some(rusty_container_of_mut(node.as_ptr(), self.offset))
+        }
+    }
+
+    /// Number of queued elements at the time of the call.
+    pub fn len(&self) -> usize {
+        let b = self.bottom.load(Ordering::SeqCst);
+        let t = self.top.load(Ordering::SeqCst);
+        (b - t).max(0) as usize
+    }
+
+    /// Returns `true` if the deque was empty at the time of the call.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Spins until the lock is free and takes it.
+    fn acquire(&self) -> LockGuard<'_> {
+        while self
+            .lock
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            spin_loop();
+        }
+        LockGuard(&self.lock)
+    }
+}
+
+impl<T> StealWorker<'_, T> {
+    /// Appends `item` at the owner's end.
+    pub fn push(&mut self, item: &mut T) {
+        let deque = self.deque;
+        let node = unsafe { (item as *mut T as *mut u8).add(deque.offset) } as *mut RustyListNode<T>;
+        let new_node = unsafe { NonNull::new_unchecked(node) };
+
+        unsafe {
+            (*node).clear_links();
+            (*node).linked = true;
+            match *deque.tail.get() {
+                // thieves leave the last element alone, so the old tail's `next` is ours
+                Some(tail) => {
+                    (*node).prev = Some(tail);
+                    (*tail.as_ptr()).next = Some(new_node);
+                }
+                // empty: no thief looks at `head` until a second element is published
+                None => *deque.head.get() = Some(new_node),
+            }
+            *deque.tail.get() = Some(new_node);
+        }
+
+        // publishes the links to thieves
+        let b = deque.bottom.load(Ordering::Relaxed);
+        deque.bottom.store(b + 1, Ordering::Release);
+    }
+
+    /// Takes the most recently pushed element.
+    pub fn pop(&mut self) -> Option<*mut T> {
+        let deque = self.deque;
+        let b = deque.bottom.load(Ordering::Relaxed) - 1;
+        deque.bottom.store(b, Ordering::SeqCst);
+        let t = deque.top.load(Ordering::SeqCst);
+
+        if t < b {
+            // at least one element stays in front of ours, and thieves can't reach past it
+            return unsafe { Some(self.unlink_tail()) };
+        }
+
+        // the deque is empty or this is its last element: settle it with thieves locked out.
+        // `top` may have dropped back meanwhile, from a thief undoing its claim.
+        let _lock = deque.acquire();
+        let t = deque.top.load(Ordering::Relaxed);
+        if t > b {
+            deque.bottom.store(b + 1, Ordering::SeqCst);
+            return None;
+        }
+
+        unsafe {
+            let item = self.unlink_tail();
+            if t == b {
+                *deque.head.get() = None;
+            }
+            Some(item)
+        }
+    }
+
+    /// Number of queued elements, including any a thief is about to take.
+    pub fn len(&self) -> usize {
+        self.deque.len()
+    }
+
+    /// Returns `true` if nothing is queued.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Unlinks the tail node. The deque must not be empty.
+    unsafe fn unlink_tail(&mut self) -> *mut T {
+        let deque = self.deque;
+        unsafe {
+            let node = (*deque.tail.get()).unwrap_unchecked();
+            let prev = (*node.as_ptr()).prev;
+            if let Some(prev) = prev {
+                (*prev.as_ptr()).next = None;
+            }
+            *deque.tail.get() = prev;
+            (*node.as_ptr()).clear_links();
+            rusty_container_of_mut(node.as_ptr(), deque.offset)
+        }
+    }
+}
+
+impl<T> Drop for StealWorker<'_, T> {
+    fn drop(&mut self) {
+        self.deque.owned.store(false, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::vec::Vec;
+    use crate::{RustyStealDeque, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    // SAFETY: an element is only touched by the thread that pushed or stole it
+    unsafe impl Send for TestItem {}
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    #[test]
+    fn owner_pops_lifo_and_thieves_steal_fifo() {
+        let deque = RustyStealDeque::<TestItem>::new();
+        let mut worker = deque.worker().unwrap();
+        assert!(deque.worker().is_none());
+
+        let mut items = [make_item(1), make_item(2), make_item(3)];
+        for item in items.iter_mut() {
+            worker.push(item);
+        }
+        assert_eq!(deque.len(), 3);
+
+        assert_eq!(deque.steal().map(|p| unsafe { (*p).value }), Some(1));
+        assert_eq!(worker.pop().map(|p| unsafe { (*p).value }), Some(3));
+        // the last element is left to the owner
+        assert!(deque.steal().is_none());
+        assert_eq!(worker.pop().map(|p| unsafe { (*p).value }), Some(2));
+        assert!(worker.pop().is_none());
+        assert!(deque.is_empty());
+        assert!(items.iter().all(|item| !item.node.is_linked()));
+
+        drop(worker);
+        assert!(deque.worker().is_some());
+    }
+
+    #[test]
+    fn concurrent_steals_take_each_element_once() {
+        const COUNT: usize = 2000;
+        let items: &'static mut [TestItem] = Vec::leak((0..COUNT as i32).map(make_item).collect());
+        let items_addr = items.as_mut_ptr() as usize;
+        let deque: &'static RustyStealDeque<TestItem> = std::boxed::Box::leak(std::boxed::Box::default());
+        let taken: &'static [AtomicUsize] = Vec::leak((0..COUNT).map(|_| AtomicUsize::new(0)).collect());
+        let done = &*std::boxed::Box::leak(std::boxed::Box::new(std::sync::atomic::AtomicBool::new(false)));
+
+        let thieves: Vec<_> = (0..3)
+            .map(|_| {
+                thread::spawn(move || {
+                    while !done.load(Ordering::Acquire) {
+                        if let Some(item) = deque.steal() {
+                            taken[unsafe { (*item).value } as usize].fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        let mut worker = deque.worker().unwrap();
+        for i in 0..COUNT {
+            worker.push(unsafe { &mut *(items_addr as *mut TestItem).add(i) });
+            if i % 3 == 0
+                && let Some(item) = worker.pop()
+            {
+                taken[unsafe { (*item).value } as usize].fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        while let Some(item) = worker.pop() {
+            taken[unsafe { (*item).value } as usize].fetch_add(1, Ordering::Relaxed);
+        }
+
+        done.store(true, Ordering::Release);
+        for thief in thieves {
+            thief.join().unwrap();
+        }
+
+        assert!(taken.iter().all(|count| count.load(Ordering::Relaxed) == 1));
+        assert!(deque.is_empty());
+    }
+}
@@ -0,0 +1,106 @@
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use std::sync::PoisonError;
+use futures_core::Stream;
+use crate::SharedRustyList;
+
+/// A `Stream` of the elements popped from a `SharedRustyList`, returned by `stream`.
+///
+/// When the list is empty the consumer's waker is registered and the next `push`,
+/// `insert` or `notify` wakes it. Only one consumer is woken per notification, so use one
+/// stream per list. The stream never ends on its own.
+pub struct PopStream<'a, T> {
+    list: &'a SharedRustyList<T>,
+}
+
+impl<T> SharedRustyList<T> {
+    /// Returns a `Stream` that pops elements from the head as they arrive.
+    pub fn stream(&self) -> PopStream<'_, T> {
+        PopStream { list: self }
+    }
+}
+
+impl<T> Stream for PopStream<'_, T> {
+    type Item = *mut T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<*mut T>> {
+        if let Some(item) = self.list.pop() {
+            return Poll::Ready(Some(item));
+        }
+
+        *self.list.waker.lock().unwrap_or_else(PoisonError::into_inner) = Some(cx.waker().clone());
+
+        // an element pushed between the first pop and registering the waker would not have
+        // woken us, so look once more
+        match self.list.pop() {
+            Some(item) => Poll::Ready(Some(item)),
+            None => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::pin::Pin;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use core::task::{Context, Poll, Waker};
+    use std::sync::Arc;
+    use std::task::Wake;
+    use futures_core::Stream;
+    use crate::{SharedRustyList, RustyListNode, HasRustyNode, rusty_offset};
+
+    #[repr(C)]
+    #[derive(Debug)]
+    struct TestItem {
+        pub value: i32,
+        pub node: RustyListNode<TestItem>,
+    }
+
+    impl HasRustyNode for TestItem {
+        fn rusty_offset() -> usize {
+            rusty_offset(|x: &Self| &x.node)
+        }
+    }
+
+    fn make_item(val: i32) -> TestItem {
+        TestItem {
+            value: val,
+            node: RustyListNode::new(),
+        }
+    }
+
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn stream_waits_for_push() {
+        let shared = SharedRustyList::<TestItem>::new();
+        let wakes = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let waker = Waker::from(Arc::clone(&wakes));
+        let mut cx = Context::from_waker(&waker);
+        let mut stream = shared.stream();
+
+        assert!(Pin::new(&mut stream).poll_next(&mut cx).is_pending());
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 0);
+
+        let mut a = make_item(1);
+        let mut b = make_item(2);
+        shared.push(&mut a);
+        shared.push(&mut b);
+        // the waker is consumed by the first push
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 1);
+
+        for expected in [1, 2] {
+            match Pin::new(&mut stream).poll_next(&mut cx) {
+                Poll::Ready(Some(item)) => assert_eq!(unsafe { (*item).value }, expected),
+                other => panic!("expected an element, got {other:?}"),
+            }
+        }
+        assert!(Pin::new(&mut stream).poll_next(&mut cx).is_pending());
+    }
+}
@@ -0,0 +1,163 @@
+// verification.rs
+// Bounded Kani proof harnesses for the core operations (feature `verify`).
+//
+// Run with `cargo kani --features verify`. Every harness works on a small array of
+// elements with nondeterministic keys and checks, after each operation, that:
+// - `prev`/`next` links are symmetric and `head`/`tail` match the ends of the chain,
+// - `len` equals the number of reachable nodes,
+// - no node is leaked: exactly the reachable nodes are marked linked,
+// - lists with an `order_function` stay sorted.
+use crate::{RustyList, RustyListNode, HasRustyNode, rusty_offset};
+
+/// Number of elements per harness; loops are unwound one past this.
+const N: usize = 3;
+
+#[repr(C)]
+struct Item {
+    key: u8,
+    node: RustyListNode<Item>,
+}
+
+impl HasRustyNode for Item {
+    fn rusty_offset() -> usize {
+        rusty_offset(|x: &Self| &x.node)
+    }
+}
+
+fn cmp(a: *const Item, b: *const Item) -> i32 {
+    unsafe { (*a).key.cmp(&(*b).key) as i32 }
+}
+
+fn any_items() -> [Item; N] {
+    core::array::from_fn(|_| Item {
+        key: kani::any(),
+        node: RustyListNode::new(),
+    })
+}
+
+fn any_index() -> usize {
+    let index: usize = kani::any();
+    kani::assume(index < N);
+    index
+}
+
+/// Checks link symmetry, `len`, and that exactly the reachable items are marked linked.
+fn check_invariants(list: &RustyList<Item>, items: &[Item; N]) {
+    let mut count = 0;
+    let mut prev = None;
+    let mut cursor = list.head;
+
+    while let Some(node) = cursor {
+        assert!(count < N, "the chain has a cycle");
+        let n = unsafe { &*node.as_ptr() };
+        assert!(n.prev == prev, "prev link is not symmetric");
+        assert!(n.linked, "reachable node is not marked linked");
+
+        prev = Some(node);
+        cursor = n.next;
+        count += 1;
+    }
+
+    assert!(list.tail == prev, "tail is not the end of the chain");
+    assert!(list.len == count, "len does not match the chain");
+
+    let marked = items.iter().filter(|item| item.node.linked).count();
+    assert!(marked == count, "a linked node is not reachable");
+}
+
+/// Checks that every adjacent pair is in `order_function` order.
+fn check_sorted(list: &RustyList<Item>) {
+    let mut cursor = list.head;
+    while let Some(node) = cursor {
+        let next = unsafe { (*node.as_ptr()).next };
+        if let Some(nx) = next {
+            assert!(cmp(list.item_of(node), list.item_of(nx)) <= 0, "list is out of order");
+        }
+        cursor = next;
+    }
+}
+
+#[kani::proof]
+#[kani::unwind(5)]
+fn insert_keeps_links_and_order() {
+    let mut items = any_items();
+    let mut list = RustyList::<Item>::new_with_order(cmp);
+
+    for item in items.iter_mut() {
+        list.insert(item);
+    }
+
+    check_invariants(&list, &items);
+    check_sorted(&list);
+}
+
+#[kani::proof]
+#[kani::unwind(5)]
+fn remove_unlinks_exactly_one() {
+    let mut items = any_items();
+    let mut list = RustyList::<Item>::new_with_order(cmp);
+
+    for item in items.iter_mut() {
+        list.insert(item);
+    }
+
+    let victim = any_index();
+    list.remove(&mut items[victim]);
+    assert!(!items[victim].node.linked);
+    check_invariants(&list, &items);
+    check_sorted(&list);
+
+    // removing it again is a no-op
+    list.remove(&mut items[victim]);
+    assert!(list.len == N - 1);
+    check_invariants(&list, &items);
+}
+
+#[kani::proof]
+#[kani::unwind(5)]
+fn pop_returns_in_push_order() {
+    let mut items = any_items();
+    let mut list = RustyList::<Item>::new();
+
+    for item in items.iter_mut() {
+        list.push(item);
+    }
+
+    let pops: usize = kani::any();
+    kani::assume(pops <= N + 1);
+
+    for i in 0..pops {
+        let popped = list.pop();
+        if i < N {
+            assert!(popped == Some(&mut items[i] as *mut Item));
+        } else {
+            assert!(popped.is_none());
+        }
+    }
+
+    check_invariants(&list, &items);
+}
+
+#[kani::proof]
+#[kani::unwind(5)]
+fn mixed_operations_keep_invariants() {
+    let mut items = any_items();
+    let mut list = RustyList::<Item>::new();
+
+    for _ in 0..N + 1 {
+        let index = any_index();
+        let op: u8 = kani::any();
+
+        match op % 4 {
+            0 if !items[index].node.linked => list.push(&mut items[index]),
+            1 if !items[index].node.linked => list.insert(&mut items[index]),
+            2 => list.remove(&mut items[index]),
+            3 => {
+                list.pop();
+            }
+            _ => {}
+        }
+
+        check_invariants(&list, &items);
+    }
+}